@@ -0,0 +1,54 @@
+//! A UTxO RPC endpoint that serves a fixed set of outputs, to try out input
+//! resolution in the block inspector without running a node.
+//!
+//! ```sh
+//! cargo run --example mock_utxorpc -- <tx hash>#<index>=<output cbor> ...
+//! ```
+//!
+//! and then resolve inputs against `http://127.0.0.1:50051`. Set `PORT` to
+//! listen elsewhere.
+
+use std::{collections::HashMap, env};
+
+use tonic::transport::Server;
+use utxorpc::spec::query::query_service_server::QueryServiceServer;
+
+#[path = "../src/rpc/mock.rs"]
+mod mock;
+
+use mock::{Input, Mock};
+
+fn parse(arg: &str) -> Option<(Input, Vec<u8>)> {
+    let (input, output) = arg.split_once('=')?;
+    let (hash, index) = input.split_once('#')?;
+
+    Some((
+        (hex::decode(hash).ok()?, index.parse().ok()?),
+        hex::decode(output).ok()?,
+    ))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut utxos = HashMap::new();
+
+    for arg in env::args().skip(1) {
+        let (input, output) = parse(&arg).ok_or(format!(
+            "expected <tx hash>#<index>=<output cbor>, got {arg}"
+        ))?;
+
+        utxos.insert(input, output);
+    }
+
+    let port = env::var("PORT").unwrap_or("50051".to_string());
+    let address = format!("127.0.0.1:{port}").parse()?;
+
+    println!("serving {} utxos on http://{address}", utxos.len());
+
+    Server::builder()
+        .add_service(QueryServiceServer::new(Mock { utxos }))
+        .serve(address)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,6 @@
+mod state;
+mod update;
+mod view;
+
+pub use state::*;
+pub use update::*;
@@ -0,0 +1,237 @@
+use pallas::{
+    codec::minicbor::{self, bytes::ByteVec},
+    ledger::addresses::{
+        byron::{AddrAttrProperty, AddrDistr, AddrType},
+        Address, ByronAddress, Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+        StakeAddress, StakePayload,
+    },
+};
+
+use crate::encoding::hrp_label;
+
+#[derive(Debug, Default)]
+pub struct State {
+    pub address: String,
+    pub parts: Vec<Part>,
+    pub warning: Option<String>,
+}
+
+/// A labelled piece of a decoded address that can be copied on its own.
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub label: String,
+    pub value: String,
+}
+
+impl Part {
+    fn new(label: impl Into<String>, value: impl ToString) -> Self {
+        Self {
+            label: label.into(),
+            value: value.to_string(),
+        }
+    }
+}
+
+/// Parse an address the same way `Address::from_str` does, but remember which
+/// textual format it was given in.
+pub fn parse(input: &str) -> Option<(&'static str, Address)> {
+    if let Ok(address) = Address::from_bech32(input) {
+        return Some(("bech32", address));
+    }
+
+    if let Ok(address) = ByronAddress::from_base58(input) {
+        return Some(("base58", address.into()));
+    }
+
+    Address::from_hex(input)
+        .ok()
+        .map(|address| ("hex", address))
+}
+
+pub fn parts(format: &str, address: &Address) -> Vec<Part> {
+    let mut parts = vec![
+        Part::new("format", format),
+        Part::new("address", address),
+        Part::new("bytes", address.to_hex()),
+        Part::new("header", format!("{:#04x}", address.to_vec()[0])),
+    ];
+
+    if let Some(prefix) = (format == "bech32")
+        .then(|| hrp_label(&address.to_string()))
+        .flatten()
+    {
+        parts.insert(1, Part::new("prefix", prefix));
+    }
+
+    match address {
+        Address::Shelley(x) => shelley(x, &mut parts),
+        Address::Stake(x) => stake(x, &mut parts),
+        Address::Byron(x) => byron(x, &mut parts),
+    }
+
+    parts
+}
+
+fn network(network: Network) -> String {
+    match network {
+        Network::Mainnet => "mainnet (1)".to_string(),
+        Network::Testnet => "testnet (0)".to_string(),
+        Network::Other(id) => format!("unknown ({id})"),
+    }
+}
+
+fn shelley(address: &ShelleyAddress, parts: &mut Vec<Part>) {
+    let kind = match address.delegation() {
+        ShelleyDelegationPart::Key(_) | ShelleyDelegationPart::Script(_) => "base",
+        ShelleyDelegationPart::Pointer(_) => "pointer",
+        ShelleyDelegationPart::Null => "enterprise",
+    };
+
+    parts.push(Part::new("network", network(address.network())));
+    parts.push(Part::new("type", format!("{kind} ({})", address.typeid())));
+
+    match address.payment() {
+        ShelleyPaymentPart::Key(hash) => parts.push(Part::new("payment key hash", hash)),
+        ShelleyPaymentPart::Script(hash) => parts.push(Part::new("payment script hash", hash)),
+    }
+
+    match address.delegation() {
+        ShelleyDelegationPart::Key(hash) => parts.push(Part::new("stake key hash", hash)),
+        ShelleyDelegationPart::Script(hash) => parts.push(Part::new("stake script hash", hash)),
+        ShelleyDelegationPart::Pointer(pointer) => {
+            parts.push(Part::new("pointer slot", pointer.slot()));
+            parts.push(Part::new("pointer tx index", pointer.tx_idx()));
+            parts.push(Part::new("pointer cert index", pointer.cert_idx()));
+        }
+        ShelleyDelegationPart::Null => {}
+    }
+
+    if let Ok(reward) = StakeAddress::try_from(address.clone()) {
+        parts.push(Part::new("reward address", Address::from(reward)));
+    }
+}
+
+fn stake(address: &StakeAddress, parts: &mut Vec<Part>) {
+    parts.push(Part::new("network", network(address.network())));
+    parts.push(Part::new("type", format!("reward ({})", address.typeid())));
+
+    match address.payload() {
+        StakePayload::Stake(hash) => parts.push(Part::new("stake key hash", hash)),
+        StakePayload::Script(hash) => parts.push(Part::new("stake script hash", hash)),
+    }
+}
+
+fn byron(address: &ByronAddress, parts: &mut Vec<Part>) {
+    parts.push(Part::new("type", format!("byron ({})", address.typeid())));
+
+    let payload = match address.decode() {
+        Ok(payload) => payload,
+        Err(e) => {
+            parts.push(Part::new("payload", format!("invalid: {e}")));
+            return;
+        }
+    };
+
+    parts.push(Part::new("root", payload.root));
+
+    let spending = match payload.addrtype {
+        AddrType::PubKey => "public key".to_string(),
+        AddrType::Script => "script".to_string(),
+        AddrType::Redeem => "redeem".to_string(),
+        AddrType::Other(x) => format!("unknown ({x})"),
+    };
+
+    parts.push(Part::new("spending data", spending));
+
+    // Addresses without a network tag are mainnet ones
+    let mut network = "mainnet".to_string();
+
+    for attribute in payload.attributes.iter() {
+        match attribute {
+            AddrAttrProperty::AddrDistr(AddrDistr::SingleKeyDistribution(id)) => {
+                parts.push(Part::new("stake distribution", format!("single key {id}")))
+            }
+            AddrAttrProperty::AddrDistr(AddrDistr::BootstrapEraDistribution) => {
+                parts.push(Part::new("stake distribution", "bootstrap era"))
+            }
+            // The derivation path is encrypted with the wallet's root key,
+            // there's nothing to show without it.
+            AddrAttrProperty::DerivationPath(path) => {
+                let path = minicbor::decode::<ByteVec>(path).unwrap_or_else(|_| path.clone());
+
+                parts.push(Part::new("derivation path", hex::encode(&path[..])))
+            }
+            AddrAttrProperty::NetworkTag(tag) => {
+                network = match minicbor::decode::<u32>(tag) {
+                    Ok(magic) => format!("protocol magic {magic}"),
+                    Err(_) => hex::encode(&tag[..]),
+                };
+            }
+        }
+    }
+
+    parts.push(Part::new("network", network));
+    parts.push(Part::new("crc32", format!("{:#010x}", address.crc)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors from CIP-19
+    const BASE: &str = "addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x";
+    const REWARD: &str = "stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw";
+    const BYRON: &str = "Ae2tdPwUPEZFRbyhz3cpfC2CumGzNkFBN2L42rcUc2yjQpEkxDbkPodpMAi";
+
+    fn part<'a>(parts: &'a [Part], label: &str) -> Option<&'a str> {
+        parts
+            .iter()
+            .find(|part| part.label == label)
+            .map(|part| part.value.as_str())
+    }
+
+    #[test]
+    fn base_addresses_are_split_into_their_credentials() {
+        let (format, address) = parse(BASE).unwrap();
+        let parts = parts(format, &address);
+
+        assert_eq!(part(&parts, "format"), Some("bech32"));
+        assert_eq!(part(&parts, "prefix"), Some("addr (mainnet address)"));
+        assert_eq!(part(&parts, "network"), Some("mainnet (1)"));
+        assert_eq!(part(&parts, "type"), Some("base (0)"));
+        assert_eq!(part(&parts, "header"), Some("0x01"));
+        assert_eq!(
+            part(&parts, "payment key hash"),
+            Some("9493315cd92eb5d8c4304e67b7e16ae36d61d34502694657811a2c8e")
+        );
+        assert_eq!(
+            part(&parts, "stake key hash"),
+            Some("337b62cfff6403a06a3acbc34f8c46003c69fe79a3628cefa9c47251")
+        );
+        assert_eq!(part(&parts, "reward address"), Some(REWARD));
+    }
+
+    #[test]
+    fn the_format_is_remembered() {
+        let (_, address) = parse(BASE).unwrap();
+        let (format, from_hex) = parse(&address.to_hex()).unwrap();
+
+        assert_eq!(format, "hex");
+        assert_eq!(from_hex, address);
+        assert_eq!(part(&parts(format, &from_hex), "prefix"), None);
+
+        assert_eq!(parse(BYRON).unwrap().0, "base58");
+        assert!(parse("not an address").is_none());
+    }
+
+    #[test]
+    fn byron_addresses_show_their_payload() {
+        let (format, address) = parse(BYRON).unwrap();
+        let parts = parts(format, &address);
+
+        assert_eq!(part(&parts, "spending data"), Some("public key"));
+        assert_eq!(part(&parts, "network"), Some("mainnet"));
+        assert!(part(&parts, "root").is_some());
+        assert!(part(&parts, "crc32").is_some());
+    }
+}
@@ -0,0 +1,48 @@
+use iced::{clipboard, Task};
+
+use crate::encoding::{decode_bech32, hrp_label, looks_bech32};
+
+use super::state::{parse, parts, State};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    AddressChanged(String),
+    Copy(String),
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            AddressChanged(address) => {
+                self.address = address;
+                self.warning = None;
+                self.parts.clear();
+
+                let input = self.address.trim();
+
+                if input.is_empty() {
+                    return Task::none();
+                }
+
+                match parse(input) {
+                    Some((format, address)) => self.parts = parts(format, &address),
+                    // Point at the typo when it's bech32 that doesn't check out
+                    None if looks_bech32(input) => {
+                        self.warning = Some(match decode_bech32(input) {
+                            Ok(_) => format!(
+                                "Valid bech32, but a {} rather than an address",
+                                hrp_label(input).unwrap_or_default()
+                            ),
+                            Err(e) => e,
+                        })
+                    }
+                    None => self.warning = Some("Not a bech32, base58 or hex address".to_string()),
+                }
+
+                Task::none()
+            }
+            Copy(s) => clipboard::write(s),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use iced::{
+    padding,
+    widget::{column, row, scrollable, text, text_input},
+    Element,
+    Length::Fill,
+};
+
+use super::{Message, State};
+use crate::copyable_text::copyable_text;
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        column![
+            text_input("addr1..., stake1..., Ae2... or hex bytes", &self.address)
+                .on_input(Message::AddressChanged)
+        ]
+        .push_maybe(
+            self.warning
+                .as_ref()
+                .map(|warning| text(warning).color([1.0, 0.0, 0.0])),
+        )
+        .push(
+            scrollable(
+                column(self.parts.iter().map(|part| {
+                    row![
+                        text(&part.label).width(160),
+                        copyable_text(&part.value, Message::Copy)
+                    ]
+                    .into()
+                }))
+                .spacing(5)
+                .padding(padding::right(15)),
+            )
+            .height(Fill),
+        )
+        .spacing(10)
+        .padding(5)
+        .into()
+    }
+}
@@ -0,0 +1,71 @@
+use crate::tree::Node;
+
+use super::Block;
+
+impl Block {
+    /// Everything about the block except its transactions, which the view
+    /// lists separately so they can be opened one at a time.
+    pub fn header_tree(&self) -> Node {
+        let header = &self.header;
+
+        let era = if self.epoch_boundary {
+            "Byron (epoch boundary)".to_string()
+        } else {
+            format!("{:?}", self.era)
+        };
+
+        Node::new("Block")
+            .value(era)
+            .push(Node::leaf("size", format!("{} bytes", self.size)))
+            .push(
+                Node::new("header")
+                    .push(Node::leaf("hash", header.hash))
+                    .push(Node::leaf("slot", header.slot))
+                    .push(Node::leaf("height", header.height))
+                    .push(Node::leaf(
+                        "previous hash",
+                        header
+                            .previous_hash
+                            .map_or("genesis".to_string(), |hash| hash.to_string()),
+                    ))
+                    .push_maybe(
+                        header
+                            .issuer_vkey
+                            .as_ref()
+                            .map(|vkey| Node::leaf("issuer vkey", hex::encode(vkey))),
+                    )
+                    .push_maybe(
+                        header
+                            .vrf_vkey
+                            .as_ref()
+                            .map(|vkey| Node::leaf("vrf vkey", hex::encode(vkey))),
+                    )
+                    .push_maybe(header.operational_cert.as_ref().map(|cert| {
+                        Node::new("operational cert")
+                            .push(Node::leaf(
+                                "hot vkey",
+                                hex::encode(&cert.operational_cert_hot_vkey[..]),
+                            ))
+                            .push(Node::leaf(
+                                "sequence number",
+                                cert.operational_cert_sequence_number,
+                            ))
+                            .push(Node::leaf("kes period", cert.operational_cert_kes_period))
+                            .push(Node::leaf(
+                                "sigma",
+                                hex::encode(&cert.operational_cert_sigma[..]),
+                            ))
+                    }))
+                    .push_maybe(header.protocol_version.map(|(major, minor)| {
+                        Node::leaf("protocol version", format!("{major}.{minor}"))
+                    }))
+                    .push_maybe(
+                        header
+                            .body_size
+                            .map(|size| Node::leaf("body size", format!("{size} bytes"))),
+                    )
+                    .push_maybe(header.body_hash.map(|hash| Node::leaf("body hash", hash))),
+            )
+            .push(Node::leaf("transactions", self.transactions.len()))
+    }
+}
@@ -0,0 +1,250 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use pallas::{
+    codec::minicbor,
+    ledger::primitives::conway::{self, ExUnits, Language, MintedTx, Redeemer},
+};
+use uplc::{
+    ast::{Constant, FakeNamedDeBruijn, NamedDeBruijn, Program, Term},
+    machine::{cost_model::ExBudget, eval_result::EvalResult},
+    tx::{
+        self,
+        error::Error,
+        script_context::{find_script, PlutusScript, ScriptContext, TxInfoV1, TxInfoV2, TxInfoV3},
+        to_plutus_data::ToPlutusData,
+        DataLookupTable, ResolvedInput, SlotConfig,
+    },
+};
+
+use super::Utxos;
+
+/// How one redeemer's script fared when run locally.
+#[derive(Debug, Clone)]
+pub struct RedeemerEvaluation {
+    pub purpose: String,
+    pub declared: ExUnits,
+    /// What the script consumed, unless it couldn't be run at all
+    pub used: Option<ExUnits>,
+    pub logs: Vec<String>,
+    pub result: Result<(), String>,
+}
+
+impl RedeemerEvaluation {
+    pub fn over_budget(&self) -> bool {
+        self.used
+            .is_some_and(|used| used.mem > self.declared.mem || used.steps > self.declared.steps)
+    }
+}
+
+/// The protocol parameters scripts are run with, parsed from
+/// [ProtocolParams](super::ProtocolParams).
+pub struct EvalParams {
+    /// Plutus V1, V2 and V3's, the built-in ones where missing
+    pub cost_models: [Option<Vec<i64>>; 3],
+    /// The max ex-units of a tx
+    pub budget: ExBudget,
+    pub slot_config: SlotConfig,
+}
+
+/// Run the script of every redeemer in `cbor` against the resolved `utxos`.
+/// Unlike the ledger, a failing redeemer doesn't stop the others from being
+/// evaluated, so all of them can be compared with what they declared.
+pub async fn evaluate(
+    cbor: Vec<u8>,
+    utxos: Utxos,
+    params: EvalParams,
+) -> Result<Vec<RedeemerEvaluation>, String> {
+    // Scripts can keep a core busy for a while, away from the UI's executor
+    tokio::task::spawn_blocking(move || evaluate_blocking(cbor, utxos, params))
+        .await
+        .map_err(|e| format!("the evaluation didn't finish: {e}"))?
+}
+
+fn evaluate_blocking(
+    cbor: Vec<u8>,
+    utxos: Utxos,
+    params: EvalParams,
+) -> Result<Vec<RedeemerEvaluation>, String> {
+    let tx: conway::MintedTx = minicbor::decode(&cbor)
+        .map_err(|e| format!("only Babbage and Conway txs can be evaluated: {e}"))?;
+
+    let utxos: Vec<_> = utxos
+        .into_iter()
+        .filter_map(|(input, output)| {
+            Some(ResolvedInput {
+                input,
+                output: output.ok()?,
+            })
+        })
+        .collect();
+
+    let Some(redeemers) = tx.transaction_witness_set.redeemer.as_ref() else {
+        return Ok(vec![]);
+    };
+
+    let lookup_table = DataLookupTable::from_transaction(&tx, &utxos);
+
+    let evaluations = tx::iter_redeemers(redeemers)
+        .map(|(key, data, ex_units)| {
+            let redeemer = Redeemer {
+                tag: key.tag,
+                index: key.index,
+                data: data.clone(),
+                ex_units,
+            };
+
+            // Malformed script contexts make the evaluator panic rather than
+            // return an error
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                eval_redeemer(&tx, &utxos, &lookup_table, &redeemer, &params)
+            }));
+
+            let mut evaluation = RedeemerEvaluation {
+                purpose: format!("{:?} #{}", key.tag, key.index),
+                declared: ex_units,
+                used: None,
+                logs: vec![],
+                result: Ok(()),
+            };
+
+            match outcome {
+                Err(_) => {
+                    evaluation.result = Err("the evaluator crashed on this redeemer".to_string());
+                }
+                Ok(Ok((language, result))) => {
+                    evaluation.used = Some(ex_units_of(result.cost()));
+
+                    match result.result() {
+                        // V3 scripts signal success by returning unit, not
+                        // just by not failing
+                        Ok(term) if language == Language::PlutusV3 && !is_unit(&term) => {
+                            evaluation.logs = result.logs();
+                            evaluation.result = Err(format!(
+                                "a Plutus V3 script has to return unit, not {}",
+                                term.to_pretty()
+                            ));
+                        }
+                        Ok(_) => evaluation.logs = result.logs(),
+                        Err(e) => {
+                            evaluation.logs =
+                                result.traces().iter().map(ToString::to_string).collect();
+                            evaluation.result = Err(e.to_string());
+                        }
+                    }
+                }
+                Ok(Err(e)) => evaluation.result = Err(e.to_string()),
+            }
+
+            evaluation
+        })
+        .collect();
+
+    Ok(evaluations)
+}
+
+/// What uplc's own `eval_redeemer` does, except that the budget is used with
+/// the built-in cost models too, where uplc falls back to mainnet's.
+fn eval_redeemer(
+    tx: &MintedTx,
+    utxos: &[ResolvedInput],
+    lookup_table: &DataLookupTable,
+    redeemer: &Redeemer,
+    params: &EvalParams,
+) -> Result<(Language, EvalResult), Error> {
+    let (script, datum) = find_script(redeemer, tx, utxos, lookup_table)?;
+    let slot_config = &params.slot_config;
+
+    let (language, tx_info, script) = match script {
+        PlutusScript::V1(script) => (
+            Language::PlutusV1,
+            TxInfoV1::from_transaction(tx, utxos, slot_config)?,
+            script.0,
+        ),
+        PlutusScript::V2(script) => (
+            Language::PlutusV2,
+            TxInfoV2::from_transaction(tx, utxos, slot_config)?,
+            script.0,
+        ),
+        PlutusScript::V3(script) => (
+            Language::PlutusV3,
+            TxInfoV3::from_transaction(tx, utxos, slot_config)?,
+            script.0,
+        ),
+    };
+
+    let script_context = tx_info
+        .into_script_context(redeemer, datum.as_ref())
+        .ok_or(Error::ExtraneousRedeemer)?;
+
+    let mut buffer = vec![];
+    let program: Program<NamedDeBruijn> =
+        Program::<FakeNamedDeBruijn>::from_cbor(&script, &mut buffer)?.into();
+
+    let program = match script_context {
+        ScriptContext::V1V2 { .. } => match datum {
+            Some(datum) => program.apply_data(datum),
+            None => program,
+        }
+        .apply_data(redeemer.data.clone())
+        .apply_data(script_context.to_plutus_data()),
+        ScriptContext::V3 { .. } => program.apply_data(script_context.to_plutus_data()),
+    };
+
+    let cost_model = match language {
+        Language::PlutusV1 => &params.cost_models[0],
+        Language::PlutusV2 => &params.cost_models[1],
+        Language::PlutusV3 => &params.cost_models[2],
+    };
+
+    let result = match cost_model {
+        Some(costs) => program.eval_as(&language, costs, Some(&params.budget)),
+        None => program.eval_version(params.budget, &language),
+    };
+
+    Ok((language, result))
+}
+
+fn is_unit(term: &Term<NamedDeBruijn>) -> bool {
+    matches!(term, Term::Constant(constant) if matches!(constant.as_ref(), Constant::Unit))
+}
+
+fn ex_units_of(budget: ExBudget) -> ExUnits {
+    ExUnits {
+        mem: budget.mem.max(0) as u64,
+        steps: budget.cpu.max(0) as u64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn only_unit_is_unit() {
+        assert!(is_unit(&Term::Constant(Rc::new(Constant::Unit))));
+        assert!(!is_unit(&Term::Constant(Rc::new(Constant::Bool(true)))));
+        assert!(!is_unit(&Term::Error));
+    }
+
+    #[tokio::test]
+    async fn txs_without_redeemers_have_nothing_to_evaluate() {
+        let params = || EvalParams {
+            cost_models: [None, None, None],
+            budget: ExBudget::default(),
+            slot_config: SlotConfig::default(),
+        };
+
+        // No inputs, no outputs, no fee and no witnesses
+        let evaluations = evaluate(
+            hex::decode("84a3008001800200a0f5f6").unwrap(),
+            Utxos::new(),
+            params(),
+        );
+        assert!(evaluations.await.unwrap().is_empty());
+
+        let evaluations = evaluate(vec![0xff], Utxos::new(), params());
+        assert!(evaluations.await.is_err());
+    }
+}
@@ -1,6 +1,15 @@
+mod block_tree;
+mod evaluate;
+mod resolve;
 mod state;
+mod summary;
+mod tx_tree;
 mod update;
 mod view;
+mod witnesses;
 
+pub use evaluate::{EvalParams, RedeemerEvaluation};
 pub use state::*;
+pub use summary::*;
 pub use update::*;
+pub use witnesses::*;
@@ -0,0 +1,133 @@
+use pallas::{
+    codec::minicbor,
+    ledger::primitives::{conway, TransactionInput},
+};
+use utxorpc::CardanoQueryClient;
+
+use crate::rpc;
+
+use super::Utxos;
+
+/// Fetch the outputs spent by `inputs` from a UTxO RPC endpoint. Inputs the
+/// endpoint doesn't know about are reported individually instead of failing
+/// the whole request.
+pub async fn resolve(
+    endpoint: String,
+    api_key: String,
+    inputs: Vec<TransactionInput>,
+) -> Result<Utxos, String> {
+    let mut client: CardanoQueryClient = rpc::connect(&endpoint, &api_key).await?;
+
+    let mut utxos = Utxos::new();
+    let mut refs = vec![];
+
+    for input in inputs {
+        match rpc::txo_ref(&input) {
+            Ok(txo_ref) => {
+                refs.push(txo_ref);
+                utxos.insert(input, Err("not found".to_string()));
+            }
+            Err(e) => {
+                utxos.insert(input, Err(e));
+            }
+        }
+    }
+
+    if refs.is_empty() {
+        return Ok(utxos);
+    }
+
+    let found = client.read_utxos(refs).await.map_err(|e| rpc::error(&e))?;
+
+    for utxo in found {
+        let Some(txo_ref) = utxo.txo_ref else {
+            continue;
+        };
+
+        let Ok(hash) = <[u8; 32]>::try_from(txo_ref.hash.as_ref()) else {
+            continue;
+        };
+
+        let input = TransactionInput {
+            transaction_id: hash.into(),
+            index: txo_ref.index.into(),
+        };
+
+        let output = minicbor::decode::<conway::TransactionOutput>(&utxo.native)
+            .map_err(|e| format!("invalid output cbor: {e}"));
+
+        utxos.insert(input, output);
+    }
+
+    Ok(utxos)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use tokio::net::TcpListener;
+    use tonic::transport::{server::TcpIncoming, Server};
+    use utxorpc::spec::query::query_service_server::QueryServiceServer;
+
+    use super::*;
+    use crate::rpc::mock::Mock;
+
+    /// An enterprise address holding 1 ada
+    const OUTPUT: &str =
+        "82581d61a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d41a000f4240";
+
+    fn input(hash: u8, index: u64) -> TransactionInput {
+        TransactionInput {
+            transaction_id: [hash; 32].into(),
+            index,
+        }
+    }
+
+    async fn serve(mock: Mock) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+
+        tokio::spawn(
+            Server::builder()
+                .add_service(QueryServiceServer::new(mock))
+                .serve_with_incoming(incoming),
+        );
+
+        format!("http://{address}")
+    }
+
+    #[tokio::test]
+    async fn resolves_known_outputs_and_reports_missing_ones() {
+        let output = hex::decode(OUTPUT).unwrap();
+
+        let endpoint = serve(Mock {
+            utxos: HashMap::from([((vec![1; 32], 0), output.clone())]),
+        })
+        .await;
+
+        let known = input(1, 0);
+        let missing = input(1, 1);
+        let out_of_range = input(2, u64::from(u32::MAX) + 1);
+
+        let utxos = resolve(
+            endpoint,
+            String::new(),
+            vec![known.clone(), missing.clone(), out_of_range.clone()],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(utxos.len(), 3);
+        assert_eq!(
+            utxos[&known],
+            Ok(minicbor::decode::<conway::TransactionOutput>(&output).unwrap())
+        );
+        assert_eq!(utxos[&missing], Err("not found".to_string()));
+        assert_eq!(
+            utxos[&out_of_range],
+            Err("output index 4294967296 is out of range".to_string())
+        );
+    }
+}
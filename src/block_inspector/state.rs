@@ -1,50 +1,534 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use iced::widget::text_editor;
 use pallas::{
     codec::minicbor,
+    crypto::hash::Hash,
     ledger::{
-        primitives::{alonzo, babbage, byron, conway},
-        traverse::Era,
+        primitives::{alonzo, babbage, byron, conway, TransactionInput},
+        traverse::{self, Era, MultiEraHeader},
     },
 };
 
+use crate::{blueprint::Blueprint, tree};
+
+use super::{ProtocolParams, RedeemerEvaluation, TxSummary};
+
 #[derive(Debug, Default)]
 pub struct State {
-    pub tx_cbor: text_editor::Content,
+    pub cbor: text_editor::Content,
+    pub block: Option<Box<Block>>,
+    pub selected_tx: Option<usize>,
     pub transaction: Option<MultiEraTx>,
+    /// The transaction as shown, rebuilt only when it or the UTxOs change
+    pub tree: Option<tree::Node>,
+    pub summary: Option<Box<TxSummary>>,
+    pub error: Option<DecodeError>,
+    /// Which failed attempt to point at in the CBOR, defaults to the one that
+    /// got the furthest.
+    pub selected_attempt: Option<usize>,
+    pub collapsed: HashSet<tree::Path>,
+    /// UTxO RPC endpoint used to resolve the outputs spent by a tx
+    pub endpoint: String,
+    pub api_key: String,
+    pub resolving: bool,
+    pub utxos: Utxos,
+    pub resolve_error: Option<String>,
+    pub params: ProtocolParams,
+    pub evaluating: bool,
+    pub evaluation: Option<Result<Vec<RedeemerEvaluation>, String>>,
+    /// The last blueprint loaded anywhere, to name datum and redeemer fields
+    pub blueprint: Option<Arc<Blueprint>>,
+}
+
+/// Outputs spent by a tx, or why they couldn't be resolved.
+pub type Utxos = HashMap<TransactionInput, Result<conway::TransactionOutput, String>>;
+
+#[derive(Debug, Clone)]
+pub enum Decoded {
+    Block(Box<Block>),
+    Tx(MultiEraTx, Option<Box<TxSummary>>),
+}
+
+impl Decoded {
+    pub fn decode(cbor: &[u8]) -> Result<Self, DecodeError> {
+        // The block decoders are much stricter than the tx ones, so try them first
+        let block_error = match Block::decode(cbor) {
+            Ok(block) => return Ok(Decoded::Block(block.into())),
+            Err(e) => e,
+        };
+
+        match MultiEraTx::decode(cbor) {
+            Ok(tx) => {
+                let summary = TxSummary::new(tx.era(), cbor).map(Box::new);
+
+                Ok(Decoded::Tx(tx, summary))
+            }
+            Err(tx_error) => Err(DecodeError::Cbor {
+                cbor: cbor.to_vec(),
+                attempts: tx_error.0.into_iter().chain(block_error.0).collect(),
+            }),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum DecodeError {
+    #[error("{0}")]
+    Encoding(String),
+    #[error("{0}")]
+    TextEnvelope(String),
+    #[error("not a block or a transaction of any known era")]
+    Cbor {
+        cbor: Vec<u8>,
+        attempts: Vec<Attempt>,
+    },
+}
+
+/// Why the input didn't decode as a given era's block or tx.
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    pub label: String,
+    pub error: String,
+    pub position: Option<usize>,
+}
+
+impl Attempt {
+    fn new(label: impl Into<String>, cbor: &[u8], error: &minicbor::decode::Error) -> Self {
+        // minicbor doesn't record where it ran out of bytes, but it can only
+        // ever be at the end
+        let position = error
+            .position()
+            .or(error.is_end_of_input().then_some(cbor.len()));
+
+        Self {
+            label: label.into(),
+            error: error.to_string(),
+            position,
+        }
+    }
 }
+
+/// The attempt that made it the furthest into the input is the most likely
+/// to be the era the input was meant to be.
+pub fn furthest_attempt(attempts: &[Attempt]) -> Option<usize> {
+    attempts
+        .iter()
+        .enumerate()
+        .filter_map(|(i, attempt)| Some((i, attempt.position?)))
+        .max_by_key(|(i, position)| (*position, std::cmp::Reverse(*i)))
+        .map(|(i, _)| i)
+}
+
 #[derive(Debug, Clone)]
 pub enum MultiEraTx {
     AlonzoCompatible(Arc<alonzo::Tx>, Era),
-    Babbage(babbage::Tx),
-    Byron(byron::Tx),
-    Conway(conway::Tx),
+    Babbage(Box<babbage::Tx>),
+    /// The tx along with its witnesses, when they came with it
+    Byron(byron::Tx, Option<byron::Witnesses>),
+    Conway(Box<conway::Tx>),
 }
 
 #[derive(thiserror::Error, Debug)]
 #[error("unknown cbor")]
-pub struct DecodeTxError;
+pub struct DecodeTxError(pub Vec<Attempt>);
 
 impl MultiEraTx {
     pub fn decode(cbor: &[u8]) -> Result<Self, DecodeTxError> {
-        if let Ok(tx) = minicbor::decode(cbor) {
-            return Ok(MultiEraTx::Conway(tx));
+        let mut attempts = vec![];
+
+        for (era, label) in [
+            (Era::Conway, "Conway tx"),
+            (Era::Babbage, "Babbage tx"),
+            // Shelley/Allegra/Mary/Alonzo will all decode to Alonzo
+            (Era::Alonzo, "Shelley to Alonzo tx"),
+            (Era::Byron, "Byron tx"),
+        ] {
+            match Self::decode_for_era(era, cbor) {
+                Ok(tx) => return Ok(tx),
+                Err(e) => attempts.push(Attempt::new(label, cbor, &e)),
+            }
         }
 
-        if let Ok(tx) = minicbor::decode(cbor) {
-            return Ok(MultiEraTx::Babbage(tx));
+        Err(DecodeTxError(attempts))
+    }
+
+    pub fn era(&self) -> Era {
+        match self {
+            MultiEraTx::AlonzoCompatible(_, era) => *era,
+            MultiEraTx::Babbage(_) => Era::Babbage,
+            MultiEraTx::Byron(..) => Era::Byron,
+            MultiEraTx::Conway(_) => Era::Conway,
         }
+    }
 
-        if let Ok(tx) = minicbor::decode::<alonzo::Tx>(cbor) {
-            // Shelley/Allegra/Mary/Alonzo will all decode to Alonzo
-            return Ok(MultiEraTx::AlonzoCompatible(tx.into(), Era::Alonzo));
+    /// Every input whose output the tx needs to see: spent, referenced and
+    /// collateral ones.
+    pub fn resolvable_inputs(&self) -> Vec<TransactionInput> {
+        let mut inputs = vec![];
+
+        match self {
+            MultiEraTx::AlonzoCompatible(tx, _) => {
+                let body = &tx.transaction_body;
+
+                inputs.extend(body.inputs.iter().cloned());
+                inputs.extend(body.collateral.iter().flatten().cloned());
+            }
+            MultiEraTx::Babbage(tx) => {
+                let body = &tx.transaction_body;
+
+                inputs.extend(body.inputs.iter().cloned());
+                inputs.extend(body.collateral.iter().flatten().cloned());
+                inputs.extend(body.reference_inputs.iter().flatten().cloned());
+            }
+            MultiEraTx::Conway(tx) => {
+                let body = &tx.transaction_body;
+
+                inputs.extend(body.inputs.iter().cloned());
+                inputs.extend(body.collateral.iter().flat_map(|c| c.iter()).cloned());
+                inputs.extend(body.reference_inputs.iter().flat_map(|r| r.iter()).cloned());
+            }
+            // Byron inputs predate the UTxO RPC model
+            MultiEraTx::Byron(..) => {}
+        }
+
+        inputs.sort();
+        inputs.dedup();
+        inputs
+    }
+
+    /// Decode a tx whose era is already known, e.g. because it came out of a
+    /// block.
+    pub fn decode_for_era(era: Era, cbor: &[u8]) -> Result<Self, minicbor::decode::Error> {
+        Ok(match era {
+            // Blocks pair Byron txs with their witnesses, a pasted tx may
+            // come without them
+            Era::Byron => match minicbor::decode::<byron::TxPayload>(cbor) {
+                Ok(payload) => MultiEraTx::Byron(payload.transaction, Some(payload.witness)),
+                Err(_) => MultiEraTx::Byron(minicbor::decode(cbor)?, None),
+            },
+            Era::Shelley | Era::Allegra | Era::Mary | Era::Alonzo => {
+                MultiEraTx::AlonzoCompatible(minicbor::decode::<alonzo::Tx>(cbor)?.into(), era)
+            }
+            Era::Babbage => MultiEraTx::Babbage(minicbor::decode(cbor)?),
+            // Era is non-exhaustive, anything newer is at least Conway
+            _ => MultiEraTx::Conway(minicbor::decode(cbor)?),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub era: Era,
+    pub epoch_boundary: bool,
+    pub size: usize,
+    pub header: BlockHeader,
+    pub transactions: Vec<BlockTx>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockHeader {
+    pub slot: u64,
+    pub height: u64,
+    pub hash: Hash<32>,
+    pub previous_hash: Option<Hash<32>>,
+    pub issuer_vkey: Option<Vec<u8>>,
+    pub vrf_vkey: Option<Vec<u8>>,
+    pub operational_cert: Option<babbage::OperationalCert>,
+    pub protocol_version: Option<(u64, u64)>,
+    pub body_size: Option<u64>,
+    pub body_hash: Option<Hash<32>>,
+}
+
+/// A transaction of a decoded block, kept as its original bytes so it can be
+/// decoded on demand with [MultiEraTx::decode_for_era].
+#[derive(Debug, Clone)]
+pub struct BlockTx {
+    pub hash: Hash<32>,
+    pub era: Era,
+    pub cbor: Vec<u8>,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("unknown block cbor")]
+pub struct DecodeBlockError(pub Vec<Attempt>);
+
+// Era tags used by the node to wrap blocks, as in `[tag, block]`
+const CONWAY: u8 = 7;
+const BABBAGE: u8 = 6;
+const ALONZO: u8 = 5;
+const BYRON: u8 = 1;
+const EPOCH_BOUNDARY: u8 = 0;
+
+impl Block {
+    /// Decode a block either wrapped with its era tag, as served by the node,
+    /// or as the bare block CBOR, as served by most explorers.
+    pub fn decode(cbor: &[u8]) -> Result<Self, DecodeBlockError> {
+        if let Ok(block) = traverse::MultiEraBlock::decode(cbor) {
+            return Ok(Block::from(&block));
+        }
+
+        let mut attempts = vec![];
+
+        for (tag, label) in [
+            (CONWAY, "Conway block"),
+            (BABBAGE, "Babbage block"),
+            (ALONZO, "Shelley to Alonzo block"),
+            (BYRON, "Byron block"),
+            (EPOCH_BOUNDARY, "Byron epoch boundary block"),
+        ] {
+            // Decode the bare block first, errors from the tagged one would
+            // be off by the two bytes of the tag.
+            let bare = match tag {
+                CONWAY => minicbor::decode::<conway::MintedBlock>(cbor).map(drop),
+                BABBAGE => minicbor::decode::<babbage::MintedBlock>(cbor).map(drop),
+                ALONZO => minicbor::decode::<alonzo::MintedBlock>(cbor).map(drop),
+                BYRON => minicbor::decode::<byron::MintedBlock>(cbor).map(drop),
+                _ => minicbor::decode::<byron::MintedEbBlock>(cbor).map(drop),
+            };
+
+            if let Err(e) = bare {
+                attempts.push(Attempt::new(label, cbor, &e));
+                continue;
+            }
+
+            let tagged = with_era_tag(tag, cbor);
+
+            let Ok(block) = traverse::MultiEraBlock::decode(&tagged) else {
+                continue;
+            };
+
+            // Post-Byron blocks are mostly supersets of each other, so pick
+            // the era announced in the header when it disagrees with the
+            // first decoder that happened to accept the bytes.
+            let block = Block::from(&block);
+
+            let expected = match block.header.protocol_version {
+                Some((2, _)) => 2,
+                Some((3, _)) => 3,
+                Some((4, _)) => 4,
+                Some((5..=6, _)) => ALONZO,
+                Some((7..=8, _)) => BABBAGE,
+                _ => tag,
+            };
+
+            if expected != tag {
+                let tagged = with_era_tag(expected, cbor);
+
+                if let Ok(block) = traverse::MultiEraBlock::decode(&tagged) {
+                    return Ok(Block::from(&block));
+                }
+            }
+
+            return Ok(block);
+        }
+
+        Err(DecodeBlockError(attempts))
+    }
+}
+
+fn with_era_tag(tag: u8, cbor: &[u8]) -> Vec<u8> {
+    let mut tagged = Vec::with_capacity(cbor.len() + 2);
+    tagged.push(0x82);
+    tagged.push(tag);
+    tagged.extend_from_slice(cbor);
+    tagged
+}
+
+impl From<&traverse::MultiEraBlock<'_>> for Block {
+    fn from(block: &traverse::MultiEraBlock<'_>) -> Self {
+        let header = block.header();
+
+        let transactions = block
+            .txs()
+            .iter()
+            .map(|tx| BlockTx {
+                hash: tx.hash(),
+                era: tx.era(),
+                cbor: tx.encode(),
+            })
+            .collect();
+
+        Block {
+            era: block.era(),
+            epoch_boundary: matches!(block, traverse::MultiEraBlock::EpochBoundary(_)),
+            size: block.size(),
+            header: BlockHeader::from(&header),
+            transactions,
         }
+    }
+}
+
+impl From<&MultiEraHeader<'_>> for BlockHeader {
+    fn from(header: &MultiEraHeader<'_>) -> Self {
+        let mut summary = BlockHeader {
+            slot: header.slot(),
+            height: header.number(),
+            hash: header.hash(),
+            previous_hash: header.previous_hash(),
+            issuer_vkey: header.issuer_vkey().map(<[u8]>::to_vec),
+            vrf_vkey: header.vrf_vkey().map(<[u8]>::to_vec),
+            operational_cert: None,
+            protocol_version: None,
+            body_size: None,
+            body_hash: None,
+        };
+
+        match header {
+            MultiEraHeader::ShelleyCompatible(x) => {
+                let body = &x.header_body;
 
-        if let Ok(tx) = minicbor::decode(cbor) {
-            Ok(MultiEraTx::Byron(tx))
-        } else {
-            Err(DecodeTxError)
+                summary.operational_cert = Some(babbage::OperationalCert {
+                    operational_cert_hot_vkey: body.operational_cert_hot_vkey.clone(),
+                    operational_cert_sequence_number: body.operational_cert_sequence_number,
+                    operational_cert_kes_period: body.operational_cert_kes_period,
+                    operational_cert_sigma: body.operational_cert_sigma.clone(),
+                });
+                summary.protocol_version = Some((body.protocol_major, body.protocol_minor));
+                summary.body_size = Some(body.block_body_size);
+                summary.body_hash = Some(body.block_body_hash);
+            }
+            MultiEraHeader::BabbageCompatible(x) => {
+                let body = &x.header_body;
+
+                summary.operational_cert = Some(body.operational_cert.clone());
+                summary.protocol_version = Some(body.protocol_version);
+                summary.body_size = Some(body.block_body_size);
+                summary.body_hash = Some(body.block_body_hash);
+            }
+            MultiEraHeader::Byron(x) => {
+                let (major, minor, _) = x.extra_data.block_version;
+
+                summary.issuer_vkey = Some(x.consensus_data.1.to_vec());
+                summary.protocol_version = Some((major.into(), minor.into()));
+            }
+            MultiEraHeader::EpochBoundary(_) => {}
         }
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::ledger::primitives::VrfCert;
+
+    use super::*;
+
+    /// An empty post-Byron block claiming `protocol_version`, without its
+    /// era tag.
+    fn empty_block(protocol_version: (u64, u64)) -> Vec<u8> {
+        let header = babbage::Header {
+            header_body: babbage::HeaderBody {
+                block_number: 42,
+                slot: 1000,
+                prev_hash: None,
+                issuer_vkey: vec![1; 32].into(),
+                vrf_vkey: vec![2; 32].into(),
+                vrf_result: VrfCert(vec![3; 64].into(), vec![4; 80].into()),
+                block_body_size: 4,
+                block_body_hash: Hash::new([5; 32]),
+                operational_cert: babbage::OperationalCert {
+                    operational_cert_hot_vkey: vec![6; 32].into(),
+                    operational_cert_sequence_number: 0,
+                    operational_cert_kes_period: 0,
+                    operational_cert_sigma: vec![7; 64].into(),
+                },
+                protocol_version,
+            },
+            body_signature: vec![8; 448].into(),
+        };
+
+        let mut encoder = minicbor::Encoder::new(vec![]);
+        encoder
+            .array(5)
+            .and_then(|e| e.encode(&header))
+            .and_then(|e| e.array(0))
+            .and_then(|e| e.array(0))
+            .and_then(|e| e.map(0))
+            .and_then(|e| e.array(0))
+            .unwrap();
+
+        encoder.into_writer()
+    }
+
+    #[test]
+    fn blocks_decode_with_or_without_their_era_tag() {
+        let bare = empty_block((9, 0));
+        let tagged = with_era_tag(CONWAY, &bare);
+
+        for cbor in [&bare, &tagged] {
+            let block = Block::decode(cbor).unwrap();
+
+            assert_eq!(block.era, Era::Conway);
+            assert_eq!(block.header.slot, 1000);
+            assert_eq!(block.header.height, 42);
+            assert_eq!(block.header.protocol_version, Some((9, 0)));
+            assert!(block.transactions.is_empty());
+        }
+    }
+
+    #[test]
+    fn bare_blocks_take_the_era_of_their_protocol_version() {
+        // A Conway decoder accepts the bytes as well
+        let block = Block::decode(&empty_block((8, 0))).unwrap();
+
+        assert_eq!(block.era, Era::Babbage);
+    }
+
+    #[test]
+    fn undecodable_input_reports_every_attempt() {
+        let Err(DecodeError::Cbor { attempts, .. }) = Decoded::decode(&[0x82, 0x01]) else {
+            panic!("expected a decode error");
+        };
+
+        let labels: Vec<_> = attempts.iter().map(|a| a.label.as_str()).collect();
+
+        assert!(labels.contains(&"Conway tx"));
+        assert!(labels.contains(&"Byron tx"));
+        assert!(labels.contains(&"Conway block"));
+        assert!(labels.contains(&"Byron epoch boundary block"));
+    }
+
+    #[test]
+    fn attempts_know_where_they_failed() {
+        let position = |cbor: &[u8], label: &str| {
+            let Err(DecodeError::Cbor { attempts, .. }) = Decoded::decode(cbor) else {
+                panic!("expected a decode error");
+            };
+
+            attempts
+                .into_iter()
+                .find(|attempt| attempt.label == label)
+                .unwrap()
+                .position
+        };
+
+        // Not even an array
+        assert_eq!(position(&[0x01], "Conway tx"), Some(0));
+        // Running out of bytes halfway through the body, after its inputs key
+        assert_eq!(position(&[0x84, 0xa1, 0x00], "Conway tx"), Some(3));
+    }
+
+    #[test]
+    fn the_furthest_attempt_is_picked_first_among_equals() {
+        let attempt = |position| Attempt {
+            label: String::new(),
+            error: String::new(),
+            position,
+        };
+
+        let attempts = [
+            attempt(Some(3)),
+            attempt(None),
+            attempt(Some(7)),
+            attempt(Some(7)),
+        ];
+
+        assert_eq!(furthest_attempt(&attempts), Some(2));
+        assert_eq!(furthest_attempt(&[attempt(None)]), None);
     }
 }
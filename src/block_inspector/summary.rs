@@ -0,0 +1,517 @@
+use pallas::{
+    codec::{
+        minicbor,
+        utils::{KeepRaw, Nullable},
+    },
+    crypto::hash::{Hash, Hasher},
+    ledger::{
+        primitives::{byron, conway, TransactionInput},
+        traverse::{self, Era},
+    },
+};
+
+use uplc::{machine::cost_model::ExBudget, tx::SlotConfig};
+
+use super::{EvalParams, Utxos, Witnesses};
+
+/// What a tx costs, measured on its original bytes rather than a re-encoding
+/// of the decoded tx, which wouldn't hash or size the same.
+#[derive(Debug, Clone)]
+pub struct TxSummary {
+    pub hash: Hash<32>,
+    pub era: Era,
+    pub size: usize,
+    pub body_size: usize,
+    pub witness_size: Option<usize>,
+    pub aux_size: Option<usize>,
+    pub fee: Option<u64>,
+    pub redeemers: usize,
+    /// Total memory and cpu steps declared by the redeemers
+    pub ex_units: (u64, u64),
+    /// Spent and referenced inputs, whose reference scripts count towards
+    /// the fee since Conway
+    pub script_inputs: Vec<TransactionInput>,
+    pub witnesses: Witnesses,
+}
+
+impl TxSummary {
+    pub fn new(era: Era, cbor: &[u8]) -> Option<Self> {
+        // Byron txs come with their witnesses out of blocks, but may be
+        // pasted without them. Either way the id is the hash of the tx alone.
+        if era == Era::Byron {
+            let (body, witness_size) = match minicbor::decode::<byron::MintedTxPayload>(cbor) {
+                Ok(payload) => (
+                    payload.transaction.raw_cbor(),
+                    Some(payload.witness.raw_cbor().len()),
+                ),
+                Err(_) => (cbor, None),
+            };
+
+            return Some(Self {
+                hash: Hasher::<256>::hash(body),
+                era,
+                size: cbor.len(),
+                body_size: body.len(),
+                witness_size,
+                aux_size: None,
+                fee: None,
+                redeemers: 0,
+                ex_units: (0, 0),
+                script_inputs: vec![],
+                witnesses: Witnesses::default(),
+            });
+        }
+
+        let tx = traverse::MultiEraTx::decode_for_era(era, cbor).ok()?;
+
+        let (body_size, witness_size, aux_size) = match &tx {
+            traverse::MultiEraTx::AlonzoCompatible(tx, _) => (
+                tx.transaction_body.raw_cbor().len(),
+                tx.transaction_witness_set.raw_cbor().len(),
+                raw_len(&tx.auxiliary_data),
+            ),
+            traverse::MultiEraTx::Babbage(tx) => (
+                tx.transaction_body.raw_cbor().len(),
+                tx.transaction_witness_set.raw_cbor().len(),
+                raw_len(&tx.auxiliary_data),
+            ),
+            traverse::MultiEraTx::Conway(tx) => (
+                tx.transaction_body.raw_cbor().len(),
+                tx.transaction_witness_set.raw_cbor().len(),
+                raw_len(&tx.auxiliary_data),
+            ),
+            // Byron is handled above, and the enum is non-exhaustive
+            _ => return None,
+        };
+
+        let redeemers = tx.redeemers();
+
+        let ex_units = redeemers.iter().fold((0, 0), |(mem, steps), redeemer| {
+            let units = redeemer.ex_units();
+
+            (mem + units.mem, steps + units.steps)
+        });
+
+        let script_inputs = tx
+            .inputs()
+            .into_iter()
+            .chain(tx.reference_inputs())
+            .map(|input| TransactionInput {
+                transaction_id: *input.hash(),
+                index: input.index(),
+            })
+            .collect();
+
+        Some(Self {
+            hash: tx.hash(),
+            era,
+            size: cbor.len(),
+            body_size,
+            witness_size: Some(witness_size),
+            aux_size,
+            fee: tx.fee(),
+            redeemers: redeemers.len(),
+            ex_units,
+            script_inputs,
+            witnesses: Witnesses::new(&tx),
+        })
+    }
+
+    /// The minimum fee under `params`. Reference scripts can only be counted
+    /// once the inputs holding them have been resolved.
+    pub fn min_fee(&self, params: &ProtocolParams, utxos: &Utxos) -> Result<MinFee, String> {
+        let min_fee_a = parse_integer("min fee a", &params.min_fee_a)?;
+        let min_fee_b = parse_integer("min fee b", &params.min_fee_b)?;
+        let price_mem = parse_ratio("memory price", &params.price_mem)?;
+        let price_steps = parse_ratio("step price", &params.price_steps)?;
+        let ref_script_cost = parse_ratio("ref script cost", &params.ref_script_cost_per_byte)?;
+
+        let too_large = || "protocol parameters are too large".to_string();
+
+        let size = min_fee_a
+            .checked_mul(self.size as u128)
+            .and_then(|fee| fee.checked_add(min_fee_b))
+            .ok_or_else(too_large)?;
+
+        // ceil(price_mem * mem + price_steps * steps)
+        let (mem, steps) = (self.ex_units.0 as u128, self.ex_units.1 as u128);
+        let scripts = (|| {
+            let mem = price_mem.0.checked_mul(mem)?.checked_mul(price_steps.1)?;
+            let steps = price_steps.0.checked_mul(steps)?.checked_mul(price_mem.1)?;
+
+            Some(
+                mem.checked_add(steps)?
+                    .div_ceil(price_mem.1.checked_mul(price_steps.1)?),
+            )
+        })()
+        .ok_or_else(too_large)?;
+
+        let mut unresolved = 0;
+        let mut ref_scripts_size = 0;
+
+        if self.era >= Era::Conway {
+            for input in &self.script_inputs {
+                match utxos.get(input) {
+                    Some(Ok(output)) => ref_scripts_size += ref_script_size(output),
+                    _ => unresolved += 1,
+                }
+            }
+        }
+
+        let reference_scripts =
+            ref_scripts_fee(ref_scripts_size as u128, ref_script_cost).ok_or_else(too_large)?;
+
+        Ok(MinFee {
+            size: size.try_into().map_err(|_| too_large())?,
+            scripts: scripts.try_into().map_err(|_| too_large())?,
+            reference_scripts: reference_scripts.try_into().map_err(|_| too_large())?,
+            unresolved,
+        })
+    }
+}
+
+fn raw_len<T: Clone>(aux: &Nullable<KeepRaw<'_, T>>) -> Option<usize> {
+    match aux {
+        Nullable::Some(aux) => Some(aux.raw_cbor().len()),
+        _ => None,
+    }
+}
+
+fn ref_script_size(output: &conway::TransactionOutput) -> usize {
+    let conway::TransactionOutput::PostAlonzo(output) = output else {
+        return 0;
+    };
+
+    match output.script_ref.as_ref().map(|script| &script.0) {
+        Some(conway::ScriptRef::NativeScript(script)) => {
+            minicbor::to_vec(script).map_or(0, |cbor| cbor.len())
+        }
+        Some(conway::ScriptRef::PlutusV1Script(script)) => script.0.len(),
+        Some(conway::ScriptRef::PlutusV2Script(script)) => script.0.len(),
+        Some(conway::ScriptRef::PlutusV3Script(script)) => script.0.len(),
+        None => 0,
+    }
+}
+
+/// Reference scripts are priced in tiers of 25 KiB, each 1.2 times more
+/// expensive per byte than the last.
+fn ref_scripts_fee(size: u128, (price, denominator): (u128, u128)) -> Option<u128> {
+    const TIER: u128 = 25 * 1024;
+
+    let (mut fee, mut price, mut denominator) = (0u128, price, denominator);
+    let mut remaining = size;
+
+    loop {
+        let tier = remaining.min(TIER);
+
+        fee = fee.checked_add(tier.checked_mul(price)?)?;
+        remaining -= tier;
+
+        if remaining == 0 {
+            return Some(fee / denominator);
+        }
+
+        // Keep everything over a common denominator, times 6/5 for the price
+        fee = fee.checked_mul(5)?;
+        denominator = denominator.checked_mul(5)?;
+        price = price.checked_mul(6)?;
+    }
+}
+
+/// The protocol parameters that go into the minimum fee and into evaluating
+/// scripts, kept as typed so they can be edited freely.
+#[derive(Debug, Clone)]
+pub struct ProtocolParams {
+    pub min_fee_a: String,
+    pub min_fee_b: String,
+    pub price_mem: String,
+    pub price_steps: String,
+    pub ref_script_cost_per_byte: String,
+    pub max_tx_mem: String,
+    pub max_tx_steps: String,
+    /// One per plutus version, left empty for the built-in cost model
+    pub cost_models: [String; 3],
+    pub zero_time: String,
+    pub zero_slot: String,
+    pub slot_length: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Param {
+    MinFeeA,
+    MinFeeB,
+    PriceMem,
+    PriceSteps,
+    RefScriptCostPerByte,
+    MaxTxMem,
+    MaxTxSteps,
+    /// Of plutus V1, V2 or V3, by index
+    CostModel(usize),
+    ZeroTime,
+    ZeroSlot,
+    SlotLength,
+}
+
+impl ProtocolParams {
+    pub fn set(&mut self, param: Param, value: String) {
+        match param {
+            Param::MinFeeA => self.min_fee_a = value,
+            Param::MinFeeB => self.min_fee_b = value,
+            Param::PriceMem => self.price_mem = value,
+            Param::PriceSteps => self.price_steps = value,
+            Param::RefScriptCostPerByte => self.ref_script_cost_per_byte = value,
+            Param::MaxTxMem => self.max_tx_mem = value,
+            Param::MaxTxSteps => self.max_tx_steps = value,
+            Param::CostModel(i) => self.cost_models[i] = value,
+            Param::ZeroTime => self.zero_time = value,
+            Param::ZeroSlot => self.zero_slot = value,
+            Param::SlotLength => self.slot_length = value,
+        }
+    }
+
+    /// What scripts are run with: the cost models, the max ex-units of a tx
+    /// as the budget and the slot config to convert validity intervals.
+    pub fn evaluation(&self) -> Result<EvalParams, String> {
+        let budget = |name, value| {
+            i64::try_from(parse_integer(name, value)?).map_err(|_| format!("{name} is too large"))
+        };
+
+        let mut cost_models: [Option<Vec<i64>>; 3] = Default::default();
+
+        for (i, cost_model) in self.cost_models.iter().enumerate() {
+            let name = format!("plutus V{} cost model", i + 1);
+            let cost_model = cost_model
+                .trim()
+                .trim_start_matches('[')
+                .trim_end_matches(']');
+
+            if cost_model.trim().is_empty() {
+                continue;
+            }
+
+            let costs = cost_model
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|cost| !cost.is_empty())
+                .map(|cost| cost.parse::<i64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| format!("{name} must be a list of whole numbers"))?;
+
+            if costs.len() < COST_MODEL_LENGTHS[i] {
+                return Err(format!(
+                    "{name} has {} costs, it needs at least {}",
+                    costs.len(),
+                    COST_MODEL_LENGTHS[i]
+                ));
+            }
+
+            cost_models[i] = Some(costs);
+        }
+
+        Ok(EvalParams {
+            cost_models,
+            budget: ExBudget {
+                mem: budget("max tx memory", &self.max_tx_mem)?,
+                cpu: budget("max tx steps", &self.max_tx_steps)?,
+            },
+            // Whole numbers are parsed as u64 to begin with
+            slot_config: SlotConfig {
+                zero_time: parse_integer("zero time", &self.zero_time)? as u64,
+                zero_slot: parse_integer("zero slot", &self.zero_slot)? as u64,
+                slot_length: parse_integer("slot length", &self.slot_length)?
+                    .try_into()
+                    .map_err(|_| "slot length is too large".to_string())?,
+            },
+        })
+    }
+}
+
+/// How many costs each plutus version's machine reads
+const COST_MODEL_LENGTHS: [usize; 3] = [166, 175, 297];
+
+/// Mainnet's current values
+impl Default for ProtocolParams {
+    fn default() -> Self {
+        Self {
+            min_fee_a: "44".to_string(),
+            min_fee_b: "155381".to_string(),
+            price_mem: "0.0577".to_string(),
+            price_steps: "0.0000721".to_string(),
+            ref_script_cost_per_byte: "15".to_string(),
+            max_tx_mem: "14000000".to_string(),
+            max_tx_steps: "10000000000".to_string(),
+            cost_models: Default::default(),
+            zero_time: "1596059091000".to_string(),
+            zero_slot: "4492800".to_string(),
+            slot_length: "1000".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MinFee {
+    pub size: u64,
+    pub scripts: u64,
+    pub reference_scripts: u64,
+    /// Inputs that may hold reference scripts but haven't been resolved
+    pub unresolved: usize,
+}
+
+impl MinFee {
+    pub fn total(&self) -> u64 {
+        self.size + self.scripts + self.reference_scripts
+    }
+}
+
+fn parse_integer(name: &str, value: &str) -> Result<u128, String> {
+    value
+        .trim()
+        .parse::<u64>()
+        .map(u128::from)
+        .map_err(|_| format!("{name} must be a whole number"))
+}
+
+/// A non-negative rational, given either as `numerator/denominator` or as a
+/// decimal like `0.0577`.
+fn parse_ratio(name: &str, value: &str) -> Result<(u128, u128), String> {
+    let invalid = || format!("{name} must be a decimal or a ratio like 577/10000");
+    let value = value.trim();
+
+    let (numerator, denominator) = match value.split_once('/') {
+        Some((numerator, denominator)) => (
+            numerator
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| invalid())?
+                .into(),
+            denominator
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| invalid())?
+                .into(),
+        ),
+        None => {
+            let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+
+            if whole.is_empty() && fraction.is_empty()
+                || !whole
+                    .chars()
+                    .chain(fraction.chars())
+                    .all(|c| c.is_ascii_digit())
+                || fraction.len() > 18
+            {
+                return Err(invalid());
+            }
+
+            let denominator = 10u128.pow(fraction.len() as u32);
+            let whole: u128 = if whole.is_empty() {
+                0
+            } else {
+                whole.parse::<u64>().map_err(|_| invalid())?.into()
+            };
+            let fraction: u128 = if fraction.is_empty() {
+                0
+            } else {
+                fraction.parse().map_err(|_| invalid())?
+            };
+
+            (whole * denominator + fraction, denominator)
+        }
+    };
+
+    if denominator == 0 {
+        return Err(invalid());
+    }
+
+    Ok((numerator, denominator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIER: u128 = 25 * 1024;
+
+    #[test]
+    fn byron_ids_leave_the_witnesses_out() {
+        let tx = hex::decode("838080a0").unwrap();
+        let payload = hex::decode("82838080a0818200d818478242aabb42ccdd").unwrap();
+
+        let bare = TxSummary::new(Era::Byron, &tx).unwrap();
+        let witnessed = TxSummary::new(Era::Byron, &payload).unwrap();
+
+        assert_eq!(bare.hash, Hasher::<256>::hash(&tx));
+        assert_eq!(witnessed.hash, bare.hash);
+        assert_eq!((bare.body_size, bare.witness_size), (4, None));
+        assert_eq!(
+            (witnessed.size, witnessed.body_size, witnessed.witness_size),
+            (18, 4, Some(13))
+        );
+    }
+
+    #[test]
+    fn ref_scripts_fee_tiers() {
+        let fee = |size| ref_scripts_fee(size, (15, 1)).unwrap();
+
+        assert_eq!(fee(0), 0);
+        assert_eq!(fee(1), 15);
+        assert_eq!(fee(TIER - 1), 383_985);
+        assert_eq!(fee(TIER), 384_000);
+        // 15 * 1.2
+        assert_eq!(fee(TIER + 1), 384_018);
+        assert_eq!(fee(2 * TIER - 1), 844_782);
+        assert_eq!(fee(2 * TIER), 844_800);
+        // 15 * 1.2^2 = 21.6, rounded down only at the end
+        assert_eq!(fee(2 * TIER + 1), 844_821);
+        assert_eq!(fee(3 * TIER), 1_397_760);
+        // 15 * 1.2^3 = 25.92
+        assert_eq!(fee(3 * TIER + 1), 1_397_785);
+        assert_eq!(fee(3 * TIER + 2), 1_397_811);
+    }
+
+    #[test]
+    fn ref_scripts_fee_fractional_price() {
+        // 12.5 a byte, half a lovelace is dropped
+        assert_eq!(ref_scripts_fee(3, (25, 2)), Some(37));
+        assert_eq!(ref_scripts_fee(TIER + 1, (25, 2)), Some(TIER * 25 / 2 + 15));
+    }
+
+    #[test]
+    fn evaluation_params() {
+        let mut params = ProtocolParams::default();
+
+        let defaults = params.evaluation().unwrap();
+
+        assert_eq!(defaults.cost_models, [None, None, None]);
+        assert_eq!(defaults.budget, ExBudget::default());
+        assert_eq!(
+            defaults.slot_config.zero_slot,
+            SlotConfig::default().zero_slot
+        );
+
+        let costs = vec!["100"; 175].join(", ");
+
+        params.set(Param::CostModel(1), format!("[{costs}]"));
+        params.set(Param::MaxTxMem, "16500000".to_string());
+
+        let evaluation = params.evaluation().unwrap();
+
+        assert_eq!(evaluation.cost_models[1], Some(vec![100; 175]));
+        assert_eq!(evaluation.budget.mem, 16_500_000);
+
+        params.set(Param::CostModel(2), costs);
+
+        assert_eq!(
+            params.evaluation().err(),
+            Some("plutus V3 cost model has 175 costs, it needs at least 297".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_ratio_decimals_and_fractions() {
+        assert_eq!(parse_ratio("", "0.0577"), Ok((577, 10_000)));
+        assert_eq!(parse_ratio("", "577/10000"), Ok((577, 10_000)));
+        assert_eq!(parse_ratio("", "15"), Ok((15, 1)));
+        assert!(parse_ratio("", "1/0").is_err());
+        assert!(parse_ratio("", ".").is_err());
+    }
+}
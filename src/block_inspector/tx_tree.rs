@@ -0,0 +1,1439 @@
+use std::net::Ipv6Addr;
+
+use pallas::{
+    codec::{
+        minicbor,
+        utils::{KeyValuePairs, Nullable},
+    },
+    crypto::hash::Hash,
+    ledger::{
+        addresses::{Address, ShelleyPaymentPart},
+        primitives::{
+            alonzo, babbage, byron, conway, ExUnits, Metadatum, PlutusData, PlutusScript, Relay,
+            StakeCredential, TransactionInput,
+        },
+    },
+};
+
+use crate::{
+    blueprint::{Blueprint, Purpose},
+    tree::Node,
+};
+
+use super::{MultiEraTx, Utxos};
+
+impl MultiEraTx {
+    /// The tx as a tree, with the outputs it spends inlined from `utxos`
+    /// wherever they've been resolved, and datums and redeemers labelled by
+    /// `blueprint` wherever it knows them.
+    pub fn tree(&self, utxos: &Utxos, blueprint: Option<&Blueprint>) -> Node {
+        match self {
+            MultiEraTx::AlonzoCompatible(tx, era) => {
+                let body = &tx.transaction_body;
+                let minted = body
+                    .mint
+                    .iter()
+                    .flat_map(|m| m.iter().map(|(p, _)| *p))
+                    .collect();
+                let context = Context::new(utxos, blueprint, body.inputs.clone(), minted);
+
+                alonzo_tx(tx, format!("{era:?}"), &context)
+            }
+            MultiEraTx::Babbage(tx) => {
+                let body = &tx.transaction_body;
+                let minted = body
+                    .mint
+                    .iter()
+                    .flat_map(|m| m.iter().map(|(p, _)| *p))
+                    .collect();
+                let context = Context::new(utxos, blueprint, body.inputs.clone(), minted);
+
+                babbage_tx(tx, &context)
+            }
+            MultiEraTx::Byron(tx, witnesses) => byron_tx(tx, witnesses.as_ref()),
+            MultiEraTx::Conway(tx) => {
+                let body = &tx.transaction_body;
+                let minted = body
+                    .mint
+                    .iter()
+                    .flat_map(|m| m.iter().map(|(p, _)| *p))
+                    .collect();
+                let inputs = body.inputs.iter().cloned().collect();
+                let context = Context::new(utxos, blueprint, inputs, minted);
+
+                conway_tx(tx, &context)
+            }
+        }
+    }
+}
+
+/// What parts of a tx need to know about the rest of it.
+struct Context<'a> {
+    utxos: &'a Utxos,
+    blueprint: Option<&'a Blueprint>,
+    /// Spent inputs and minted policies, in the order redeemers point into
+    inputs: Vec<TransactionInput>,
+    policies: Vec<Hash<28>>,
+}
+
+impl<'a> Context<'a> {
+    fn new(
+        utxos: &'a Utxos,
+        blueprint: Option<&'a Blueprint>,
+        mut inputs: Vec<TransactionInput>,
+        mut policies: Vec<Hash<28>>,
+    ) -> Self {
+        inputs.sort();
+        policies.sort();
+
+        Self {
+            utxos,
+            blueprint,
+            inputs,
+            policies,
+        }
+    }
+
+    /// The script locking the input a spend redeemer points at.
+    fn spent_script(&self, index: u32) -> Option<Hash<28>> {
+        let input = self.inputs.get(index as usize)?;
+
+        match self.utxos.get(input)? {
+            Ok(conway::PseudoTransactionOutput::Legacy(output)) => payment_script(&output.address),
+            Ok(conway::PseudoTransactionOutput::PostAlonzo(output)) => {
+                payment_script(&output.address)
+            }
+            Err(_) => None,
+        }
+    }
+
+    fn minted_script(&self, index: u32) -> Option<Hash<28>> {
+        self.policies.get(index as usize).copied()
+    }
+
+    /// Data labelled by the blueprint when it knows what it is.
+    fn data(
+        &self,
+        label: impl Into<String>,
+        purpose: Purpose,
+        data: &PlutusData,
+        script: Option<Hash<28>>,
+    ) -> Node {
+        let label = label.into();
+
+        self.blueprint
+            .and_then(|blueprint| {
+                blueprint.annotate_for(label.clone(), purpose, data, script.as_ref())
+            })
+            .unwrap_or_else(|| plutus_data(label, data))
+    }
+}
+
+fn payment_script(address: &[u8]) -> Option<Hash<28>> {
+    match Address::from_bytes(address).ok()? {
+        Address::Shelley(address) => match address.payment() {
+            ShelleyPaymentPart::Script(hash) => Some(*hash),
+            ShelleyPaymentPart::Key(_) => None,
+        },
+        _ => None,
+    }
+}
+
+fn alonzo_tx(tx: &alonzo::Tx, era: String, context: &Context) -> Node {
+    let body = &tx.transaction_body;
+    let witnesses = &tx.transaction_witness_set;
+
+    Node::new("Transaction")
+        .value(era)
+        .push(
+            Node::new("body")
+                .push(inputs("inputs", &body.inputs, context))
+                .push(Node::list(
+                    "outputs",
+                    body.outputs
+                        .iter()
+                        .enumerate()
+                        .map(|(i, out)| legacy_output(format!("#{i}"), out)),
+                ))
+                .push(Node::leaf("fee", lovelace(body.fee)))
+                .push(validity_interval(body.validity_interval_start, body.ttl))
+                .push_maybe(
+                    body.certificates.as_ref().map(|certs| {
+                        Node::list("certificates", certs.iter().map(alonzo_certificate))
+                    }),
+                )
+                .push_maybe(body.withdrawals.as_ref().map(|w| withdrawals(w.iter())))
+                .push_maybe(body.update.as_ref().map(alonzo_update))
+                .push_maybe(
+                    body.auxiliary_data_hash
+                        .as_ref()
+                        .map(|hash| Node::leaf("auxiliary data hash", hash)),
+                )
+                .push_maybe(body.mint.as_ref().map(|mint| {
+                    multiasset(
+                        "mint",
+                        mint.iter()
+                            .map(|(p, a)| (p, assets(a.iter(), i64::to_string))),
+                    )
+                }))
+                .push_maybe(
+                    body.script_data_hash
+                        .as_ref()
+                        .map(|hash| Node::leaf("script data hash", hash)),
+                )
+                .push_maybe(
+                    body.collateral
+                        .as_ref()
+                        .map(|c| inputs("collateral", c, context)),
+                )
+                .push_maybe(body.required_signers.as_ref().map(|s| required_signers(s)))
+                .push_maybe(
+                    body.network_id
+                        .as_ref()
+                        .map(|id| Node::leaf("network id", format!("{id:?}"))),
+                ),
+        )
+        .push(
+            Node::new("witness set")
+                .push_maybe(witnesses.vkeywitness.as_ref().map(|w| vkey_witnesses(w)))
+                .push_maybe(witnesses.native_script.as_ref().map(|s| native_scripts(s)))
+                .push_maybe(
+                    witnesses
+                        .bootstrap_witness
+                        .as_ref()
+                        .map(|w| bootstrap_witnesses(w)),
+                )
+                .push_maybe(
+                    witnesses
+                        .plutus_script
+                        .as_ref()
+                        .map(|s| plutus_scripts("plutus v1 scripts", s)),
+                )
+                .push_maybe(
+                    witnesses
+                        .plutus_data
+                        .as_ref()
+                        .map(|d| plutus_data_list(d, context)),
+                )
+                .push_maybe(
+                    witnesses
+                        .redeemer
+                        .as_ref()
+                        .map(|r| alonzo_redeemers(r, context)),
+                ),
+        )
+        .push(Node::leaf("is valid", tx.success))
+        .push_maybe(auxiliary_data(&tx.auxiliary_data))
+}
+
+fn babbage_tx(tx: &babbage::Tx, context: &Context) -> Node {
+    let body = &tx.transaction_body;
+    let witnesses = &tx.transaction_witness_set;
+
+    Node::new("Transaction")
+        .value("Babbage")
+        .push(
+            Node::new("body")
+                .push(inputs("inputs", &body.inputs, context))
+                .push(Node::list(
+                    "outputs",
+                    body.outputs
+                        .iter()
+                        .enumerate()
+                        .map(|(i, out)| babbage_output(format!("#{i}"), out, context)),
+                ))
+                .push(Node::leaf("fee", lovelace(body.fee)))
+                .push(validity_interval(body.validity_interval_start, body.ttl))
+                .push_maybe(
+                    body.certificates.as_ref().map(|certs| {
+                        Node::list("certificates", certs.iter().map(alonzo_certificate))
+                    }),
+                )
+                .push_maybe(body.withdrawals.as_ref().map(|w| withdrawals(w.iter())))
+                .push_maybe(body.update.as_ref().map(babbage_update))
+                .push_maybe(
+                    body.auxiliary_data_hash
+                        .as_ref()
+                        .map(|hash| Node::leaf("auxiliary data hash", hash)),
+                )
+                .push_maybe(body.mint.as_ref().map(|mint| {
+                    multiasset(
+                        "mint",
+                        mint.iter()
+                            .map(|(p, a)| (p, assets(a.iter(), i64::to_string))),
+                    )
+                }))
+                .push_maybe(
+                    body.script_data_hash
+                        .as_ref()
+                        .map(|hash| Node::leaf("script data hash", hash)),
+                )
+                .push_maybe(
+                    body.collateral
+                        .as_ref()
+                        .map(|c| inputs("collateral", c, context)),
+                )
+                .push_maybe(
+                    body.collateral_return
+                        .as_ref()
+                        .map(|out| babbage_output("collateral return", out, context)),
+                )
+                .push_maybe(
+                    body.total_collateral
+                        .map(|coin| Node::leaf("total collateral", lovelace(coin))),
+                )
+                .push_maybe(
+                    body.reference_inputs
+                        .as_ref()
+                        .map(|r| inputs("reference inputs", r, context)),
+                )
+                .push_maybe(body.required_signers.as_ref().map(|s| required_signers(s)))
+                .push_maybe(
+                    body.network_id
+                        .as_ref()
+                        .map(|id| Node::leaf("network id", format!("{id:?}"))),
+                ),
+        )
+        .push(
+            Node::new("witness set")
+                .push_maybe(witnesses.vkeywitness.as_ref().map(|w| vkey_witnesses(w)))
+                .push_maybe(witnesses.native_script.as_ref().map(|s| native_scripts(s)))
+                .push_maybe(
+                    witnesses
+                        .bootstrap_witness
+                        .as_ref()
+                        .map(|w| bootstrap_witnesses(w)),
+                )
+                .push_maybe(
+                    witnesses
+                        .plutus_v1_script
+                        .as_ref()
+                        .map(|s| plutus_scripts("plutus v1 scripts", s)),
+                )
+                .push_maybe(
+                    witnesses
+                        .plutus_v2_script
+                        .as_ref()
+                        .map(|s| plutus_scripts("plutus v2 scripts", s)),
+                )
+                .push_maybe(
+                    witnesses
+                        .plutus_data
+                        .as_ref()
+                        .map(|d| plutus_data_list(d, context)),
+                )
+                .push_maybe(
+                    witnesses
+                        .redeemer
+                        .as_ref()
+                        .map(|r| alonzo_redeemers(r, context)),
+                ),
+        )
+        .push(Node::leaf("is valid", tx.success))
+        .push_maybe(auxiliary_data(&tx.auxiliary_data))
+}
+
+fn conway_tx(tx: &conway::Tx, context: &Context) -> Node {
+    let body = &tx.transaction_body;
+    let witnesses = &tx.transaction_witness_set;
+
+    Node::new("Transaction")
+        .value("Conway")
+        .push(
+            Node::new("body")
+                .push(inputs("inputs", &body.inputs, context))
+                .push(Node::list(
+                    "outputs",
+                    body.outputs
+                        .iter()
+                        .enumerate()
+                        .map(|(i, out)| conway_output(format!("#{i}"), out, context)),
+                ))
+                .push(Node::leaf("fee", lovelace(body.fee)))
+                .push(validity_interval(body.validity_interval_start, body.ttl))
+                .push_maybe(
+                    body.certificates.as_ref().map(|certs| {
+                        Node::list("certificates", certs.iter().map(conway_certificate))
+                    }),
+                )
+                .push_maybe(body.withdrawals.as_ref().map(|w| withdrawals(w.iter())))
+                .push_maybe(
+                    body.auxiliary_data_hash
+                        .as_ref()
+                        .map(|hash| Node::leaf("auxiliary data hash", hash)),
+                )
+                .push_maybe(body.mint.as_ref().map(|mint| {
+                    multiasset(
+                        "mint",
+                        mint.iter()
+                            .map(|(p, a)| (p, assets(a.iter(), |q| i64::from(*q).to_string()))),
+                    )
+                }))
+                .push_maybe(
+                    body.script_data_hash
+                        .as_ref()
+                        .map(|hash| Node::leaf("script data hash", hash)),
+                )
+                .push_maybe(
+                    body.collateral
+                        .as_ref()
+                        .map(|c| inputs("collateral", c, context)),
+                )
+                .push_maybe(
+                    body.collateral_return
+                        .as_ref()
+                        .map(|out| conway_output("collateral return", out, context)),
+                )
+                .push_maybe(
+                    body.total_collateral
+                        .map(|coin| Node::leaf("total collateral", lovelace(coin))),
+                )
+                .push_maybe(
+                    body.reference_inputs
+                        .as_ref()
+                        .map(|r| inputs("reference inputs", r, context)),
+                )
+                .push_maybe(body.required_signers.as_ref().map(|s| required_signers(s)))
+                .push_maybe(
+                    body.network_id
+                        .as_ref()
+                        .map(|id| Node::leaf("network id", format!("{id:?}"))),
+                )
+                .push_maybe(body.voting_procedures.as_ref().map(voting_procedures))
+                .push_maybe(body.proposal_procedures.as_ref().map(|proposals| {
+                    Node::list(
+                        "proposal procedures",
+                        proposals.iter().enumerate().map(proposal_procedure),
+                    )
+                }))
+                .push_maybe(
+                    body.treasury_value
+                        .map(|coin| Node::leaf("current treasury value", lovelace(coin))),
+                )
+                .push_maybe(
+                    body.donation
+                        .as_ref()
+                        .map(|coin| Node::leaf("donation", lovelace(u64::from(coin)))),
+                ),
+        )
+        .push(
+            Node::new("witness set")
+                .push_maybe(witnesses.vkeywitness.as_ref().map(|w| vkey_witnesses(w)))
+                .push_maybe(witnesses.native_script.as_ref().map(|s| native_scripts(s)))
+                .push_maybe(
+                    witnesses
+                        .bootstrap_witness
+                        .as_ref()
+                        .map(|w| bootstrap_witnesses(w)),
+                )
+                .push_maybe(
+                    witnesses
+                        .plutus_v1_script
+                        .as_ref()
+                        .map(|s| plutus_scripts("plutus v1 scripts", s)),
+                )
+                .push_maybe(
+                    witnesses
+                        .plutus_v2_script
+                        .as_ref()
+                        .map(|s| plutus_scripts("plutus v2 scripts", s)),
+                )
+                .push_maybe(
+                    witnesses
+                        .plutus_v3_script
+                        .as_ref()
+                        .map(|s| plutus_scripts("plutus v3 scripts", s)),
+                )
+                .push_maybe(
+                    witnesses
+                        .plutus_data
+                        .as_ref()
+                        .map(|d| plutus_data_list(d, context)),
+                )
+                .push_maybe(
+                    witnesses
+                        .redeemer
+                        .as_ref()
+                        .map(|r| conway_redeemers(r, context)),
+                ),
+        )
+        .push(Node::leaf("is valid", tx.success))
+        .push_maybe(auxiliary_data(&tx.auxiliary_data))
+}
+
+fn byron_tx(tx: &byron::Tx, witnesses: Option<&byron::Witnesses>) -> Node {
+    Node::new("Transaction")
+        .value("Byron")
+        .push(Node::list(
+            "inputs",
+            tx.inputs.iter().enumerate().map(|(i, input)| match input {
+                byron::TxIn::Variant0(wrapped) => {
+                    let (id, index) = &wrapped.0;
+                    Node::leaf(format!("#{i}"), format!("{id}#{index}"))
+                }
+                byron::TxIn::Other(variant, bytes) => Node::leaf(
+                    format!("#{i}"),
+                    format!(
+                        "unknown variant {variant}: {}",
+                        hex::encode(bytes.as_slice())
+                    ),
+                ),
+            }),
+        ))
+        .push(Node::list(
+            "outputs",
+            tx.outputs.iter().enumerate().map(|(i, out)| {
+                let address = minicbor::to_vec(&out.address)
+                    .map(|bytes| address(&bytes))
+                    .unwrap_or_else(|e| e.to_string());
+
+                Node::new(format!("#{i}"))
+                    .push(Node::leaf("address", address))
+                    .push(Node::leaf("value", lovelace(out.amount)))
+            }),
+        ))
+        .push_maybe(witnesses.map(|witnesses| {
+            Node::list(
+                "witnesses",
+                witnesses
+                    .iter()
+                    .enumerate()
+                    .map(|(i, witness)| byron_witness(i, witness)),
+            )
+        }))
+}
+
+fn byron_witness(i: usize, witness: &byron::Twit) -> Node {
+    let key = |kind: &str, (key, signature): &(byron::PubKey, byron::Signature)| {
+        Node::new(format!("#{i}"))
+            .value(kind)
+            .push(Node::leaf("key", hex::encode(key.as_slice())))
+            .push(Node::leaf("signature", hex::encode(signature.as_slice())))
+    };
+    let script = |label: &str, (version, script): &byron::ValidatorScript| {
+        Node::leaf(label, hex::encode(script.as_slice())).push(Node::leaf("version", *version))
+    };
+
+    match witness {
+        byron::Twit::PkWitness(wrapped) => key("public key", &wrapped.0),
+        byron::Twit::RedeemWitness(wrapped) => key("redeem", &wrapped.0),
+        byron::Twit::ScriptWitness(wrapped) => {
+            let (validator, redeemer) = &wrapped.0;
+
+            Node::new(format!("#{i}"))
+                .value("script")
+                .push(script("validator", validator))
+                .push(script("redeemer", redeemer))
+        }
+        byron::Twit::Other(variant, bytes) => Node::leaf(
+            format!("#{i}"),
+            format!(
+                "unknown variant {variant}: {}",
+                hex::encode(bytes.as_slice())
+            ),
+        ),
+    }
+}
+
+pub fn address(bytes: &[u8]) -> String {
+    match Address::from_bytes(bytes) {
+        Ok(address) => address.to_string(),
+        Err(_) => hex::encode(bytes),
+    }
+}
+
+pub fn lovelace(coin: u64) -> String {
+    format!("{coin} lovelace")
+}
+
+fn input(i: usize, input: &TransactionInput, context: &Context) -> Node {
+    Node::leaf(
+        format!("#{i}"),
+        format!("{}#{}", input.transaction_id, input.index),
+    )
+    .push_maybe(context.utxos.get(input).map(|utxo| match utxo {
+        Ok(output) => conway_output("spent output", output, context),
+        Err(e) => Node::leaf("spent output", e),
+    }))
+}
+
+fn inputs<'a>(
+    label: &str,
+    inputs: impl IntoIterator<Item = &'a TransactionInput>,
+    context: &Context,
+) -> Node {
+    Node::list(
+        label,
+        inputs
+            .into_iter()
+            .enumerate()
+            .map(|(i, tx_in)| input(i, tx_in, context)),
+    )
+}
+
+fn validity_interval(start: Option<u64>, ttl: Option<u64>) -> Node {
+    let slot = |slot: Option<u64>| match slot {
+        Some(slot) => format!("slot {slot}"),
+        None => "unbounded".to_string(),
+    };
+
+    Node::new("validity interval")
+        .push(Node::leaf("invalid before", slot(start)))
+        .push(Node::leaf("invalid hereafter", slot(ttl)))
+}
+
+fn asset_name(name: &[u8]) -> String {
+    match std::str::from_utf8(name) {
+        Ok(s) if !s.is_empty() && s.chars().all(|c| !c.is_control()) => {
+            format!("{s} ({})", hex::encode(name))
+        }
+        _ => hex::encode(name),
+    }
+}
+
+fn multiasset<'a>(
+    label: &str,
+    policies: impl Iterator<Item = (&'a Hash<28>, Vec<(&'a [u8], String)>)>,
+) -> Node {
+    Node::list(
+        label,
+        policies.map(|(policy, assets)| {
+            Node::branch(
+                "policy",
+                assets
+                    .into_iter()
+                    .map(|(name, quantity)| Node::leaf(asset_name(name), quantity)),
+            )
+            .value(policy)
+        }),
+    )
+}
+
+fn assets<'a, Q>(
+    assets: impl IntoIterator<Item = &'a (alonzo::Bytes, Q)>,
+    quantity: impl Fn(&Q) -> String,
+) -> Vec<(&'a [u8], String)>
+where
+    Q: 'a,
+{
+    assets
+        .into_iter()
+        .map(|(name, q)| (name.as_slice(), quantity(q)))
+        .collect()
+}
+
+fn alonzo_value(value: &alonzo::Value) -> Vec<Node> {
+    match value {
+        alonzo::Value::Coin(coin) => vec![Node::leaf("lovelace", coin)],
+        alonzo::Value::Multiasset(coin, assets) => vec![
+            Node::leaf("lovelace", coin),
+            multiasset(
+                "assets",
+                assets
+                    .iter()
+                    .map(|(p, a)| (p, self::assets(a.iter(), u64::to_string))),
+            ),
+        ],
+    }
+}
+
+fn conway_value(value: &conway::Value) -> Vec<Node> {
+    match value {
+        conway::Value::Coin(coin) => vec![Node::leaf("lovelace", coin)],
+        conway::Value::Multiasset(coin, assets) => vec![
+            Node::leaf("lovelace", coin),
+            multiasset(
+                "assets",
+                assets
+                    .iter()
+                    .map(|(p, a)| (p, self::assets(a.iter(), |q| u64::from(q).to_string()))),
+            ),
+        ],
+    }
+}
+
+fn legacy_output(label: impl Into<String>, output: &alonzo::TransactionOutput) -> Node {
+    Node::new(label)
+        .push(Node::leaf("address", address(&output.address)))
+        .push(Node::branch("value", alonzo_value(&output.amount)))
+        .push_maybe(
+            output
+                .datum_hash
+                .as_ref()
+                .map(|hash| Node::leaf("datum hash", hash)),
+        )
+}
+
+fn babbage_output(
+    label: impl Into<String>,
+    output: &babbage::TransactionOutput,
+    context: &Context,
+) -> Node {
+    match output {
+        babbage::PseudoTransactionOutput::Legacy(output) => legacy_output(label, output),
+        babbage::PseudoTransactionOutput::PostAlonzo(output) => Node::new(label)
+            .push(Node::leaf("address", address(&output.address)))
+            .push(Node::branch("value", alonzo_value(&output.value)))
+            .push_maybe(
+                output
+                    .datum_option
+                    .as_ref()
+                    .map(|datum| datum_option(datum, &output.address, context)),
+            )
+            .push_maybe(output.script_ref.as_ref().map(|script| match &script.0 {
+                babbage::PseudoScript::NativeScript(script) => {
+                    Node::new("script ref").push(native_script("native script", script))
+                }
+                babbage::PseudoScript::PlutusV1Script(script) => {
+                    plutus_script("script ref", "plutus v1", script)
+                }
+                babbage::PseudoScript::PlutusV2Script(script) => {
+                    plutus_script("script ref", "plutus v2", script)
+                }
+            })),
+    }
+}
+
+fn conway_output(
+    label: impl Into<String>,
+    output: &conway::TransactionOutput,
+    context: &Context,
+) -> Node {
+    match output {
+        conway::PseudoTransactionOutput::Legacy(output) => legacy_output(label, output),
+        conway::PseudoTransactionOutput::PostAlonzo(output) => Node::new(label)
+            .push(Node::leaf("address", address(&output.address)))
+            .push(Node::branch("value", conway_value(&output.value)))
+            .push_maybe(
+                output
+                    .datum_option
+                    .as_ref()
+                    .map(|datum| datum_option(datum, &output.address, context)),
+            )
+            .push_maybe(output.script_ref.as_ref().map(|script| match &script.0 {
+                conway::PseudoScript::NativeScript(script) => {
+                    Node::new("script ref").push(native_script("native script", script))
+                }
+                conway::PseudoScript::PlutusV1Script(script) => {
+                    plutus_script("script ref", "plutus v1", script)
+                }
+                conway::PseudoScript::PlutusV2Script(script) => {
+                    plutus_script("script ref", "plutus v2", script)
+                }
+                conway::PseudoScript::PlutusV3Script(script) => {
+                    plutus_script("script ref", "plutus v3", script)
+                }
+            })),
+    }
+}
+
+fn datum_option(datum: &babbage::DatumOption, address: &[u8], context: &Context) -> Node {
+    match datum {
+        babbage::PseudoDatumOption::Hash(hash) => Node::leaf("datum hash", hash),
+        babbage::PseudoDatumOption::Data(data) => context.data(
+            "inline datum",
+            Purpose::Datum,
+            &data.0,
+            payment_script(address),
+        ),
+    }
+}
+
+fn plutus_data(label: impl Into<String>, data: &PlutusData) -> Node {
+    crate::plutus_data::tree(label, data)
+}
+
+fn plutus_data_list(data: &[PlutusData], context: &Context) -> Node {
+    Node::list(
+        "plutus data",
+        data.iter()
+            .enumerate()
+            .map(|(i, d)| context.data(format!("#{i}"), Purpose::Datum, d, None)),
+    )
+}
+
+fn plutus_script<const VERSION: usize>(
+    label: impl Into<String>,
+    version: &str,
+    script: &PlutusScript<VERSION>,
+) -> Node {
+    Node::new(label)
+        .value(version)
+        .push(Node::leaf("size", format!("{} bytes", script.0.len())))
+        .push(Node::leaf("cbor", &script.0))
+}
+
+fn plutus_scripts<const VERSION: usize>(label: &str, scripts: &[PlutusScript<VERSION>]) -> Node {
+    Node::list(
+        label,
+        scripts.iter().enumerate().map(|(i, script)| {
+            Node::new(format!("#{i}"))
+                .push(Node::leaf("size", format!("{} bytes", script.0.len())))
+                .push(Node::leaf("cbor", &script.0))
+        }),
+    )
+}
+
+pub fn native_script(label: impl Into<String>, script: &alonzo::NativeScript) -> Node {
+    let label = label.into();
+
+    match script {
+        alonzo::NativeScript::ScriptPubkey(hash) => Node::leaf(label, format!("sig {hash}")),
+        alonzo::NativeScript::ScriptAll(scripts) => Node::list(
+            label,
+            scripts
+                .iter()
+                .enumerate()
+                .map(|(i, s)| native_script(format!("#{i}"), s)),
+        )
+        .value(format!("all [{}]", scripts.len())),
+        alonzo::NativeScript::ScriptAny(scripts) => Node::list(
+            label,
+            scripts
+                .iter()
+                .enumerate()
+                .map(|(i, s)| native_script(format!("#{i}"), s)),
+        )
+        .value(format!("any [{}]", scripts.len())),
+        alonzo::NativeScript::ScriptNOfK(n, scripts) => Node::list(
+            label,
+            scripts
+                .iter()
+                .enumerate()
+                .map(|(i, s)| native_script(format!("#{i}"), s)),
+        )
+        .value(format!("at least {n} of [{}]", scripts.len())),
+        alonzo::NativeScript::InvalidBefore(slot) => {
+            Node::leaf(label, format!("after slot {slot}"))
+        }
+        alonzo::NativeScript::InvalidHereafter(slot) => {
+            Node::leaf(label, format!("before slot {slot}"))
+        }
+    }
+}
+
+fn native_scripts(scripts: &[alonzo::NativeScript]) -> Node {
+    Node::list(
+        "native scripts",
+        scripts
+            .iter()
+            .enumerate()
+            .map(|(i, s)| native_script(format!("#{i}"), s)),
+    )
+}
+
+fn credential(label: impl Into<String>, credential: &StakeCredential) -> Node {
+    match credential {
+        StakeCredential::AddrKeyhash(hash) => Node::leaf(label, format!("key hash {hash}")),
+        StakeCredential::ScriptHash(hash) => Node::leaf(label, format!("script hash {hash}")),
+    }
+}
+
+fn withdrawals<'a>(withdrawals: impl Iterator<Item = &'a (alonzo::Bytes, u64)>) -> Node {
+    Node::list(
+        "withdrawals",
+        withdrawals.map(|(account, coin)| Node::leaf(address(account), lovelace(*coin))),
+    )
+}
+
+fn required_signers(signers: &[Hash<28>]) -> Node {
+    Node::list(
+        "required signers",
+        signers
+            .iter()
+            .enumerate()
+            .map(|(i, hash)| Node::leaf(format!("#{i}"), hash)),
+    )
+}
+
+fn vkey_witnesses(witnesses: &[alonzo::VKeyWitness]) -> Node {
+    Node::list(
+        "vkey witnesses",
+        witnesses.iter().enumerate().map(|(i, witness)| {
+            Node::new(format!("#{i}"))
+                .push(Node::leaf("vkey", &witness.vkey))
+                .push(Node::leaf("signature", &witness.signature))
+        }),
+    )
+}
+
+fn bootstrap_witnesses(witnesses: &[alonzo::BootstrapWitness]) -> Node {
+    Node::list(
+        "bootstrap witnesses",
+        witnesses.iter().enumerate().map(|(i, witness)| {
+            Node::new(format!("#{i}"))
+                .push(Node::leaf("public key", &witness.public_key))
+                .push(Node::leaf("signature", &witness.signature))
+                .push(Node::leaf("chain code", &witness.chain_code))
+                .push(Node::leaf("attributes", &witness.attributes))
+        }),
+    )
+}
+
+fn ex_units(ex_units: &ExUnits) -> Node {
+    Node::new("ex units")
+        .push(Node::leaf("mem", ex_units.mem))
+        .push(Node::leaf("steps", ex_units.steps))
+}
+
+/// A redeemer, with its data labelled by the blueprint of `script`.
+fn redeemer(
+    label: impl Into<String>,
+    tag: impl std::fmt::Debug,
+    index: u32,
+    data: &PlutusData,
+    units: &ExUnits,
+    script: Option<Hash<28>>,
+    context: &Context,
+) -> Node {
+    Node::new(label)
+        .value(format!("{tag:?} #{index}"))
+        .push(context.data("data", Purpose::Redeemer, data, script))
+        .push(ex_units(units))
+}
+
+fn alonzo_redeemers(redeemers: &[alonzo::Redeemer], context: &Context) -> Node {
+    let script = |r: &alonzo::Redeemer| match r.tag {
+        alonzo::RedeemerTag::Spend => context.spent_script(r.index),
+        alonzo::RedeemerTag::Mint => context.minted_script(r.index),
+        _ => None,
+    };
+
+    Node::list(
+        "redeemers",
+        redeemers.iter().enumerate().map(|(i, r)| {
+            redeemer(
+                format!("#{i}"),
+                r.tag,
+                r.index,
+                &r.data,
+                &r.ex_units,
+                script(r),
+                context,
+            )
+        }),
+    )
+}
+
+fn conway_redeemers(redeemers: &conway::Redeemers, context: &Context) -> Node {
+    let script = |tag: &conway::RedeemerTag, index: u32| match tag {
+        conway::RedeemerTag::Spend => context.spent_script(index),
+        conway::RedeemerTag::Mint => context.minted_script(index),
+        _ => None,
+    };
+
+    match redeemers {
+        conway::Redeemers::List(list) => Node::list(
+            "redeemers",
+            list.iter().enumerate().map(|(i, r)| {
+                redeemer(
+                    format!("#{i}"),
+                    r.tag,
+                    r.index,
+                    &r.data,
+                    &r.ex_units,
+                    script(&r.tag, r.index),
+                    context,
+                )
+            }),
+        ),
+        conway::Redeemers::Map(map) => Node::list(
+            "redeemers",
+            map.iter().enumerate().map(|(i, (key, value))| {
+                redeemer(
+                    format!("#{i}"),
+                    key.tag,
+                    key.index,
+                    &value.data,
+                    &value.ex_units,
+                    script(&key.tag, key.index),
+                    context,
+                )
+            }),
+        ),
+    }
+}
+
+fn relay(i: usize, relay: &Relay) -> Node {
+    let port = |port: &Nullable<u32>| match port {
+        Nullable::Some(port) => format!(":{port}"),
+        _ => "".to_string(),
+    };
+
+    let value = match relay {
+        Relay::SingleHostAddr(p, ipv4, ipv6) => {
+            let ip = match (ipv4, ipv6) {
+                (Nullable::Some(ip), _) if ip.len() == 4 => {
+                    format!("{}.{}.{}.{}", ip[0], ip[1], ip[2], ip[3])
+                }
+                (_, Nullable::Some(ip)) => match <[u8; 16]>::try_from(ip.as_slice()) {
+                    Ok(ip) => format!("[{}]", Ipv6Addr::from(ip)),
+                    Err(_) => hex::encode(ip.as_slice()),
+                },
+                _ => "?".to_string(),
+            };
+            format!("{ip}{}", port(p))
+        }
+        Relay::SingleHostName(p, name) => format!("{name}{}", port(p)),
+        Relay::MultiHostName(name) => format!("{name} (multi)"),
+    };
+
+    Node::leaf(format!("#{i}"), value)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn pool_registration(
+    operator: &Hash<28>,
+    vrf_keyhash: &Hash<32>,
+    pledge: u64,
+    cost: u64,
+    margin: &alonzo::RationalNumber,
+    reward_account: &[u8],
+    pool_owners: &[Hash<28>],
+    relays: &[Relay],
+    pool_metadata: &Nullable<alonzo::PoolMetadata>,
+) -> Node {
+    Node::new("pool registration")
+        .push(Node::leaf("operator", operator))
+        .push(Node::leaf("vrf key hash", vrf_keyhash))
+        .push(Node::leaf("pledge", lovelace(pledge)))
+        .push(Node::leaf("cost", lovelace(cost)))
+        .push(Node::leaf(
+            "margin",
+            format!("{}/{}", margin.numerator, margin.denominator),
+        ))
+        .push(Node::leaf("reward account", address(reward_account)))
+        .push(Node::list(
+            "owners",
+            pool_owners
+                .iter()
+                .enumerate()
+                .map(|(i, owner)| Node::leaf(format!("#{i}"), owner)),
+        ))
+        .push(Node::list(
+            "relays",
+            relays.iter().enumerate().map(|(i, r)| relay(i, r)),
+        ))
+        .push_maybe(match pool_metadata {
+            Nullable::Some(metadata) => Some(
+                Node::new("metadata")
+                    .push(Node::leaf("url", &metadata.url))
+                    .push(Node::leaf("hash", metadata.hash)),
+            ),
+            _ => None,
+        })
+}
+
+fn alonzo_certificate(certificate: &alonzo::Certificate) -> Node {
+    use alonzo::Certificate::*;
+    match certificate {
+        StakeRegistration(cred) => {
+            Node::new("stake registration").push(credential("credential", cred))
+        }
+        StakeDeregistration(cred) => {
+            Node::new("stake deregistration").push(credential("credential", cred))
+        }
+        StakeDelegation(cred, pool) => Node::new("stake delegation")
+            .push(credential("credential", cred))
+            .push(Node::leaf("pool", pool)),
+        PoolRegistration {
+            operator,
+            vrf_keyhash,
+            pledge,
+            cost,
+            margin,
+            reward_account,
+            pool_owners,
+            relays,
+            pool_metadata,
+        } => pool_registration(
+            operator,
+            vrf_keyhash,
+            *pledge,
+            *cost,
+            margin,
+            reward_account,
+            pool_owners,
+            relays,
+            pool_metadata,
+        ),
+        PoolRetirement(pool, epoch) => Node::new("pool retirement")
+            .push(Node::leaf("pool", pool))
+            .push(Node::leaf("epoch", epoch)),
+        GenesisKeyDelegation(genesis, delegate, vrf) => Node::new("genesis key delegation")
+            .push(Node::leaf("genesis hash", genesis))
+            .push(Node::leaf("delegate hash", delegate))
+            .push(Node::leaf("vrf key hash", vrf)),
+        MoveInstantaneousRewardsCert(mir) => Node::new("move instantaneous rewards")
+            .push(Node::leaf("source", format!("{:?}", mir.source)))
+            .push(match &mir.target {
+                alonzo::InstantaneousRewardTarget::StakeCredentials(creds) => Node::list(
+                    "targets",
+                    creds.iter().map(|(cred, delta)| {
+                        credential("credential", cred).push(Node::leaf("delta", delta))
+                    }),
+                ),
+                alonzo::InstantaneousRewardTarget::OtherAccountingPot(coin) => {
+                    Node::leaf("other accounting pot", lovelace(*coin))
+                }
+            }),
+    }
+}
+
+fn drep(label: &str, drep: &conway::DRep) -> Node {
+    match drep {
+        conway::DRep::Key(hash) => Node::leaf(label, format!("key hash {hash}")),
+        conway::DRep::Script(hash) => Node::leaf(label, format!("script hash {hash}")),
+        conway::DRep::Abstain => Node::leaf(label, "always abstain"),
+        conway::DRep::NoConfidence => Node::leaf(label, "always no confidence"),
+    }
+}
+
+fn anchor(label: &str, anchor: &Nullable<conway::Anchor>) -> Option<Node> {
+    match anchor {
+        Nullable::Some(anchor) => Some(
+            Node::new(label)
+                .push(Node::leaf("url", &anchor.url))
+                .push(Node::leaf("content hash", anchor.content_hash)),
+        ),
+        _ => None,
+    }
+}
+
+fn conway_certificate(certificate: &conway::Certificate) -> Node {
+    use conway::Certificate::*;
+    match certificate {
+        StakeRegistration(cred) => {
+            Node::new("stake registration").push(credential("credential", cred))
+        }
+        StakeDeregistration(cred) => {
+            Node::new("stake deregistration").push(credential("credential", cred))
+        }
+        StakeDelegation(cred, pool) => Node::new("stake delegation")
+            .push(credential("credential", cred))
+            .push(Node::leaf("pool", pool)),
+        PoolRegistration {
+            operator,
+            vrf_keyhash,
+            pledge,
+            cost,
+            margin,
+            reward_account,
+            pool_owners,
+            relays,
+            pool_metadata,
+        } => pool_registration(
+            operator,
+            vrf_keyhash,
+            *pledge,
+            *cost,
+            margin,
+            reward_account,
+            pool_owners,
+            relays,
+            pool_metadata,
+        ),
+        PoolRetirement(pool, epoch) => Node::new("pool retirement")
+            .push(Node::leaf("pool", pool))
+            .push(Node::leaf("epoch", epoch)),
+        Reg(cred, deposit) => Node::new("registration")
+            .push(credential("credential", cred))
+            .push(Node::leaf("deposit", lovelace(*deposit))),
+        UnReg(cred, refund) => Node::new("unregistration")
+            .push(credential("credential", cred))
+            .push(Node::leaf("refund", lovelace(*refund))),
+        VoteDeleg(cred, d) => Node::new("vote delegation")
+            .push(credential("credential", cred))
+            .push(drep("drep", d)),
+        StakeVoteDeleg(cred, pool, d) => Node::new("stake and vote delegation")
+            .push(credential("credential", cred))
+            .push(Node::leaf("pool", pool))
+            .push(drep("drep", d)),
+        StakeRegDeleg(cred, pool, deposit) => Node::new("stake registration and delegation")
+            .push(credential("credential", cred))
+            .push(Node::leaf("pool", pool))
+            .push(Node::leaf("deposit", lovelace(*deposit))),
+        VoteRegDeleg(cred, d, deposit) => Node::new("vote registration and delegation")
+            .push(credential("credential", cred))
+            .push(drep("drep", d))
+            .push(Node::leaf("deposit", lovelace(*deposit))),
+        StakeVoteRegDeleg(cred, pool, d, deposit) => {
+            Node::new("stake and vote registration and delegation")
+                .push(credential("credential", cred))
+                .push(Node::leaf("pool", pool))
+                .push(drep("drep", d))
+                .push(Node::leaf("deposit", lovelace(*deposit)))
+        }
+        AuthCommitteeHot(cold, hot) => Node::new("committee hot key authorization")
+            .push(credential("cold credential", cold))
+            .push(credential("hot credential", hot)),
+        ResignCommitteeCold(cold, a) => Node::new("committee cold key resignation")
+            .push(credential("cold credential", cold))
+            .push_maybe(anchor("anchor", a)),
+        RegDRepCert(cred, deposit, a) => Node::new("drep registration")
+            .push(credential("credential", cred))
+            .push(Node::leaf("deposit", lovelace(*deposit)))
+            .push_maybe(anchor("anchor", a)),
+        UnRegDRepCert(cred, refund) => Node::new("drep unregistration")
+            .push(credential("credential", cred))
+            .push(Node::leaf("refund", lovelace(*refund))),
+        UpdateDRepCert(cred, a) => Node::new("drep update")
+            .push(credential("credential", cred))
+            .push_maybe(anchor("anchor", a)),
+    }
+}
+
+fn voter(voter: &conway::Voter) -> String {
+    match voter {
+        conway::Voter::ConstitutionalCommitteeKey(hash) => format!("committee key hash {hash}"),
+        conway::Voter::ConstitutionalCommitteeScript(hash) => {
+            format!("committee script hash {hash}")
+        }
+        conway::Voter::DRepKey(hash) => format!("drep key hash {hash}"),
+        conway::Voter::DRepScript(hash) => format!("drep script hash {hash}"),
+        conway::Voter::StakePoolKey(hash) => format!("pool {hash}"),
+    }
+}
+
+fn gov_action_id(id: &conway::GovActionId) -> String {
+    format!("{}#{}", id.transaction_id, id.action_index)
+}
+
+fn voting_procedures(procedures: &conway::VotingProcedures) -> Node {
+    Node::list(
+        "voting procedures",
+        procedures.iter().map(|(v, votes)| {
+            Node::list(
+                "voter",
+                votes.iter().map(|(action, procedure)| {
+                    Node::new("vote")
+                        .value(format!("{:?}", procedure.vote))
+                        .push(Node::leaf("action", gov_action_id(action)))
+                        .push_maybe(anchor("anchor", &procedure.anchor))
+                }),
+            )
+            .value(voter(v))
+        }),
+    )
+}
+
+fn previous_action(id: &Nullable<conway::GovActionId>) -> Node {
+    match id {
+        Nullable::Some(id) => Node::leaf("previous action", gov_action_id(id)),
+        _ => Node::leaf("previous action", "none"),
+    }
+}
+
+fn gov_action(action: &conway::GovAction) -> Node {
+    use conway::GovAction::*;
+    match action {
+        ParameterChange(previous, update, guardrail) => Node::new("parameter change")
+            .push(previous_action(previous))
+            .push(Node::leaf("update", format!("{update:?}")))
+            .push_maybe(match guardrail {
+                Nullable::Some(hash) => Some(Node::leaf("guardrail script", hash)),
+                _ => None,
+            }),
+        HardForkInitiation(previous, (major, minor)) => Node::new("hard fork initiation")
+            .push(previous_action(previous))
+            .push(Node::leaf("protocol version", format!("{major}.{minor}"))),
+        TreasuryWithdrawals(withdrawals, guardrail) => Node::new("treasury withdrawals")
+            .push(Node::list(
+                "withdrawals",
+                withdrawals
+                    .iter()
+                    .map(|(account, coin)| Node::leaf(address(account), lovelace(*coin))),
+            ))
+            .push_maybe(match guardrail {
+                Nullable::Some(hash) => Some(Node::leaf("guardrail script", hash)),
+                _ => None,
+            }),
+        NoConfidence(previous) => Node::new("no confidence").push(previous_action(previous)),
+        UpdateCommittee(previous, removed, added, threshold) => Node::new("update committee")
+            .push(previous_action(previous))
+            .push(Node::list(
+                "removed",
+                removed.iter().map(|cred| credential("member", cred)),
+            ))
+            .push(Node::list(
+                "added",
+                added.iter().map(|(cred, epoch)| {
+                    credential("member", cred).push(Node::leaf("until epoch", epoch))
+                }),
+            ))
+            .push(Node::leaf(
+                "threshold",
+                format!("{}/{}", threshold.numerator, threshold.denominator),
+            )),
+        NewConstitution(previous, constitution) => Node::new("new constitution")
+            .push(previous_action(previous))
+            .push(
+                Node::new("anchor")
+                    .push(Node::leaf("url", &constitution.anchor.url))
+                    .push(Node::leaf("content hash", constitution.anchor.content_hash)),
+            )
+            .push_maybe(match &constitution.guardrail_script {
+                Nullable::Some(hash) => Some(Node::leaf("guardrail script", hash)),
+                _ => None,
+            }),
+        Information => Node::new("information"),
+    }
+}
+
+fn proposal_procedure((i, proposal): (usize, &conway::ProposalProcedure)) -> Node {
+    Node::new(format!("#{i}"))
+        .push(Node::leaf("deposit", lovelace(proposal.deposit)))
+        .push(Node::leaf(
+            "return address",
+            address(&proposal.reward_account),
+        ))
+        .push(gov_action(&proposal.gov_action))
+        .push(
+            Node::new("anchor")
+                .push(Node::leaf("url", &proposal.anchor.url))
+                .push(Node::leaf("content hash", proposal.anchor.content_hash)),
+        )
+}
+
+fn alonzo_update(update: &alonzo::Update) -> Node {
+    Node::new("update")
+        .push(Node::leaf("epoch", update.epoch))
+        .push(Node::list(
+            "proposals",
+            update
+                .proposed_protocol_parameter_updates
+                .iter()
+                .map(|(genesis, params)| Node::leaf(genesis.to_string(), format!("{params:?}"))),
+        ))
+}
+
+fn babbage_update(update: &babbage::Update) -> Node {
+    Node::new("update")
+        .push(Node::leaf("epoch", update.epoch))
+        .push(Node::list(
+            "proposals",
+            update
+                .proposed_protocol_parameter_updates
+                .iter()
+                .map(|(genesis, params)| Node::leaf(genesis.to_string(), format!("{params:?}"))),
+        ))
+}
+
+fn metadatum(label: impl Into<String>, datum: &Metadatum) -> Node {
+    match datum {
+        Metadatum::Int(i) => Node::leaf(label, i128::from(i.0)),
+        Metadatum::Bytes(bytes) => Node::leaf(label, format!("0x{bytes}")),
+        Metadatum::Text(s) => Node::leaf(label, format!("{s:?}")),
+        Metadatum::Array(items) => Node::list(
+            label,
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| metadatum(format!("#{i}"), item)),
+        ),
+        Metadatum::Map(entries) => Node::list(label, metadatum_entries(entries)),
+    }
+}
+
+fn metadatum_entries(entries: &KeyValuePairs<Metadatum, Metadatum>) -> Vec<Node> {
+    entries
+        .iter()
+        .map(|(key, value)| {
+            let key = match key {
+                Metadatum::Int(i) => i128::from(i.0).to_string(),
+                Metadatum::Text(s) => format!("{s:?}"),
+                Metadatum::Bytes(bytes) => format!("0x{bytes}"),
+                other => format!("{other:?}"),
+            };
+
+            metadatum(key, value)
+        })
+        .collect()
+}
+
+fn metadata(metadata: &KeyValuePairs<u64, Metadatum>) -> Node {
+    Node::list(
+        "metadata",
+        metadata
+            .iter()
+            .map(|(label, datum)| metadatum(label.to_string(), datum)),
+    )
+}
+
+fn auxiliary_data(aux: &Nullable<alonzo::AuxiliaryData>) -> Option<Node> {
+    let aux = match aux {
+        Nullable::Some(aux) => aux,
+        _ => return None,
+    };
+
+    let node = Node::new("auxiliary data");
+
+    Some(match aux {
+        alonzo::AuxiliaryData::Shelley(m) => node.push(metadata(m)),
+        alonzo::AuxiliaryData::ShelleyMa(ma) => node
+            .push(metadata(&ma.transaction_metadata))
+            .push_maybe(ma.auxiliary_scripts.as_ref().map(|s| native_scripts(s))),
+        alonzo::AuxiliaryData::PostAlonzo(data) => node
+            .push_maybe(data.metadata.as_ref().map(metadata))
+            .push_maybe(data.native_scripts.as_ref().map(|s| native_scripts(s)))
+            .push_maybe(
+                data.plutus_scripts
+                    .as_ref()
+                    .map(|s| plutus_scripts("plutus v1 scripts", s)),
+            ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::ledger::traverse::Era;
+
+    use super::*;
+
+    #[test]
+    fn relays_show_their_address() {
+        let ipv4 = Relay::SingleHostAddr(
+            Nullable::Some(3001),
+            Nullable::Some(vec![192, 168, 0, 1].into()),
+            Nullable::Null,
+        );
+        let mut ip = [0; 16];
+        ip[..2].copy_from_slice(&[0x20, 0x01]);
+        ip[15] = 1;
+        let ipv6 = Relay::SingleHostAddr(
+            Nullable::Some(3001),
+            Nullable::Null,
+            Nullable::Some(ip.to_vec().into()),
+        );
+
+        assert_eq!(relay(0, &ipv4).value.unwrap(), "192.168.0.1:3001");
+        assert_eq!(relay(0, &ipv6).value.unwrap(), "[2001::1]:3001");
+    }
+
+    #[test]
+    fn byron_txs_show_their_witnesses() {
+        // [[inputs], [outputs], {}] followed by a public key witness
+        let payload = hex::decode("82838080a0818200d818478242aabb42ccdd").unwrap();
+
+        let tx = MultiEraTx::decode_for_era(Era::Byron, &payload).unwrap();
+        let tree = tx.tree(&Utxos::default(), None);
+        let witnesses = tree
+            .children
+            .iter()
+            .find(|node| node.label == "witnesses")
+            .unwrap();
+
+        assert_eq!(
+            witnesses.children,
+            [Node::leaf("#0", "public key")
+                .push(Node::leaf("key", "aabb"))
+                .push(Node::leaf("signature", "ccdd"))]
+        );
+
+        // Without them there's nothing to show
+        let tx = MultiEraTx::decode_for_era(Era::Byron, &payload[1..5]).unwrap();
+        let tree = tx.tree(&Utxos::default(), None);
+
+        assert!(tree.children.iter().all(|node| node.label != "witnesses"));
+    }
+}
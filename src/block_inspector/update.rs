@@ -1,36 +1,272 @@
-use iced::{widget::text_editor, Task};
+use std::sync::Arc;
 
-use super::state::{MultiEraTx, State};
+use iced::{clipboard, widget::text_editor, Task};
+
+use crate::{
+    blueprint::Blueprint,
+    encoding::{likely_encoding, looks_text_envelope, Contents, Encoding, TextEnvelope},
+    tree,
+};
+
+use super::{
+    evaluate::evaluate,
+    resolve::resolve,
+    state::{DecodeError, Decoded, MultiEraTx, State, Utxos},
+    summary::{Param, TxSummary},
+    RedeemerEvaluation,
+};
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    TxDecoded(Box<Option<MultiEraTx>>),
-    TxCborChanged(text_editor::Action),
+    Decoded(Box<Result<Decoded, DecodeError>>),
+    CborChanged(text_editor::Action),
+    SelectTx(Option<usize>),
+    SelectAttempt(usize),
+    ToggleNode(tree::Path),
+    EndpointChanged(String),
+    ApiKeyChanged(String),
+    Resolve,
+    Resolved(Result<Utxos, String>),
+    ParamChanged(Param, String),
+    EvaluateScripts,
+    ScriptsEvaluated(Result<Vec<RedeemerEvaluation>, String>),
+    Copy(String),
 }
 
 impl State {
+    /// An inspector already showing `cbor`, for tools that hand txs over.
+    pub fn from_cbor(cbor: &[u8]) -> Self {
+        let mut state = Self {
+            cbor: text_editor::Content::with_text(&hex::encode(cbor)),
+            ..Default::default()
+        };
+
+        let _ = state.update(Message::Decoded(Box::new(Decoded::decode(cbor))));
+
+        state
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         use Message::*;
         match message {
-            TxDecoded(tx) => {
-                self.transaction = *tx;
+            Decoded(decoded) => {
+                self.clear();
+
+                match *decoded {
+                    Ok(super::Decoded::Block(block)) => self.block = Some(block),
+                    Ok(super::Decoded::Tx(tx, summary)) => {
+                        self.transaction = Some(tx);
+                        self.summary = summary;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+
+                self.build_tree();
+
+                Task::none()
+            }
+            CborChanged(action) => {
+                self.cbor.perform(action);
+
+                let cbor = self.cbor.text();
+
+                if cbor.trim().is_empty() {
+                    self.clear();
+
+                    return Task::none();
+                }
+
+                Task::perform(decode(cbor), Decoded)
+            }
+            SelectTx(selected) => {
+                let block_tx = selected.and_then(|i| self.block.as_ref()?.transactions.get(i));
+
+                self.selected_tx = selected;
+                self.transaction =
+                    block_tx.and_then(|tx| MultiEraTx::decode_for_era(tx.era, &tx.cbor).ok());
+                self.summary = block_tx
+                    .and_then(|tx| TxSummary::new(tx.era, &tx.cbor))
+                    .map(Box::new);
+                self.evaluation = None;
+                self.collapsed.clear();
+                self.build_tree();
+
+                Task::none()
+            }
+            SelectAttempt(i) => {
+                self.selected_attempt = Some(i);
+
+                Task::none()
+            }
+            ToggleNode(path) => {
+                if !self.collapsed.remove(&path) {
+                    self.collapsed.insert(path);
+                }
+
+                Task::none()
+            }
+            EndpointChanged(endpoint) => {
+                self.endpoint = endpoint;
+
+                Task::none()
+            }
+            ApiKeyChanged(api_key) => {
+                self.api_key = api_key;
+
+                Task::none()
+            }
+            Resolve => {
+                let Some(tx) = &self.transaction else {
+                    return Task::none();
+                };
+
+                let inputs = tx.resolvable_inputs();
+
+                if inputs.is_empty() {
+                    return Task::none();
+                }
+
+                self.resolving = true;
+                self.resolve_error = None;
+
+                Task::perform(
+                    resolve(self.endpoint.clone(), self.api_key.clone(), inputs),
+                    Resolved,
+                )
+            }
+            Resolved(result) => {
+                self.resolving = false;
+
+                match result {
+                    Ok(utxos) => {
+                        self.utxos.extend(utxos);
+                        self.build_tree();
+                    }
+                    Err(e) => self.resolve_error = Some(e),
+                }
 
                 Task::none()
             }
-            TxCborChanged(action) => {
-                self.tx_cbor.perform(action);
+            ParamChanged(param, value) => {
+                self.params.set(param, value);
+
+                Task::none()
+            }
+            EvaluateScripts => {
+                // Evaluation needs the tx's original bytes, the decoded tx
+                // wouldn't re-encode to the same script context
+                let cbor = match (&self.block, self.selected_tx) {
+                    (Some(block), Some(i)) => block.transactions.get(i).map(|tx| tx.cbor.clone()),
+                    _ => cbor_of(&self.cbor.text()).ok(),
+                };
+
+                let Some(cbor) = cbor else {
+                    return Task::none();
+                };
+
+                let params = match self.params.evaluation() {
+                    Ok(params) => params,
+                    Err(e) => {
+                        self.evaluation = Some(Err(e));
 
-                let cbor = self.tx_cbor.text();
+                        return Task::none();
+                    }
+                };
 
-                Task::perform(tx_decode(cbor), TxDecoded)
+                self.evaluating = true;
+                self.evaluation = None;
+
+                Task::perform(evaluate(cbor, self.utxos.clone(), params), ScriptsEvaluated)
+            }
+            ScriptsEvaluated(result) => {
+                self.evaluating = false;
+                self.evaluation = Some(result);
+
+                Task::none()
             }
+            Copy(s) => clipboard::write(s),
         }
     }
+
+    fn clear(&mut self) {
+        self.block = None;
+        self.selected_tx = None;
+        self.transaction = None;
+        self.tree = None;
+        self.summary = None;
+        self.error = None;
+        self.selected_attempt = None;
+        // Outputs resolved for the previous input would annotate this one's
+        self.utxos.clear();
+        self.resolve_error = None;
+        self.evaluation = None;
+        self.collapsed.clear();
+    }
+
+    pub fn set_blueprint(&mut self, blueprint: Option<Arc<Blueprint>>) {
+        self.blueprint = blueprint;
+        self.build_tree();
+    }
+
+    fn build_tree(&mut self) {
+        self.tree = self
+            .transaction
+            .as_ref()
+            .map(|tx| tx.tree(&self.utxos, self.blueprint.as_deref()));
+    }
 }
 
-async fn tx_decode(cbor: String) -> Box<Option<MultiEraTx>> {
-    hex::decode(cbor.trim())
-        .ok()
-        .and_then(|b| MultiEraTx::decode(&b).ok())
+async fn decode(cbor: String) -> Box<Result<Decoded, DecodeError>> {
+    cbor_of(&cbor)
+        .and_then(|bytes| Decoded::decode(&bytes))
         .into()
 }
+
+/// The pasted bytes, in any encoding but plain text, or in a TextEnvelope
+/// holding a tx.
+fn cbor_of(input: &str) -> Result<Vec<u8>, DecodeError> {
+    let input = input.trim();
+
+    if !looks_text_envelope(input) {
+        return match likely_encoding(input) {
+            Encoding::UTF8 => Err(DecodeError::Encoding(
+                "Expected hex, base64, bech32, base58 or a TextEnvelope".to_string(),
+            )),
+            encoding => encoding.decode(input).map_err(DecodeError::Encoding),
+        };
+    }
+
+    let envelope = TextEnvelope::parse(input).map_err(DecodeError::TextEnvelope)?;
+
+    match envelope.contents() {
+        Contents::Tx | Contents::Other => envelope.decode().map_err(DecodeError::TextEnvelope),
+        Contents::Key | Contents::Script => Err(DecodeError::TextEnvelope(format!(
+            "A {} isn't a block or a tx",
+            envelope.kind
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine;
+
+    use super::*;
+
+    #[test]
+    fn txs_can_be_pasted_in_any_encoding() {
+        // A body and witnesses with nothing in them, valid and with no metadata
+        let cbor = hex::decode("84a0a0f5f6").unwrap();
+        let envelope = TextEnvelope::new("Witnessed Tx ConwayEra", "", &cbor);
+
+        for input in [
+            hex::encode(&cbor),
+            base64::engine::general_purpose::STANDARD.encode(&cbor),
+            envelope.to_json(),
+        ] {
+            assert_eq!(cbor_of(&input).unwrap(), cbor, "{input}");
+        }
+
+        assert!(cbor_of("not a tx").is_err());
+    }
+}
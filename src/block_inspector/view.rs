@@ -1,107 +1,538 @@
 use iced::{
     font, padding,
-    widget::{column, container, row, scrollable, text, text_editor},
-    Border, Element,
+    widget::{
+        button, column, container, rich_text, row, scrollable, span, text, text_editor, text_input,
+        Column,
+    },
+    Alignment::Center,
+    Border, Color, Element, Font,
     Length::Fill,
     Theme,
 };
-use pallas::ledger::{addresses::Address, primitives::conway};
 
-use super::{Message, MultiEraTx, State};
+use iced_font_awesome::fa_icon_solid;
+
+use crate::{copyable_text::copyable_text, tree::tree};
+
+use super::{
+    furthest_attempt, Attempt, Coverage, DecodeError, Message, Param, RedeemerEvaluation, State,
+    TxSummary, Witnesses,
+};
+
+const RED: Color = Color::from_rgb(1.0, 0.0, 0.0);
+const GREEN: Color = Color::from_rgb(0.0, 0.7, 0.0);
+
+/// Bytes shown per line, and lines shown around a decoding failure
+const BYTES_PER_LINE: usize = 16;
+const CONTEXT_LINES: usize = 3;
 
 impl State {
-    pub fn view(&self) -> Element<Message> {
+    pub fn view(&self) -> Element<'_, Message> {
         row![
-            column![text_editor(&self.tx_cbor)
-                .placeholder("tx cbor...")
-                .on_action(Message::TxCborChanged)
-                .height(Fill)
-                .wrapping(text::Wrapping::Glyph)
-                .style(|theme: &Theme, status| {
-                    let mut defaults = text_editor::default(theme, status);
-
-                    defaults.border.radius = 4.0.into();
-
-                    defaults
-                })],
-            container(
-                scrollable(column![match &self.transaction {
-                    Some(tx) => render_tx(tx),
-                    None => text("nothing to decode").into(),
-                }])
-                .width(Fill)
+            column![
+                text_editor(&self.cbor)
+                    .placeholder("tx or block cbor...")
+                    .on_action(Message::CborChanged)
+                    .height(Fill)
+                    .wrapping(text::Wrapping::Glyph)
+                    .style(|theme: &Theme, status| {
+                        let mut defaults = text_editor::default(theme, status);
+
+                        defaults.border.radius = 4.0.into();
+
+                        defaults
+                    }),
+                row![
+                    text_input(
+                        "utxo rpc endpoint, e.g. http://localhost:50051",
+                        &self.endpoint
+                    )
+                    .on_input(Message::EndpointChanged),
+                    text_input("api key", &self.api_key)
+                        .on_input(Message::ApiKeyChanged)
+                        .secure(true)
+                        .width(160),
+                    button(text(if self.resolving {
+                        "Resolving..."
+                    } else {
+                        "Resolve inputs"
+                    }))
+                    .on_press_maybe(
+                        (self.transaction.is_some()
+                            && !self.resolving
+                            && !self.endpoint.trim().is_empty())
+                        .then_some(Message::Resolve)
+                    )
+                ]
+                .spacing(5)
+            ]
+            .push_maybe(
+                self.resolve_error
+                    .as_ref()
+                    .map(|e| text(format!("Could not resolve inputs: {e}")).color(RED))
             )
-            .width(Fill)
-            .height(Fill)
-            .style(|theme: &Theme| {
-                let palette = theme.extended_palette();
-
-                container::Style {
-                    border: Border {
-                        width: 1.0,
-                        radius: 4.0.into(),
-                        color: palette.background.strong.color,
-                    },
-                    ..Default::default()
-                }
-            })
-            .padding(10)
+            .spacing(5),
+            container(scrollable(self.view_decoded().width(Fill)).width(Fill))
+                .width(Fill)
+                .height(Fill)
+                .style(|theme: &Theme| {
+                    let palette = theme.extended_palette();
+
+                    container::Style {
+                        border: Border {
+                            width: 1.0,
+                            radius: 4.0.into(),
+                            color: palette.background.strong.color,
+                        },
+                        ..Default::default()
+                    }
+                })
+                .padding(10)
         ]
         .height(Fill)
         .padding(padding::top(30).left(10).right(10).bottom(10))
         .spacing(10)
         .into()
     }
-}
 
-fn render_tx(tx: &MultiEraTx) -> Element<Message> {
-    match tx {
-        MultiEraTx::AlonzoCompatible(tx, era) => column![
-            text("Alonzo Compatible"),
-            text(format!("Era: {:?}", era)),
-            text(format!("{:?}", tx))
-        ]
-        .into(),
-        MultiEraTx::Babbage(tx) => column![text("Babbage"), text(format!("{:?}", tx))].into(),
-        MultiEraTx::Byron(tx) => column![text("Byron"), text(format!("{:?}", tx))].into(),
-        MultiEraTx::Conway(tx) => column![
-            text("Conway").size(18).font(font::Font {
-                weight: font::Weight::Bold,
-                ..Default::default()
-            }),
-            text(format!(
-                "total inputs = {}",
-                tx.transaction_body.inputs.len()
-            )),
-            column(tx.transaction_body.inputs.iter().map(|input| {
+    fn view_decoded(&self) -> Column<'_, Message> {
+        if let Some(error) = &self.error {
+            return self.view_error(error);
+        }
+
+        match (&self.block, self.selected_tx, &self.transaction) {
+            (Some(block), None, _) => column![
+                tree(&block.header_tree(), &self.collapsed, Message::ToggleNode),
+                column(block.transactions.iter().enumerate().map(|(i, tx)| {
+                    button(
+                        row![
+                            text(format!("#{i}")),
+                            text(tx.hash.to_string()).font(Font::MONOSPACE).size(14)
+                        ]
+                        .spacing(8),
+                    )
+                    .style(button::text)
+                    .padding(0)
+                    .on_press(Message::SelectTx(Some(i)))
+                    .into()
+                }))
+                .spacing(4)
+            ]
+            .spacing(10),
+            (Some(_), Some(i), _) => column![button(
+                row![fa_icon_solid("arrow-left").size(14.), text("block")].spacing(8)
+            )
+            .style(button::text)
+            .padding(0)
+            .on_press(Message::SelectTx(None))]
+            .push_maybe(
+                self.summary
+                    .as_ref()
+                    .map(|summary| self.view_summary(summary)),
+            )
+            .push(match &self.tree {
+                Some(root) => tree(root, &self.collapsed, Message::ToggleNode),
+                None => text(format!("failed to decode tx #{i}")).into(),
+            })
+            .spacing(10),
+            (None, _, Some(_)) => column![]
+                .push_maybe(
+                    self.summary
+                        .as_ref()
+                        .map(|summary| self.view_summary(summary)),
+                )
+                .push_maybe(
+                    self.tree
+                        .as_ref()
+                        .map(|root| tree(root, &self.collapsed, Message::ToggleNode)),
+                )
+                .spacing(10),
+            (None, _, None) => column![text("nothing to decode")],
+        }
+    }
+
+    fn view_summary<'a>(&'a self, summary: &'a TxSummary) -> Element<'a, Message> {
+        let field = |label, value: Element<'a, Message>| row![text(label).width(160), value];
+
+        let mut sizes = format!("{} bytes, body {}", summary.size, summary.body_size);
+
+        if let Some(witness_size) = summary.witness_size {
+            sizes.push_str(&format!(", witnesses {witness_size}"));
+        }
+
+        if let Some(aux_size) = summary.aux_size {
+            sizes.push_str(&format!(", aux data {aux_size}"));
+        }
+
+        let mut fees = column![field(
+            "fee",
+            text(
+                summary
+                    .fee
+                    .map_or("implicit".to_string(), |fee| format!("{fee} lovelace"))
+            )
+            .into()
+        )]
+        .spacing(5);
+
+        // Byron fees are implicit and priced differently
+        if summary.fee.is_some() {
+            fees = fees.push(match summary.min_fee(&self.params, &self.utxos) {
+                Ok(min_fee) => {
+                    let mut parts = format!("size {}", min_fee.size);
+
+                    if min_fee.scripts > 0 {
+                        parts.push_str(&format!(", scripts {}", min_fee.scripts));
+                    }
+
+                    if min_fee.reference_scripts > 0 {
+                        parts.push_str(&format!(
+                            ", reference scripts {}",
+                            min_fee.reference_scripts
+                        ));
+                    }
+
+                    let min_fee_text = text(format!("{} lovelace ({parts})", min_fee.total()));
+
+                    column![field(
+                        "min fee",
+                        if summary.fee < Some(min_fee.total()) {
+                            min_fee_text.color(RED).into()
+                        } else {
+                            min_fee_text.into()
+                        }
+                    )]
+                    .push_maybe((min_fee.unresolved > 0).then(|| {
+                        text(format!(
+                            "{} unresolved inputs may hold reference scripts that aren't counted",
+                            min_fee.unresolved
+                        ))
+                        .size(14)
+                    }))
+                    .spacing(5)
+                }
+                Err(e) => column![field("min fee", text(e).color(RED).into())],
+            });
+
+            let param = |label, value: &'a str, param| {
                 column![
-                    text(format!("index = {}", input.index)),
-                    text(format!("hash  = {}", input.transaction_id)),
+                    text(label).size(12),
+                    text_input("", value)
+                        .on_input(move |value| Message::ParamChanged(param, value))
+                        .size(14)
                 ]
+                .spacing(2)
                 .into()
+            };
+
+            fees = fees.push(
+                row([
+                    param("min fee a", &self.params.min_fee_a, Param::MinFeeA),
+                    param("min fee b", &self.params.min_fee_b, Param::MinFeeB),
+                    param("memory price", &self.params.price_mem, Param::PriceMem),
+                    param("step price", &self.params.price_steps, Param::PriceSteps),
+                    param(
+                        "ref script cost per byte",
+                        &self.params.ref_script_cost_per_byte,
+                        Param::RefScriptCostPerByte,
+                    ),
+                ])
+                .spacing(5),
+            );
+        }
+
+        column![
+            field(
+                "tx id",
+                copyable_text(&summary.hash.to_string(), Message::Copy)
+            ),
+            field("size", text(sizes).into()),
+            fees,
+            self.view_witnesses(&summary.witnesses)
+        ]
+        .push_maybe((summary.redeemers > 0).then(|| self.view_evaluation()))
+        .spacing(5)
+        .into()
+    }
+
+    fn view_evaluation(&self) -> Column<'_, Message> {
+        let unresolved = self.transaction.as_ref().map_or(0, |tx| {
+            tx.resolvable_inputs()
+                .iter()
+                .filter(|input| !matches!(self.utxos.get(input), Some(Ok(_))))
+                .count()
+        });
+
+        let mut evaluation = column![row![
+            text("scripts").width(160),
+            button(text(if self.evaluating {
+                "Evaluating..."
+            } else {
+                "Evaluate scripts"
             }))
-            .spacing(5),
+            .on_press_maybe((!self.evaluating).then_some(Message::EvaluateScripts))
+        ]
+        .align_y(Center)]
+        .push_maybe((unresolved > 0).then(|| {
             text(format!(
-                "total outputs = {}",
-                tx.transaction_body.outputs.len()
-            )),
-            column(tx.transaction_body.outputs.iter().map(|out| {
-                match out {
-                    conway::PseudoTransactionOutput::Legacy(output) => {
-                        let address = Address::from_bytes(&output.address).unwrap();
-
-                        column![text(format!("address = {}", address.to_bech32().unwrap())),].into()
-                    }
-                    conway::PseudoTransactionOutput::PostAlonzo(output) => {
-                        let address = Address::from_bytes(&output.address).unwrap();
+                "{unresolved} inputs are unresolved, scripts that look at them will fail"
+            ))
+            .size(14)
+        }))
+        .spacing(5);
 
-                        column![text(format!("address = {}", address.to_bech32().unwrap())),].into()
-                    }
-                }
+        let param = |label, value, param| {
+            column![
+                text(label).size(12),
+                text_input("", value)
+                    .on_input(move |value| Message::ParamChanged(param, value))
+                    .size(14)
+            ]
+            .spacing(2)
+            .into()
+        };
+
+        let cost_model = |version: usize| {
+            text_input(
+                &format!("plutus V{} cost model, built-in if empty", version + 1),
+                &self.params.cost_models[version],
+            )
+            .on_input(move |value| Message::ParamChanged(Param::CostModel(version), value))
+            .size(14)
+            .into()
+        };
+
+        evaluation = evaluation
+            .push(
+                row([
+                    param("max tx memory", &self.params.max_tx_mem, Param::MaxTxMem),
+                    param("max tx steps", &self.params.max_tx_steps, Param::MaxTxSteps),
+                    param("zero time", &self.params.zero_time, Param::ZeroTime),
+                    param("zero slot", &self.params.zero_slot, Param::ZeroSlot),
+                    param("slot length", &self.params.slot_length, Param::SlotLength),
+                ])
+                .spacing(5),
+            )
+            .extend((0..3).map(cost_model));
+
+        match &self.evaluation {
+            Some(Ok(redeemers)) => {
+                evaluation = evaluation.extend(redeemers.iter().map(view_redeemer));
+            }
+            Some(Err(e)) => evaluation = evaluation.push(text(e).color(RED)),
+            None => {}
+        }
+
+        evaluation
+    }
+
+    fn view_witnesses<'a>(&self, witnesses: &'a Witnesses) -> Column<'a, Message> {
+        let entry = |status: &'a str, color, key_hash: String, description: String| {
+            row![
+                text(status).color(color).width(160),
+                text(key_hash).font(Font::MONOSPACE).size(14),
+                text(description).size(14)
+            ]
+            .spacing(8)
+            .into()
+        };
+
+        let signatures = witnesses.witnesses.iter().map(|witness| {
+            entry(
+                if witness.valid {
+                    "valid signature"
+                } else {
+                    "invalid signature"
+                },
+                if witness.valid { GREEN } else { RED },
+                witness.key_hash.to_string(),
+                if witness.bootstrap {
+                    "bootstrap witness".to_string()
+                } else {
+                    "vkey witness".to_string()
+                },
+            )
+        });
+
+        let (requirements, unresolved) = witnesses.requirements(&self.utxos);
+
+        let requirements = requirements.into_iter().map(|requirement| {
+            let (status, color) = match witnesses.coverage(&requirement.key_hash) {
+                Coverage::Covered => ("covered", GREEN),
+                Coverage::BadSignature => ("bad signature", RED),
+                Coverage::Missing => ("missing", RED),
+            };
+
+            entry(
+                status,
+                color,
+                requirement.key_hash.to_string(),
+                requirement.reason,
+            )
+        });
+
+        column![
+            text("signatures"),
+            column(signatures).spacing(2),
+            text("required witnesses"),
+            column(requirements).spacing(2)
+        ]
+        .push_maybe((unresolved > 0).then(|| {
+            text(format!(
+                "{unresolved} spent inputs are unresolved, resolve them to check their payment \
+                 credentials"
+            ))
+            .size(14)
+        }))
+        .spacing(5)
+    }
+
+    fn view_error<'a>(&self, error: &'a DecodeError) -> Column<'a, Message> {
+        let DecodeError::Cbor { cbor, attempts } = error else {
+            return column![text(error.to_string()).color(RED)];
+        };
+
+        let selected = self.selected_attempt.or(furthest_attempt(attempts));
+
+        column![
+            text("Not a block or a transaction of any known era").color(RED),
+            column(attempts.iter().enumerate().map(|(i, attempt)| {
+                button(
+                    row![
+                        text(&attempt.label).width(220),
+                        text(&attempt.error).font(Font::MONOSPACE).size(14)
+                    ]
+                    .spacing(8),
+                )
+                .style(if selected == Some(i) {
+                    button::secondary
+                } else {
+                    button::text
+                })
+                .padding(2)
+                .on_press(Message::SelectAttempt(i))
+                .into()
             }))
-            .spacing(5),
+            .spacing(4)
         ]
+        .push_maybe(
+            selected
+                .and_then(|i| attempts.get(i))
+                .and_then(|attempt| cbor_context(cbor, attempt)),
+        )
         .spacing(10)
-        .into(),
     }
 }
+
+fn view_redeemer(redeemer: &RedeemerEvaluation) -> Element<'_, Message> {
+    let (status, color) = match &redeemer.result {
+        Ok(()) if redeemer.over_budget() => ("over budget", RED),
+        Ok(()) => ("succeeded", GREEN),
+        Err(_) => ("failed", RED),
+    };
+
+    let units = |label, used: Option<u64>, declared: u64| {
+        let units = text(match used {
+            Some(used) => format!("{label} {used} / {declared}"),
+            None => format!("{label} ? / {declared}"),
+        })
+        .font(Font::MONOSPACE)
+        .size(14);
+
+        if used > Some(declared) {
+            units.color(RED)
+        } else {
+            units
+        }
+    };
+
+    column![row![
+        text(status).color(color).width(160),
+        text(&redeemer.purpose).width(100),
+        units(
+            "mem",
+            redeemer.used.map(|used| used.mem),
+            redeemer.declared.mem
+        ),
+        units(
+            "steps",
+            redeemer.used.map(|used| used.steps),
+            redeemer.declared.steps
+        )
+    ]
+    .spacing(8)]
+    .push(
+        column![]
+            .push_maybe(
+                redeemer
+                    .result
+                    .as_ref()
+                    .err()
+                    .map(|e| text(e).font(Font::MONOSPACE).size(14).color(RED)),
+            )
+            .extend(redeemer.logs.iter().map(|log| {
+                text(format!("trace: {log}"))
+                    .font(Font::MONOSPACE)
+                    .size(14)
+                    .into()
+            }))
+            .padding(padding::left(168))
+            .spacing(2),
+    )
+    .spacing(2)
+    .into()
+}
+
+/// A hex dump of the lines around where an attempt failed, with the offending
+/// byte highlighted.
+fn cbor_context<'a>(cbor: &[u8], attempt: &Attempt) -> Option<Element<'a, Message>> {
+    let position = attempt.position?;
+    let line = position / BYTES_PER_LINE;
+    let first = line.saturating_sub(CONTEXT_LINES);
+    let last = (line + CONTEXT_LINES).min(cbor.len().div_ceil(BYTES_PER_LINE));
+
+    let bold = Font {
+        weight: font::Weight::Bold,
+        ..Font::MONOSPACE
+    };
+
+    let lines = (first..=last).filter_map(|line| {
+        let start = line * BYTES_PER_LINE;
+        let end = (start + BYTES_PER_LINE).min(cbor.len());
+
+        // Running out of bytes is reported one past the last byte, which is
+        // marked at the end of the last line rather than on an empty one
+        if start >= end {
+            return None;
+        }
+
+        let bytes = |range: std::ops::Range<usize>| {
+            range
+                .map(|i| format!("{:02x} ", cbor[i]))
+                .collect::<String>()
+        };
+
+        let mut spans = vec![span(format!("{start:08x}  ")).color(Color::from_rgb(0.5, 0.5, 0.5))];
+
+        if (start..end).contains(&position) {
+            spans.push(span(bytes(start..position)));
+            spans.push(span(bytes(position..position + 1)).color(RED).font(bold));
+            spans.push(span(bytes(position + 1..end)));
+        } else {
+            spans.push(span(bytes(start..end)));
+
+            if position == end && end == cbor.len() {
+                spans.push(span("<end of input>").color(RED).font(bold));
+            }
+        }
+
+        Some(rich_text(spans).font(Font::MONOSPACE).size(14).into())
+    });
+
+    Some(
+        column![text(format!("{} at byte {position}", attempt.label))]
+            .extend(lines)
+            .spacing(2)
+            .into(),
+    )
+}
@@ -0,0 +1,379 @@
+use cryptoxide::{ed25519, hashing::sha3_256};
+use pallas::{
+    crypto::hash::{Hash, Hasher},
+    ledger::{
+        addresses::{Address, StakePayload},
+        primitives::{alonzo, conway, StakeCredential, TransactionInput},
+        traverse::{self, MultiEraCert},
+    },
+};
+
+use super::Utxos;
+
+/// A vkey or bootstrap witness, checked against the tx id it should sign.
+#[derive(Debug, Clone)]
+pub struct Witness {
+    pub bootstrap: bool,
+    pub key_hash: Hash<28>,
+    pub valid: bool,
+}
+
+/// A key hash the ledger expects a witness for, and why.
+#[derive(Debug, Clone)]
+pub struct Requirement {
+    pub reason: String,
+    pub key_hash: Hash<28>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Coverage {
+    Covered,
+    BadSignature,
+    Missing,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Witnesses {
+    pub witnesses: Vec<Witness>,
+    /// Requirements known from the tx alone
+    pub requirements: Vec<Requirement>,
+    /// Spent and collateral inputs, whose payment credentials are only known
+    /// once they've been resolved
+    pub spent: Vec<(&'static str, TransactionInput)>,
+}
+
+impl Witnesses {
+    pub fn new(tx: &traverse::MultiEraTx) -> Self {
+        let tx_id = tx.hash();
+
+        let vkeys = tx.vkey_witnesses().iter().map(|witness| Witness {
+            bootstrap: false,
+            key_hash: Hasher::<224>::hash(&witness.vkey),
+            valid: verify(&tx_id, &witness.vkey, &witness.signature),
+        });
+
+        let bootstraps = tx.bootstrap_witnesses().iter().map(|witness| Witness {
+            bootstrap: true,
+            key_hash: bootstrap_key_hash(witness),
+            valid: verify(&tx_id, &witness.public_key, &witness.signature),
+        });
+
+        let mut requirements: Vec<_> = tx
+            .required_signers()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|key_hash| Requirement {
+                reason: "required signer".to_string(),
+                key_hash: *key_hash,
+            })
+            .collect();
+
+        for (account, _) in tx.withdrawals_sorted_set() {
+            if let Ok(Address::Stake(address)) = Address::from_bytes(account) {
+                if let StakePayload::Stake(key_hash) = address.payload() {
+                    requirements.push(Requirement {
+                        reason: format!(
+                            "withdrawal from {}",
+                            address.to_bech32().unwrap_or_else(|_| address.to_hex())
+                        ),
+                        key_hash: *key_hash,
+                    });
+                }
+            }
+        }
+
+        for (i, cert) in tx.certs().iter().enumerate() {
+            for (kind, key_hash) in cert_key_hashes(cert) {
+                requirements.push(Requirement {
+                    reason: format!("certificate #{i} ({kind})"),
+                    key_hash,
+                });
+            }
+        }
+
+        let spent = tx
+            .inputs()
+            .into_iter()
+            .map(|input| ("input", input))
+            .chain(
+                tx.collateral()
+                    .into_iter()
+                    .map(|input| ("collateral", input)),
+            )
+            .map(|(label, input)| {
+                (
+                    label,
+                    TransactionInput {
+                        transaction_id: *input.hash(),
+                        index: input.index(),
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            witnesses: vkeys.chain(bootstraps).collect(),
+            requirements,
+            spent,
+        }
+    }
+
+    /// Every requirement, including the payment credentials of resolved
+    /// inputs, along with how many inputs are still unresolved.
+    pub fn requirements(&self, utxos: &Utxos) -> (Vec<Requirement>, usize) {
+        let mut requirements = vec![];
+        let mut unresolved = 0;
+
+        for (label, input) in &self.spent {
+            let Some(Ok(output)) = utxos.get(input) else {
+                unresolved += 1;
+
+                continue;
+            };
+
+            let address = match output {
+                conway::TransactionOutput::Legacy(output) => &output.address,
+                conway::TransactionOutput::PostAlonzo(output) => &output.address,
+            };
+
+            let reason = format!("{label} {}#{}", input.transaction_id, input.index);
+
+            match Address::from_bytes(address) {
+                Ok(Address::Shelley(address)) if !address.payment().is_script() => {
+                    requirements.push(Requirement {
+                        reason,
+                        key_hash: *address.payment().as_hash(),
+                    });
+                }
+                // Byron addresses are witnessed by bootstrap witnesses whose
+                // key hash is the address root
+                Ok(Address::Byron(address)) => {
+                    if let Ok(payload) = address.decode() {
+                        requirements.push(Requirement {
+                            reason,
+                            key_hash: payload.root,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        requirements.extend(self.requirements.iter().cloned());
+
+        (requirements, unresolved)
+    }
+
+    pub fn coverage(&self, key_hash: &Hash<28>) -> Coverage {
+        let mut matching = self.witnesses.iter().filter(|w| w.key_hash == *key_hash);
+
+        match matching.next() {
+            None => Coverage::Missing,
+            Some(first) if first.valid || matching.any(|w| w.valid) => Coverage::Covered,
+            Some(_) => Coverage::BadSignature,
+        }
+    }
+}
+
+fn verify(tx_id: &Hash<32>, vkey: &[u8], signature: &[u8]) -> bool {
+    let (Ok(vkey), Ok(signature)) = (vkey.try_into(), signature.try_into()) else {
+        return false;
+    };
+
+    ed25519::verify(tx_id.as_ref(), vkey, signature)
+}
+
+/// The root of the Byron address the witness unlocks: the hash of the
+/// address type, the extended public key and the address attributes.
+fn bootstrap_key_hash(witness: &alonzo::BootstrapWitness) -> Hash<28> {
+    // [0, [0, bytes(64)], attributes]
+    let mut root = vec![0x83, 0x00, 0x82, 0x00, 0x58, 0x40];
+
+    root.extend_from_slice(&witness.public_key);
+    root.extend_from_slice(&witness.chain_code);
+    root.extend_from_slice(&witness.attributes);
+
+    Hasher::<224>::hash(&sha3_256(&root))
+}
+
+/// Key hashes a certificate needs witnesses from. Script credentials are
+/// witnessed by their scripts, and genesis certificates by the genesis
+/// delegates, neither of which can be checked here.
+fn cert_key_hashes(cert: &MultiEraCert) -> Vec<(&'static str, Hash<28>)> {
+    let key = |kind, credential: &StakeCredential| match credential {
+        StakeCredential::AddrKeyhash(key_hash) => vec![(kind, *key_hash)],
+        StakeCredential::ScriptHash(_) => vec![],
+    };
+
+    if let Some(cert) = cert.as_alonzo() {
+        return match cert {
+            alonzo::Certificate::StakeDeregistration(credential) => {
+                key("stake deregistration", credential)
+            }
+            alonzo::Certificate::StakeDelegation(credential, _) => {
+                key("stake delegation", credential)
+            }
+            alonzo::Certificate::PoolRegistration {
+                operator,
+                pool_owners,
+                ..
+            } => std::iter::once(("pool operator", *operator))
+                .chain(pool_owners.iter().map(|owner| ("pool owner", *owner)))
+                .collect(),
+            alonzo::Certificate::PoolRetirement(pool, _) => vec![("pool retirement", *pool)],
+            _ => vec![],
+        };
+    }
+
+    let Some(cert) = cert.as_conway() else {
+        return vec![];
+    };
+
+    match cert {
+        // Only the legacy registration without a deposit goes unwitnessed
+        conway::Certificate::StakeRegistration(_) => vec![],
+        conway::Certificate::StakeDeregistration(credential) => {
+            key("stake deregistration", credential)
+        }
+        conway::Certificate::StakeDelegation(credential, _) => key("stake delegation", credential),
+        conway::Certificate::PoolRegistration {
+            operator,
+            pool_owners,
+            ..
+        } => std::iter::once(("pool operator", *operator))
+            .chain(pool_owners.iter().map(|owner| ("pool owner", *owner)))
+            .collect(),
+        conway::Certificate::PoolRetirement(pool, _) => vec![("pool retirement", *pool)],
+        conway::Certificate::Reg(credential, _) => key("stake registration", credential),
+        conway::Certificate::UnReg(credential, _) => key("stake deregistration", credential),
+        conway::Certificate::VoteDeleg(credential, _) => key("vote delegation", credential),
+        conway::Certificate::StakeVoteDeleg(credential, _, _) => {
+            key("stake and vote delegation", credential)
+        }
+        conway::Certificate::StakeRegDeleg(credential, _, _) => {
+            key("stake registration and delegation", credential)
+        }
+        conway::Certificate::VoteRegDeleg(credential, _, _) => {
+            key("stake registration and vote delegation", credential)
+        }
+        conway::Certificate::StakeVoteRegDeleg(credential, _, _, _) => {
+            key("stake registration, stake and vote delegation", credential)
+        }
+        conway::Certificate::AuthCommitteeHot(cold, _) => key("committee hot key", cold),
+        conway::Certificate::ResignCommitteeCold(cold, _) => key("committee resignation", cold),
+        conway::Certificate::RegDRepCert(drep, _, _) => key("drep registration", drep),
+        conway::Certificate::UnRegDRepCert(drep, _) => key("drep retirement", drep),
+        conway::Certificate::UpdateDRepCert(drep, _) => key("drep update", drep),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::widget::text_editor;
+    use pallas::ledger::primitives::conway::PostAlonzoTransactionOutput;
+
+    use crate::tx_builder;
+
+    use super::*;
+
+    const INPUT: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+    /// The cbor of a tx spending two inputs, signed with a single key.
+    fn signed(required_signers: &str) -> (Vec<u8>, Hash<28>) {
+        let built = tx_builder::State {
+            inputs: vec![format!("{INPUT}#0"), format!("{INPUT}#1")],
+            fee: "200000".to_string(),
+            required_signers: required_signers.to_string(),
+            signing_keys: text_editor::Content::with_text(&"07".repeat(32)),
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+
+        (built.cbor, built.signers[0])
+    }
+
+    fn witness(key_hash: Hash<28>, valid: bool) -> Witness {
+        Witness {
+            bootstrap: false,
+            key_hash,
+            valid,
+        }
+    }
+
+    #[test]
+    fn signatures_are_checked_against_the_tx_id() {
+        let other = Hash::new([9; 28]);
+        let (_, signer) = signed("");
+        let (cbor, _) = signed(&format!("{signer} {other}"));
+
+        let witnesses = Witnesses::new(&traverse::MultiEraTx::decode(&cbor).unwrap());
+
+        assert_eq!(witnesses.witnesses.len(), 1);
+        assert!(witnesses.witnesses[0].valid);
+        assert_eq!(witnesses.witnesses[0].key_hash, signer);
+
+        let required: Vec<_> = witnesses.requirements.iter().map(|r| r.key_hash).collect();
+
+        assert_eq!(required, [signer, other]);
+        assert_eq!(witnesses.coverage(&signer), Coverage::Covered);
+        assert_eq!(witnesses.coverage(&other), Coverage::Missing);
+    }
+
+    #[test]
+    fn one_good_signature_is_enough() {
+        let key_hash = Hash::new([1; 28]);
+
+        let bad = Witnesses {
+            witnesses: vec![witness(key_hash, false)],
+            ..Default::default()
+        };
+        let mixed = Witnesses {
+            witnesses: vec![witness(key_hash, false), witness(key_hash, true)],
+            ..Default::default()
+        };
+
+        assert_eq!(bad.coverage(&key_hash), Coverage::BadSignature);
+        assert_eq!(mixed.coverage(&key_hash), Coverage::Covered);
+    }
+
+    #[test]
+    fn resolved_inputs_require_their_payment_keys() {
+        let (cbor, signer) = signed("");
+        let witnesses = Witnesses::new(&traverse::MultiEraTx::decode(&cbor).unwrap());
+
+        let output = |header: u8| {
+            let mut address = vec![header];
+            address.extend_from_slice(signer.as_ref());
+
+            Ok(conway::TransactionOutput::PostAlonzo(
+                PostAlonzoTransactionOutput {
+                    address: address.into(),
+                    value: conway::Value::Coin(1),
+                    datum_option: None,
+                    script_ref: None,
+                },
+            ))
+        };
+        let input = |index| TransactionInput {
+            transaction_id: INPUT.parse().unwrap(),
+            index,
+        };
+
+        // A key address, then a script one that no key can witness
+        let utxos = Utxos::from([(input(0), output(0x61))]);
+        let (requirements, unresolved) = witnesses.requirements(&utxos);
+
+        assert_eq!(unresolved, 1);
+        assert_eq!(requirements.len(), 1);
+        assert_eq!(requirements[0].key_hash, signer);
+        assert_eq!(requirements[0].reason, format!("input {INPUT}#0"));
+
+        let utxos = Utxos::from([(input(0), output(0x71)), (input(1), output(0x71))]);
+
+        let (requirements, unresolved) = witnesses.requirements(&utxos);
+
+        assert!(requirements.is_empty());
+        assert_eq!(unresolved, 0);
+    }
+}
@@ -0,0 +1,8 @@
+mod schema;
+mod state;
+mod update;
+mod view;
+
+pub use schema::*;
+pub use state::*;
+pub use update::*;
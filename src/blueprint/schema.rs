@@ -0,0 +1,825 @@
+use std::fmt::Display;
+
+use pallas::{crypto::hash::Hash, ledger::primitives::PlutusData};
+use serde_json::{Map, Value};
+
+use crate::{
+    plutus_data::{self, constructor_index},
+    tree::Node,
+    uplc_debugger::{hashes, unwrap_flat, Version},
+};
+
+/// A CIP-57 blueprint, the `plutus.json` Aiken writes next to a project.
+#[derive(Debug, Clone)]
+pub struct Blueprint {
+    pub title: String,
+    pub version: Option<String>,
+    pub plutus_version: Version,
+    pub compiler: Option<String>,
+    pub validators: Vec<Validator>,
+    pub definitions: Map<String, Value>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Validator {
+    pub title: String,
+    pub datum: Option<Argument>,
+    pub redeemer: Option<Argument>,
+    pub parameters: Vec<Argument>,
+    /// The hash the blueprint claims the compiled code has
+    pub hash: Option<String>,
+    pub compiled: Option<Result<Compiled, String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Argument {
+    pub title: Option<String>,
+    pub schema: Value,
+}
+
+/// The compiled code as the ledger would store it.
+#[derive(Debug, Clone)]
+pub struct Compiled {
+    pub cbor: Vec<u8>,
+    pub hash: Hash<28>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Purpose {
+    Datum,
+    #[default]
+    Redeemer,
+}
+
+impl Purpose {
+    pub const ALL: [Purpose; 2] = [Purpose::Datum, Purpose::Redeemer];
+}
+
+impl Display for Purpose {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Purpose::Datum => "Datum",
+                Purpose::Redeemer => "Redeemer",
+            }
+        )
+    }
+}
+
+/// Schemas can refer to each other, and recursive types always do, so give
+/// up on chains of references that never reach an actual schema.
+const MAX_REFERENCES: usize = 64;
+
+impl Blueprint {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let json: Value = serde_json::from_str(json).map_err(|e| format!("Invalid JSON: {e}"))?;
+
+        let preamble = json
+            .get("preamble")
+            .and_then(Value::as_object)
+            .ok_or("$: missing \"preamble\"")?;
+
+        let string = |value: Option<&Value>| value.and_then(Value::as_str).map(str::to_string);
+
+        let plutus_version = match preamble.get("plutusVersion").and_then(Value::as_str) {
+            Some("v1") => Version::V1,
+            Some("v2") => Version::V2,
+            Some("v3") | None => Version::V3,
+            Some(other) => {
+                return Err(format!(
+                    "$.preamble.plutusVersion: unknown plutus version \"{other}\""
+                ))
+            }
+        };
+
+        let compiler = preamble.get("compiler").and_then(|compiler| {
+            let name = string(compiler.get("name"))?;
+
+            Some(match string(compiler.get("version")) {
+                Some(version) => format!("{name} {version}"),
+                None => name,
+            })
+        });
+
+        let validators = json
+            .get("validators")
+            .and_then(Value::as_array)
+            .ok_or("$: missing \"validators\"")?
+            .iter()
+            .enumerate()
+            .map(|(i, validator)| {
+                Validator::parse(validator, plutus_version, &format!("$.validators[{i}]"))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            title: string(preamble.get("title")).unwrap_or_default(),
+            version: string(preamble.get("version")),
+            plutus_version,
+            compiler,
+            validators,
+            definitions: json
+                .get("definitions")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Follow `$ref`s until an actual schema.
+    fn resolve<'a>(&'a self, mut schema: &'a Value) -> Result<&'a Value, String> {
+        for _ in 0..MAX_REFERENCES {
+            let Some(reference) = schema.get("$ref").and_then(Value::as_str) else {
+                return Ok(schema);
+            };
+
+            schema = definition_name(reference)
+                .and_then(|name| self.definitions.get(&name))
+                .ok_or_else(|| format!("unknown definition {reference}"))?;
+        }
+
+        Err("too many nested references".to_string())
+    }
+
+    /// A short name for the type a schema describes.
+    pub fn type_name(&self, schema: &Value) -> String {
+        let title = |schema: &Value| {
+            schema
+                .get("title")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = definition_name(reference).unwrap_or_else(|| reference.to_string());
+
+            return self.definitions.get(&name).and_then(title).unwrap_or(name);
+        }
+
+        match schema.get("dataType").and_then(Value::as_str) {
+            Some("constructor") => format!(
+                "Constr {}",
+                schema.get("index").and_then(Value::as_u64).unwrap_or(0)
+            ),
+            Some(data_type) => title(schema).unwrap_or_else(|| data_type.to_string()),
+            None => title(schema).unwrap_or_else(|| "Data".to_string()),
+        }
+    }
+
+    /// The shape of the data a schema describes, with recursive types only
+    /// expanded once.
+    pub fn schema_tree(&self, label: impl Into<String>, schema: &Value) -> Node {
+        self.schema_node(label.into(), schema, &mut vec![])
+    }
+
+    fn schema_node(&self, label: String, schema: &Value, expanding: &mut Vec<String>) -> Node {
+        let name = self.type_name(schema);
+        let reference = schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        if let Some(reference) = &reference {
+            if expanding.contains(reference) {
+                return Node::leaf(label, name);
+            }
+
+            expanding.push(reference.clone());
+        }
+
+        let node = match self.resolve(schema) {
+            Ok(schema) => {
+                let children = match alternatives(schema) {
+                    Some(alternatives) => alternatives
+                        .iter()
+                        .enumerate()
+                        .map(|(i, alternative)| {
+                            self.schema_node(field_label(alternative, i), alternative, expanding)
+                        })
+                        .collect(),
+                    None => match schema.get("dataType").and_then(Value::as_str) {
+                        Some("list") => match schema.get("items") {
+                            Some(Value::Array(items)) => items
+                                .iter()
+                                .enumerate()
+                                .map(|(i, item)| {
+                                    self.schema_node(field_label(item, i), item, expanding)
+                                })
+                                .collect(),
+                            Some(items) => {
+                                vec![self.schema_node("items".to_string(), items, expanding)]
+                            }
+                            None => vec![],
+                        },
+                        Some("map") => ["keys", "values"]
+                            .into_iter()
+                            .filter_map(|key| {
+                                let schema = schema.get(key)?;
+
+                                Some(self.schema_node(key.to_string(), schema, expanding))
+                            })
+                            .collect(),
+                        Some("constructor") => fields(schema)
+                            .iter()
+                            .enumerate()
+                            .map(|(i, field)| {
+                                self.schema_node(field_label(field, i), field, expanding)
+                            })
+                            .collect(),
+                        _ => vec![],
+                    },
+                };
+
+                Node::branch(label, children).value(name)
+            }
+            Err(e) => Node::leaf(label, e),
+        };
+
+        if reference.is_some() {
+            expanding.pop();
+        }
+
+        node
+    }
+
+    /// `data` labelled with the names the schema gives its fields, along
+    /// with everywhere it doesn't match the schema.
+    pub fn annotate(
+        &self,
+        label: impl Into<String>,
+        schema: &Value,
+        data: &PlutusData,
+    ) -> (Node, Vec<String>) {
+        let mut errors = vec![];
+        let node = self.annotate_at(label.into(), schema, data, "$", &mut errors);
+
+        (node, errors)
+    }
+
+    /// `data` labelled by the validator `script_hash` belongs to, or failing
+    /// that by the first validator whose `purpose` argument it fits. Only a
+    /// validator known by its hash gets to report mismatches, with any other
+    /// they'd just mean it's the wrong one.
+    pub fn annotate_for(
+        &self,
+        label: impl Into<String>,
+        purpose: Purpose,
+        data: &PlutusData,
+        script_hash: Option<&Hash<28>>,
+    ) -> Option<Node> {
+        let label = label.into();
+
+        let titled = |node: Node, validator: &Validator| {
+            let value = match &node.value {
+                Some(value) => format!("{}: {value}", validator.title),
+                None => validator.title.clone(),
+            };
+
+            node.value(value)
+        };
+
+        let known = script_hash.and_then(|hash| {
+            self.validators
+                .iter()
+                .find(|validator| validator.script_hash().as_ref() == Some(hash))
+        });
+
+        if let Some(validator) = known {
+            let argument = validator.argument(purpose)?;
+            let (node, mismatches) = self.annotate(label, &argument.schema, data);
+
+            return Some(
+                titled(node, validator).children(
+                    mismatches
+                        .into_iter()
+                        .map(|mismatch| Node::leaf("mismatch", mismatch)),
+                ),
+            );
+        }
+
+        self.validators.iter().find_map(|validator| {
+            let argument = validator.argument(purpose)?;
+
+            // Opaque data fits anything, so it says nothing about the validator
+            let schema = self.resolve(&argument.schema).ok()?;
+
+            if schema.get("dataType").is_none() && alternatives(schema).is_none() {
+                return None;
+            }
+
+            let (node, mismatches) = self.annotate(label.clone(), &argument.schema, data);
+
+            mismatches.is_empty().then(|| titled(node, validator))
+        })
+    }
+
+    fn annotate_at(
+        &self,
+        label: String,
+        schema: &Value,
+        data: &PlutusData,
+        path: &str,
+        errors: &mut Vec<String>,
+    ) -> Node {
+        let name = self.type_name(schema);
+
+        // Parts that don't match are still shown, just without names
+        let mismatch = |errors: &mut Vec<String>, label: String, error: String| {
+            errors.push(format!("{path}: {error}"));
+
+            plutus_data::tree(label, data)
+        };
+
+        let schema = match self.resolve(schema) {
+            Ok(schema) => schema,
+            Err(e) => return mismatch(errors, label, e),
+        };
+
+        if let Some(alternatives) = alternatives(schema) {
+            // Constructors are told apart by their index, anything else by
+            // being the first alternative that fits
+            let index = match data {
+                PlutusData::Constr(constr) => constructor_index(constr),
+                _ => None,
+            };
+
+            let candidates: Vec<_> = alternatives
+                .iter()
+                .filter(|alternative| {
+                    let Ok(alternative) = self.resolve(alternative) else {
+                        return false;
+                    };
+
+                    match alternative.get("dataType").and_then(Value::as_str) {
+                        Some("constructor") => {
+                            index.is_some()
+                                && alternative.get("index").and_then(Value::as_u64) == index
+                        }
+                        _ => true,
+                    }
+                })
+                .collect();
+
+            for candidate in &candidates {
+                let mut candidate_errors = vec![];
+                let node =
+                    self.annotate_at(label.clone(), candidate, data, path, &mut candidate_errors);
+
+                // A single candidate is the right one even when its fields
+                // aren't, and its errors are more useful than a generic one
+                if candidate_errors.is_empty() || candidates.len() == 1 {
+                    errors.extend(candidate_errors);
+
+                    return node;
+                }
+            }
+
+            let error = match index {
+                Some(index) if candidates.is_empty() => {
+                    format!("constructor {index} isn't a variant of {name}")
+                }
+                _ => format!("not any of the variants of {name}"),
+            };
+
+            return mismatch(errors, label, error);
+        }
+
+        match (schema.get("dataType").and_then(Value::as_str), data) {
+            // Opaque data, anything goes
+            (None, _) => plutus_data::tree(label, data),
+            (Some("integer"), PlutusData::BigInt(_))
+            | (Some("bytes"), PlutusData::BoundedBytes(_)) => plutus_data::tree(label, data),
+            (Some("integer"), _) => {
+                mismatch(errors, label, format!("expected an integer for {name}"))
+            }
+            (Some("bytes"), _) => mismatch(errors, label, format!("expected bytes for {name}")),
+            (Some("list"), PlutusData::Array(items)) => match schema.get("items") {
+                // Tuples list the schema of each of their items
+                Some(Value::Array(schemas)) => {
+                    if schemas.len() != items.len() {
+                        return mismatch(
+                            errors,
+                            label,
+                            format!(
+                                "expected {} items for {name}, got {}",
+                                schemas.len(),
+                                items.len()
+                            ),
+                        );
+                    }
+
+                    let children: Vec<_> = schemas
+                        .iter()
+                        .zip(items.iter())
+                        .enumerate()
+                        .map(|(i, (schema, item))| {
+                            self.annotate_at(
+                                field_label(schema, i),
+                                schema,
+                                item,
+                                &format!("{path}[{i}]"),
+                                errors,
+                            )
+                        })
+                        .collect();
+
+                    Node::branch(label, children).value(name)
+                }
+                Some(schema) => {
+                    let children: Vec<_> = items
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| {
+                            self.annotate_at(
+                                format!("#{i}"),
+                                schema,
+                                item,
+                                &format!("{path}[{i}]"),
+                                errors,
+                            )
+                        })
+                        .collect();
+
+                    Node::list(label, children)
+                }
+                None => plutus_data::tree(label, data),
+            },
+            (Some("list"), _) => mismatch(errors, label, format!("expected a list for {name}")),
+            (Some("map"), PlutusData::Map(entries)) => {
+                let any = Value::Object(Map::new());
+                let keys = schema.get("keys").unwrap_or(&any);
+                let values = schema.get("values").unwrap_or(&any);
+
+                let children: Vec<_> = entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (k, v))| {
+                        Node::branch(
+                            format!("#{i}"),
+                            [
+                                self.annotate_at(
+                                    "key".to_string(),
+                                    keys,
+                                    k,
+                                    &format!("{path}[{i}].key"),
+                                    errors,
+                                ),
+                                self.annotate_at(
+                                    "value".to_string(),
+                                    values,
+                                    v,
+                                    &format!("{path}[{i}].value"),
+                                    errors,
+                                ),
+                            ],
+                        )
+                    })
+                    .collect();
+
+                Node::branch(label, children).value(format!("{name} [{}]", entries.len()))
+            }
+            (Some("map"), _) => mismatch(errors, label, format!("expected a map for {name}")),
+            (Some("constructor"), PlutusData::Constr(constr)) => {
+                let expected = schema.get("index").and_then(Value::as_u64);
+                let name = match schema.get("title") {
+                    Some(Value::String(title)) => title.clone(),
+                    _ => name,
+                };
+
+                if constructor_index(constr) != expected {
+                    return mismatch(
+                        errors,
+                        label,
+                        format!(
+                            "expected constructor {} for {name}",
+                            expected.map_or("?".to_string(), |i| i.to_string())
+                        ),
+                    );
+                }
+
+                let schemas = fields(schema);
+
+                if schemas.len() != constr.fields.len() {
+                    return mismatch(
+                        errors,
+                        label,
+                        format!(
+                            "expected {} fields for {name}, got {}",
+                            schemas.len(),
+                            constr.fields.len()
+                        ),
+                    );
+                }
+
+                let children: Vec<_> = schemas
+                    .iter()
+                    .zip(constr.fields.iter())
+                    .enumerate()
+                    .map(|(i, (schema, field))| {
+                        let label = field_label(schema, i);
+                        let path = match schema.get("title").and_then(Value::as_str) {
+                            Some(title) => format!("{path}.{title}"),
+                            None => format!("{path}.fields[{i}]"),
+                        };
+
+                        self.annotate_at(label, schema, field, &path, errors)
+                    })
+                    .collect();
+
+                Node::branch(label, children).value(name)
+            }
+            (Some("constructor"), _) => {
+                mismatch(errors, label, format!("expected a constructor for {name}"))
+            }
+            (Some(data_type), _) => {
+                mismatch(errors, label, format!("{data_type} isn't plutus data"))
+            }
+        }
+    }
+}
+
+impl Validator {
+    fn parse(json: &Value, version: Version, path: &str) -> Result<Self, String> {
+        let argument = |json: &Value| Argument {
+            title: json
+                .get("title")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            schema: json.get("schema").cloned().unwrap_or_default(),
+        };
+
+        let compiled = json
+            .get("compiledCode")
+            .and_then(Value::as_str)
+            .map(|code| compile(code, version));
+
+        Ok(Self {
+            title: json
+                .get("title")
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("{path}: missing \"title\""))?
+                .to_string(),
+            datum: json.get("datum").map(argument),
+            redeemer: json.get("redeemer").map(argument),
+            parameters: json
+                .get("parameters")
+                .and_then(Value::as_array)
+                .map(|parameters| parameters.iter().map(argument).collect())
+                .unwrap_or_default(),
+            hash: json.get("hash").and_then(Value::as_str).map(str::to_string),
+            compiled,
+        })
+    }
+
+    /// The hash of the compiled code, or else the one the blueprint claims.
+    pub fn script_hash(&self) -> Option<Hash<28>> {
+        match &self.compiled {
+            Some(Ok(compiled)) => Some(compiled.hash),
+            _ => self.hash.as_ref()?.parse().ok(),
+        }
+    }
+
+    pub fn argument(&self, purpose: Purpose) -> Option<&Argument> {
+        match purpose {
+            Purpose::Datum => self.datum.as_ref(),
+            Purpose::Redeemer => self.redeemer.as_ref(),
+        }
+    }
+}
+
+/// Compiled code is the flat program wrapped in a CBOR bytestring, exactly
+/// as it goes on chain.
+fn compile(code: &str, version: Version) -> Result<Compiled, String> {
+    let cbor = hex::decode(code).map_err(|e| format!("Invalid compiled code: {e}"))?;
+    let (flat, _) = unwrap_flat(&cbor)?;
+
+    let hash = hashes(&flat)
+        .into_iter()
+        .find_map(|(v, hash)| (v == version).then_some(hash))
+        .ok_or("Unknown plutus version")?;
+
+    Ok(Compiled { cbor, hash })
+}
+
+/// Definitions are referenced by JSON pointers, which escape slashes in
+/// names like `aiken/crypto/VerificationKeyHash`.
+fn definition_name(reference: &str) -> Option<String> {
+    reference
+        .strip_prefix("#/definitions/")
+        .map(|name| name.replace("~1", "/").replace("~0", "~"))
+}
+
+fn alternatives(schema: &Value) -> Option<&Vec<Value>> {
+    schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(Value::as_array)
+}
+
+fn fields(schema: &Value) -> &[Value] {
+    schema
+        .get("fields")
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+fn field_label(schema: &Value, i: usize) -> String {
+    match schema.get("title").and_then(Value::as_str) {
+        Some(title) => title.to_string(),
+        None => format!("#{i}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    // The identity program, wrapped once as the ledger stores it
+    const COMPILED: &str = "450101002499";
+
+    fn blueprint() -> Blueprint {
+        let json = json!({
+            "preamble": {
+                "title": "test/vesting",
+                "plutusVersion": "v2",
+                "compiler": { "name": "Aiken", "version": "v1.1.17" }
+            },
+            "validators": [{
+                "title": "vesting.vesting.spend",
+                "datum": { "title": "datum", "schema": { "$ref": "#/definitions/vesting~1Datum" } },
+                "redeemer": { "title": "redeemer", "schema": { "$ref": "#/definitions/Action" } },
+                "compiledCode": COMPILED
+            }],
+            "definitions": {
+                "Int": { "dataType": "integer" },
+                "VerificationKeyHash": { "title": "VerificationKeyHash", "dataType": "bytes" },
+                "vesting/Datum": {
+                    "title": "Datum",
+                    "anyOf": [{
+                        "title": "Datum",
+                        "dataType": "constructor",
+                        "index": 0,
+                        "fields": [
+                            { "title": "owner", "$ref": "#/definitions/VerificationKeyHash" },
+                            { "title": "deadline", "$ref": "#/definitions/Int" }
+                        ]
+                    }]
+                },
+                "Action": {
+                    "title": "Action",
+                    "anyOf": [
+                        { "title": "Claim", "dataType": "constructor", "index": 0, "fields": [] },
+                        { "title": "Cancel", "dataType": "constructor", "index": 1, "fields": [] }
+                    ]
+                },
+                "List": {
+                    "title": "List",
+                    "anyOf": [
+                        { "title": "Nil", "dataType": "constructor", "index": 0, "fields": [] },
+                        {
+                            "title": "Cons",
+                            "dataType": "constructor",
+                            "index": 1,
+                            "fields": [{ "$ref": "#/definitions/Int" }, { "$ref": "#/definitions/List" }]
+                        }
+                    ]
+                }
+            }
+        });
+
+        Blueprint::parse(&json.to_string()).unwrap()
+    }
+
+    fn data(json: Value) -> PlutusData {
+        plutus_data::from_json(&json).unwrap()
+    }
+
+    fn datum_schema() -> Value {
+        json!({ "$ref": "#/definitions/vesting~1Datum" })
+    }
+
+    #[test]
+    fn the_preamble_and_validators_are_read() {
+        let blueprint = blueprint();
+        let validator = &blueprint.validators[0];
+
+        assert_eq!(blueprint.title, "test/vesting");
+        assert_eq!(blueprint.plutus_version, Version::V2);
+        assert_eq!(blueprint.compiler.as_deref(), Some("Aiken v1.1.17"));
+        assert_eq!(validator.title, "vesting.vesting.spend");
+        assert_eq!(blueprint.type_name(&datum_schema()), "Datum");
+
+        let compiled = validator.compiled.clone().unwrap().unwrap();
+        let (flat, _) = unwrap_flat(&compiled.cbor).unwrap();
+
+        assert_eq!(validator.script_hash(), Some(hashes(&flat)[1].1));
+    }
+
+    #[test]
+    fn blueprints_say_where_they_are_malformed() {
+        assert_eq!(
+            Blueprint::parse(r#"{"validators": []}"#).unwrap_err(),
+            "$: missing \"preamble\""
+        );
+        assert_eq!(
+            Blueprint::parse(r#"{"preamble": {}, "validators": [{}]}"#).unwrap_err(),
+            "$.validators[0]: missing \"title\""
+        );
+    }
+
+    #[test]
+    fn matching_data_is_labelled_with_field_names() {
+        let datum = data(json!({
+            "constructor": 0,
+            "fields": [{ "bytes": "abcd" }, { "int": 1700000000 }]
+        }));
+
+        let (node, mismatches) = blueprint().annotate("datum", &datum_schema(), &datum);
+
+        assert_eq!(mismatches, Vec::<String>::new());
+        assert_eq!(node.value.as_deref(), Some("Datum"));
+
+        let labels: Vec<_> = node.children.iter().map(|c| c.label.as_str()).collect();
+
+        assert_eq!(labels, ["owner", "deadline"]);
+    }
+
+    #[test]
+    fn mismatches_point_at_the_offending_field() {
+        let blueprint = blueprint();
+        let mismatches =
+            |schema: &Value, json: Value| blueprint.annotate("", schema, &data(json)).1;
+
+        assert_eq!(
+            mismatches(
+                &datum_schema(),
+                json!({ "constructor": 0, "fields": [{ "bytes": "abcd" }, { "bytes": "00" }] })
+            ),
+            ["$.deadline: expected an integer for Int"]
+        );
+        assert_eq!(
+            mismatches(
+                &datum_schema(),
+                json!({ "constructor": 0, "fields": [{ "bytes": "abcd" }] })
+            ),
+            ["$: expected 2 fields for Datum, got 1"]
+        );
+        assert_eq!(
+            mismatches(
+                &json!({ "$ref": "#/definitions/Action" }),
+                json!({ "constructor": 5, "fields": [] })
+            ),
+            ["$: constructor 5 isn't a variant of Action"]
+        );
+        assert_eq!(
+            mismatches(
+                &json!({ "$ref": "#/definitions/Missing" }),
+                json!({ "int": 1 })
+            ),
+            ["$: unknown definition #/definitions/Missing"]
+        );
+    }
+
+    #[test]
+    fn data_is_matched_to_the_validator_it_fits() {
+        let blueprint = blueprint();
+        let claim = data(json!({ "constructor": 0, "fields": [] }));
+        let unknown = data(json!({ "constructor": 5, "fields": [] }));
+
+        let node = blueprint
+            .annotate_for("redeemer", Purpose::Redeemer, &claim, None)
+            .unwrap();
+
+        assert_eq!(node.value.as_deref(), Some("vesting.vesting.spend: Claim"));
+        assert!(blueprint
+            .annotate_for("redeemer", Purpose::Redeemer, &unknown, None)
+            .is_none());
+
+        // Known by its hash, the validator reports what's wrong instead
+        let hash = blueprint.validators[0].script_hash().unwrap();
+        let node = blueprint
+            .annotate_for("redeemer", Purpose::Redeemer, &unknown, Some(&hash))
+            .unwrap();
+
+        assert_eq!(
+            node.children.last(),
+            Some(&Node::leaf(
+                "mismatch",
+                "$: constructor 5 isn't a variant of Action"
+            ))
+        );
+    }
+
+    #[test]
+    fn recursive_types_are_expanded_once() {
+        let tree = blueprint().schema_tree("list", &json!({ "$ref": "#/definitions/List" }));
+        let cons = &tree.children[1];
+
+        assert_eq!(cons.label, "Cons");
+        assert_eq!(cons.children[1], Node::leaf("#1", "List"));
+    }
+}
@@ -0,0 +1,26 @@
+use std::collections::HashSet;
+
+use iced::widget::text_editor;
+
+use crate::tree::{self, Node};
+
+use super::{Blueprint, Purpose};
+
+#[derive(Debug, Default)]
+pub struct State {
+    /// Where the `plutus.json` is, usually the root of an Aiken project
+    pub path: String,
+    pub loading: bool,
+    pub blueprint: Option<Blueprint>,
+    pub error: Option<String>,
+    pub selected: Option<usize>,
+    pub purpose: Purpose,
+    pub data: text_editor::Content,
+    /// The pasted data labelled with the names from the blueprint
+    pub annotated: Option<Node>,
+    /// Where the pasted data doesn't match the schema
+    pub mismatches: Vec<String>,
+    pub warning: Option<String>,
+    pub schema_collapsed: HashSet<tree::Path>,
+    pub data_collapsed: HashSet<tree::Path>,
+}
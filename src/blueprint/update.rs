@@ -0,0 +1,135 @@
+use iced::{clipboard, widget::text_editor, Task};
+
+use crate::{plutus_data::parse, tree};
+
+use super::{Blueprint, Purpose, State};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    PathChanged(String),
+    Load,
+    Loaded(Result<Box<Blueprint>, String>),
+    ValidatorSelected(usize),
+    PurposeSet(Purpose),
+    DataChanged(text_editor::Action),
+    ToggleSchemaNode(tree::Path),
+    ToggleDataNode(tree::Path),
+    Copy(String),
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            PathChanged(path) => {
+                self.path = path;
+                Task::none()
+            }
+            Load => {
+                self.loading = true;
+                self.error = None;
+                Task::perform(load(self.path.trim().to_string()), Loaded)
+            }
+            Loaded(result) => {
+                self.loading = false;
+
+                match result {
+                    Ok(blueprint) => {
+                        self.selected = (!blueprint.validators.is_empty()).then_some(0);
+                        self.blueprint = Some(*blueprint);
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+
+                self.schema_collapsed.clear();
+                self.check();
+                Task::none()
+            }
+            ValidatorSelected(i) => {
+                self.selected = Some(i);
+                self.schema_collapsed.clear();
+                self.check();
+                Task::none()
+            }
+            PurposeSet(purpose) => {
+                self.purpose = purpose;
+                self.check();
+                Task::none()
+            }
+            DataChanged(action) => {
+                self.data.perform(action);
+                self.check();
+                Task::none()
+            }
+            ToggleSchemaNode(path) => {
+                if !self.schema_collapsed.remove(&path) {
+                    self.schema_collapsed.insert(path);
+                }
+                Task::none()
+            }
+            ToggleDataNode(path) => {
+                if !self.data_collapsed.remove(&path) {
+                    self.data_collapsed.insert(path);
+                }
+                Task::none()
+            }
+            Copy(s) => clipboard::write(s),
+        }
+    }
+
+    /// Match the pasted data against the schema of the selected validator's
+    /// datum or redeemer.
+    fn check(&mut self) {
+        self.annotated = None;
+        self.mismatches.clear();
+        self.warning = None;
+        self.data_collapsed.clear();
+
+        let input = self.data.text();
+
+        if input.trim().is_empty() {
+            return;
+        }
+
+        let (data, _) = match parse(&input) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.warning = Some(e);
+                return;
+            }
+        };
+
+        let Some(blueprint) = &self.blueprint else {
+            return;
+        };
+
+        let Some(validator) = self.selected.and_then(|i| blueprint.validators.get(i)) else {
+            return;
+        };
+
+        let Some(argument) = validator.argument(self.purpose) else {
+            self.warning = Some(format!(
+                "{} takes no {}",
+                validator.title,
+                self.purpose.to_string().to_lowercase()
+            ));
+            return;
+        };
+
+        let label = argument
+            .title
+            .clone()
+            .unwrap_or_else(|| self.purpose.to_string().to_lowercase());
+
+        let (node, mismatches) = blueprint.annotate(label, &argument.schema, &data);
+
+        self.annotated = Some(node);
+        self.mismatches = mismatches;
+    }
+}
+
+async fn load(path: String) -> Result<Box<Blueprint>, String> {
+    let json = std::fs::read_to_string(&path).map_err(|e| format!("Could not read {path}: {e}"))?;
+
+    Blueprint::parse(&json).map(Box::new)
+}
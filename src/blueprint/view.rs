@@ -0,0 +1,237 @@
+use iced::{
+    padding,
+    widget::{
+        button, column, container, pick_list, row, scrollable, text, text_editor, text_input,
+        Column,
+    },
+    Border, Color, Element, Font,
+    Length::{Fill, FillPortion},
+    Theme,
+};
+
+use super::{Message, Purpose, State, Validator};
+use crate::{copyable_text::copyable_text, tree::tree};
+
+const RED: Color = Color::from_rgb(1.0, 0.0, 0.0);
+const GREEN: Color = Color::from_rgb(0.0, 0.7, 0.0);
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        let validators = self.blueprint.iter().flat_map(|blueprint| {
+            blueprint
+                .validators
+                .iter()
+                .enumerate()
+                .map(|(i, validator)| {
+                    button(text(&validator.title).font(Font::MONOSPACE).size(14))
+                        .style(if self.selected == Some(i) {
+                            button::secondary
+                        } else {
+                            button::text
+                        })
+                        .padding(2)
+                        .on_press(Message::ValidatorSelected(i))
+                        .into()
+                })
+        });
+
+        row![
+            column![row![
+                text_input("path to plutus.json", &self.path)
+                    .on_input(Message::PathChanged)
+                    .on_submit(Message::Load),
+                button(text(if self.loading { "Loading..." } else { "Load" })).on_press_maybe(
+                    (!self.loading && !self.path.trim().is_empty()).then_some(Message::Load)
+                )
+            ]
+            .spacing(5)]
+            .push_maybe(self.error.as_ref().map(|e| text(e).color(RED)))
+            .push(scrollable(column(validators).spacing(2)).height(Fill))
+            .push(pick_list(
+                &Purpose::ALL[..],
+                Some(self.purpose),
+                Message::PurposeSet
+            ))
+            .push(
+                text_editor(&self.data)
+                    .placeholder("datum or redeemer: cbor or detailed schema json...")
+                    .on_action(Message::DataChanged)
+                    .height(Fill)
+                    .wrapping(text::Wrapping::Glyph)
+                    .style(|theme: &Theme, status| {
+                        let mut defaults = text_editor::default(theme, status);
+
+                        defaults.border.radius = 4.0.into();
+
+                        defaults
+                    })
+            )
+            .push_maybe(
+                self.warning
+                    .as_ref()
+                    .map(|warning| text(warning).color(RED))
+            )
+            .spacing(5)
+            .width(FillPortion(1))
+            .padding(5),
+            container(scrollable(self.view_blueprint().width(Fill)).width(Fill))
+                .width(FillPortion(2))
+                .height(Fill)
+                .style(|theme: &Theme| {
+                    let palette = theme.extended_palette();
+
+                    container::Style {
+                        border: Border {
+                            width: 1.0,
+                            radius: 4.0.into(),
+                            color: palette.background.strong.color,
+                        },
+                        ..Default::default()
+                    }
+                })
+                .padding(10)
+        ]
+        .spacing(5)
+        .padding(padding::top(20).left(5).right(10).bottom(10))
+        .into()
+    }
+
+    fn view_blueprint(&self) -> Column<'_, Message> {
+        let Some(blueprint) = &self.blueprint else {
+            return column![text("no blueprint loaded")];
+        };
+
+        let field = |label, value: String| row![text(label).width(160), text(value)];
+
+        let mut content = column![
+            field("title", blueprint.title.clone()),
+            field(
+                "version",
+                blueprint.version.clone().unwrap_or("-".to_string())
+            ),
+            field("plutus version", blueprint.plutus_version.to_string()),
+            field(
+                "compiler",
+                blueprint.compiler.clone().unwrap_or("-".to_string())
+            ),
+        ]
+        .spacing(5);
+
+        if let Some(validator) = self.selected.and_then(|i| blueprint.validators.get(i)) {
+            content = content.push(self.view_validator(validator));
+        }
+
+        if let Some(annotated) = &self.annotated {
+            content = content
+                .push(if self.mismatches.is_empty() {
+                    text(format!("{} matches the schema", self.purpose)).color(GREEN)
+                } else {
+                    text(format!("{} doesn't match the schema", self.purpose)).color(RED)
+                })
+                .extend(self.mismatches.iter().map(|mismatch| {
+                    text(mismatch)
+                        .font(Font::MONOSPACE)
+                        .size(14)
+                        .color(RED)
+                        .into()
+                }))
+                .push(tree(
+                    annotated,
+                    &self.data_collapsed,
+                    Message::ToggleDataNode,
+                ));
+        }
+
+        content.spacing(10)
+    }
+
+    fn view_validator<'a>(&self, validator: &'a Validator) -> Column<'a, Message> {
+        let Some(blueprint) = &self.blueprint else {
+            return column![];
+        };
+
+        let field = |label, value: Element<'a, Message>| row![text(label).width(160), value];
+
+        let mut details = column![text(&validator.title).font(Font::MONOSPACE)].spacing(5);
+
+        match &validator.compiled {
+            Some(Ok(compiled)) => {
+                let hash = compiled.hash.to_string();
+
+                details = details
+                    .push(field(
+                        "size",
+                        text(format!("{} bytes", compiled.cbor.len())).into(),
+                    ))
+                    .push(field("hash", copyable_text(&hash, Message::Copy)))
+                    .push_maybe(
+                        validator
+                            .hash
+                            .as_ref()
+                            .filter(|claimed| **claimed != hash)
+                            .map(|claimed| {
+                                text(format!(
+                                    "the blueprint says the hash is {claimed}, it may be out of \
+                                     date"
+                                ))
+                                .color(RED)
+                            }),
+                    );
+            }
+            Some(Err(e)) => {
+                details = details.push(field("compiled code", text(e).color(RED).into()))
+            }
+            None => details = details.push(field("compiled code", text("-").into())),
+        }
+
+        let argument_name = |title: &Option<String>, fallback: &str| {
+            title.clone().unwrap_or_else(|| fallback.to_string())
+        };
+
+        details = details.push(field(
+            "parameters",
+            if validator.parameters.is_empty() {
+                text("none").into()
+            } else {
+                column(
+                    validator
+                        .parameters
+                        .iter()
+                        .enumerate()
+                        .map(|(i, parameter)| {
+                            text(format!(
+                                "{}: {}",
+                                argument_name(&parameter.title, &format!("#{i}")),
+                                blueprint.type_name(&parameter.schema)
+                            ))
+                            .font(Font::MONOSPACE)
+                            .size(14)
+                            .into()
+                        }),
+                )
+                .spacing(2)
+                .into()
+            },
+        ));
+
+        let schemas = validator
+            .datum
+            .iter()
+            .map(|datum| (Purpose::Datum, datum))
+            .chain(validator.redeemer.iter().map(|r| (Purpose::Redeemer, r)))
+            .map(|(purpose, argument)| {
+                blueprint.schema_tree(
+                    format!("{purpose} {}", argument_name(&argument.title, ""))
+                        .trim_end()
+                        .to_string(),
+                    &argument.schema,
+                )
+            });
+
+        details.push(tree(
+            &crate::tree::Node::branch("schemas", schemas),
+            &self.schema_collapsed,
+            Message::ToggleSchemaNode,
+        ))
+    }
+}
@@ -0,0 +1,660 @@
+//! A CBOR reader that keeps everything a ledger decoder throws away: where
+//! each item starts, how its length was encoded and which tags wrapped it.
+
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use crate::tree::Node;
+
+/// Deep enough for any ledger type, shallow enough not to overflow the stack.
+const MAX_DEPTH: usize = 256;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub offset: usize,
+    pub kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Kind {
+    Unsigned(u64),
+    /// Stored as the raw argument `n`, the value is `-1 - n`.
+    Negative(u64),
+    Bytes(Vec<u8>),
+    Text(String),
+    /// Indefinite length byte or text strings, made of definite chunks.
+    Chunks(Vec<Item>),
+    Array {
+        items: Vec<Item>,
+        indefinite: bool,
+    },
+    Map {
+        entries: Vec<(Item, Item)>,
+        indefinite: bool,
+    },
+    Tag(u64, Box<Item>),
+    Bool(bool),
+    Null,
+    Undefined,
+    Simple(u8),
+    Float(f64, Precision),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Precision {
+    Half,
+    Single,
+    Double,
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+#[error("{message} at byte {offset}")]
+pub struct Error {
+    pub offset: usize,
+    pub message: String,
+}
+
+/// Decode a CBOR sequence, which is a single item most of the time.
+pub fn decode_sequence(bytes: &[u8]) -> Result<Vec<Item>, Error> {
+    let mut reader = Reader { bytes, position: 0 };
+    let mut items = vec![];
+
+    while reader.position < bytes.len() {
+        items.push(reader.item(0)?);
+    }
+
+    Ok(items)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn error(&self, offset: usize, message: impl Into<String>) -> Error {
+        Error {
+            offset,
+            message: message.into(),
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8], Error> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| self.error(self.bytes.len(), "unexpected end of input"))?;
+
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn uint(&mut self, len: usize) -> Result<u64, Error> {
+        Ok(self
+            .take(len)?
+            .iter()
+            .fold(0, |acc, byte| (acc << 8) | u64::from(*byte)))
+    }
+
+    /// The argument of an initial byte, `None` for indefinite lengths.
+    fn argument(&mut self, offset: usize, info: u8) -> Result<Option<u64>, Error> {
+        match info {
+            0..=23 => Ok(Some(info.into())),
+            24 => self.uint(1).map(Some),
+            25 => self.uint(2).map(Some),
+            26 => self.uint(4).map(Some),
+            27 => self.uint(8).map(Some),
+            31 => Ok(None),
+            _ => Err(self.error(offset, format!("reserved additional info {info}"))),
+        }
+    }
+
+    fn length(&mut self, offset: usize, info: u8) -> Result<Option<usize>, Error> {
+        match self.argument(offset, info)? {
+            Some(len) => usize::try_from(len)
+                .map(Some)
+                .map_err(|_| self.error(offset, format!("length {len} is too large"))),
+            None => Ok(None),
+        }
+    }
+
+    fn is_break(&self) -> bool {
+        self.bytes.get(self.position) == Some(&0xff)
+    }
+
+    fn item(&mut self, depth: usize) -> Result<Item, Error> {
+        let offset = self.position;
+
+        if depth > MAX_DEPTH {
+            return Err(self.error(offset, "nested too deeply"));
+        }
+
+        let initial = self.byte()?;
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+
+        let kind = match major {
+            0 | 1 | 6 => {
+                let Some(argument) = self.argument(offset, info)? else {
+                    return Err(self.error(offset, "indefinite length on a non-container"));
+                };
+
+                match major {
+                    0 => Kind::Unsigned(argument),
+                    1 => Kind::Negative(argument),
+                    _ => Kind::Tag(argument, Box::new(self.item(depth + 1)?)),
+                }
+            }
+            2 | 3 => match self.length(offset, info)? {
+                Some(len) => {
+                    let bytes = self.take(len)?.to_vec();
+
+                    if major == 2 {
+                        Kind::Bytes(bytes)
+                    } else {
+                        Kind::Text(
+                            String::from_utf8(bytes)
+                                .map_err(|_| self.error(offset, "invalid utf-8 in text string"))?,
+                        )
+                    }
+                }
+                None => {
+                    let mut chunks = vec![];
+
+                    while !self.is_break() {
+                        let chunk = self.item(depth + 1)?;
+
+                        let same_major = matches!(
+                            (&chunk.kind, major),
+                            (Kind::Bytes(_), 2) | (Kind::Text(_), 3)
+                        );
+
+                        if !same_major {
+                            return Err(self.error(chunk.offset, "invalid indefinite string chunk"));
+                        }
+
+                        chunks.push(chunk);
+                    }
+
+                    self.position += 1;
+
+                    Kind::Chunks(chunks)
+                }
+            },
+            4 => {
+                let len = self.length(offset, info)?;
+                let mut items = vec![];
+
+                match len {
+                    Some(len) => {
+                        for _ in 0..len {
+                            items.push(self.item(depth + 1)?);
+                        }
+                    }
+                    None => {
+                        while !self.is_break() {
+                            items.push(self.item(depth + 1)?);
+                        }
+
+                        self.position += 1;
+                    }
+                }
+
+                Kind::Array {
+                    items,
+                    indefinite: len.is_none(),
+                }
+            }
+            5 => {
+                let len = self.length(offset, info)?;
+                let mut entries = vec![];
+
+                match len {
+                    Some(len) => {
+                        for _ in 0..len {
+                            entries.push((self.item(depth + 1)?, self.item(depth + 1)?));
+                        }
+                    }
+                    None => {
+                        while !self.is_break() {
+                            entries.push((self.item(depth + 1)?, self.item(depth + 1)?));
+                        }
+
+                        self.position += 1;
+                    }
+                }
+
+                Kind::Map {
+                    entries,
+                    indefinite: len.is_none(),
+                }
+            }
+            _ => match info {
+                20 => Kind::Bool(false),
+                21 => Kind::Bool(true),
+                22 => Kind::Null,
+                23 => Kind::Undefined,
+                0..=19 => Kind::Simple(info),
+                24 => match self.byte()? {
+                    value @ 32.. => Kind::Simple(value),
+                    _ => return Err(self.error(offset, "invalid two byte simple value")),
+                },
+                25 => Kind::Float(half(self.uint(2)? as u16), Precision::Half),
+                26 => Kind::Float(
+                    f32::from_bits(self.uint(4)? as u32).into(),
+                    Precision::Single,
+                ),
+                27 => Kind::Float(f64::from_bits(self.uint(8)?), Precision::Double),
+                31 => return Err(self.error(offset, "unexpected break")),
+                _ => return Err(self.error(offset, format!("reserved additional info {info}"))),
+            },
+        };
+
+        Ok(Item { offset, kind })
+    }
+}
+
+fn half(bits: u16) -> f64 {
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = f64::from(bits & 0x3ff);
+
+    let value = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1024.0 + mantissa) * 2f64.powi(i32::from(exponent) - 25),
+    };
+
+    if bits & 0x8000 != 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+impl Kind {
+    pub fn major(&self) -> &'static str {
+        match self {
+            Kind::Unsigned(_) => "unsigned",
+            Kind::Negative(_) => "negative",
+            Kind::Bytes(_) => "bytes",
+            Kind::Text(_) => "text",
+            Kind::Chunks(chunks) => match chunks.first().map(|c| &c.kind) {
+                Some(Kind::Text(_)) => "text",
+                _ => "bytes",
+            },
+            Kind::Array { .. } => "array",
+            Kind::Map { .. } => "map",
+            Kind::Tag(..) => "tag",
+            Kind::Float(_, Precision::Half) => "float16",
+            Kind::Float(_, Precision::Single) => "float32",
+            Kind::Float(_, Precision::Double) => "float64",
+            Kind::Bool(_) | Kind::Null | Kind::Undefined | Kind::Simple(_) => "simple",
+        }
+    }
+}
+
+impl Item {
+    pub fn tree(&self, label: impl Into<String>) -> Node {
+        let label = format!("{} {}", label.into(), self.kind.major());
+        let at = format!("@{}", self.offset);
+
+        match &self.kind {
+            Kind::Chunks(chunks) => Node::branch(
+                label,
+                chunks
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| c.tree(format!("#{i}"))),
+            )
+            .value(format!("(_) [{}] {at}", chunks.len())),
+            Kind::Array { items, indefinite } => Node::branch(
+                label,
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| item.tree(format!("#{i}"))),
+            )
+            .value(format!(
+                "{}[{}] {at}",
+                if *indefinite { "(_) " } else { "" },
+                items.len()
+            )),
+            Kind::Map {
+                entries,
+                indefinite,
+            } => Node::branch(
+                label,
+                entries.iter().enumerate().map(|(i, (key, value))| {
+                    Node::new(format!("#{i}"))
+                        .push(key.tree("key"))
+                        .push(value.tree("value"))
+                }),
+            )
+            .value(format!(
+                "{}{{{}}} {at}",
+                if *indefinite { "(_) " } else { "" },
+                entries.len()
+            )),
+            Kind::Tag(tag, item) => {
+                let node = Node::new(label)
+                    .value(format!("{tag} {at}"))
+                    .push(item.tree("content"));
+
+                // Tag 24 is CBOR embedded in a byte string, as used for
+                // inline datums and script refs
+                match (tag, &item.kind) {
+                    (24, Kind::Bytes(bytes)) => {
+                        node.push_maybe(decode_sequence(bytes).ok().and_then(|items| {
+                            match items.as_slice() {
+                                [item] => Some(item.tree("embedded")),
+                                _ => None,
+                            }
+                        }))
+                    }
+                    _ => node,
+                }
+            }
+            _ => Node::leaf(label, format!("{} {at}", diagnostic(self))),
+        }
+    }
+}
+
+/// RFC 8949 diagnostic notation, indented when it doesn't fit on a line.
+pub fn diagnostic(item: &Item) -> String {
+    let mut widths = vec![];
+    measure(item, &mut widths);
+
+    let mut out = String::new();
+    write_diagnostic(item, 0, &widths, &mut 0, &mut out);
+    out
+}
+
+const LINE_WIDTH: usize = 80;
+
+/// Diagnostic notation on a single line.
+pub fn compact(item: &Item) -> String {
+    let mut out = String::new();
+    write_compact(item, &mut out);
+    out
+}
+
+fn write_compact(item: &Item, out: &mut String) {
+    match &item.kind {
+        Kind::Unsigned(n) => write!(out, "{n}").unwrap(),
+        Kind::Negative(n) => write!(out, "{}", -1 - i128::from(*n)).unwrap(),
+        Kind::Bytes(bytes) => write!(out, "h'{}'", hex::encode(bytes)).unwrap(),
+        // Diagnostic notation escapes strings the way JSON does
+        Kind::Text(text) => out.push_str(&Value::from(text.as_str()).to_string()),
+        Kind::Chunks(chunks) => {
+            out.push_str("(_ ");
+            separated(chunks.iter(), out, write_compact);
+            out.push(')');
+        }
+        Kind::Array { items, indefinite } => {
+            out.push_str(if *indefinite { "[_ " } else { "[" });
+            separated(items.iter(), out, write_compact);
+            out.push(']');
+        }
+        Kind::Map {
+            entries,
+            indefinite,
+        } => {
+            out.push_str(if *indefinite { "{_ " } else { "{" });
+            separated(entries.iter(), out, |(key, value), out| {
+                write_compact(key, out);
+                out.push_str(": ");
+                write_compact(value, out);
+            });
+            out.push('}');
+        }
+        Kind::Tag(tag, item) => {
+            write!(out, "{tag}(").unwrap();
+            write_compact(item, out);
+            out.push(')');
+        }
+        Kind::Bool(b) => write!(out, "{b}").unwrap(),
+        Kind::Null => out.push_str("null"),
+        Kind::Undefined => out.push_str("undefined"),
+        Kind::Simple(n) => write!(out, "simple({n})").unwrap(),
+        Kind::Float(f, _) => {
+            if f.is_nan() {
+                out.push_str("NaN");
+            } else if f.is_infinite() {
+                out.push_str(if *f > 0.0 { "Infinity" } else { "-Infinity" });
+            } else {
+                write!(out, "{f:?}").unwrap();
+            }
+        }
+    }
+}
+
+fn separated<T>(
+    items: impl Iterator<Item = T>,
+    out: &mut String,
+    mut write: impl FnMut(T, &mut String),
+) {
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write(item, out);
+    }
+}
+
+/// How wide an item is on a single line and how many items it's made of,
+/// itself included, for each item in the order they're written.
+#[derive(Clone, Copy, Default)]
+struct Width {
+    flat: usize,
+    items: usize,
+}
+
+/// Work out every item's width bottom up, so that laying out a whole block
+/// doesn't have to write each item out once for every level it's nested in.
+fn measure(item: &Item, widths: &mut Vec<Width>) -> usize {
+    let index = widths.len();
+    widths.push(Width::default());
+
+    let separators = |n: usize| n.saturating_sub(1) * 2;
+
+    let flat = match &item.kind {
+        Kind::Chunks(chunks) => {
+            let inner: usize = chunks.iter().map(|chunk| measure(chunk, widths)).sum();
+
+            "(_ ".len() + inner + separators(chunks.len()) + ")".len()
+        }
+        Kind::Array { items, indefinite } => {
+            let open = if *indefinite { "[_ " } else { "[" };
+            let inner: usize = items.iter().map(|item| measure(item, widths)).sum();
+
+            open.len() + inner + separators(items.len()) + "]".len()
+        }
+        Kind::Map {
+            entries,
+            indefinite,
+        } => {
+            let open = if *indefinite { "{_ " } else { "{" };
+            let inner: usize = entries
+                .iter()
+                .map(|(key, value)| measure(key, widths) + ": ".len() + measure(value, widths))
+                .sum();
+
+            open.len() + inner + separators(entries.len()) + "}".len()
+        }
+        Kind::Tag(tag, item) => tag.to_string().len() + 1 + measure(item, widths) + 1,
+        _ => compact(item).len(),
+    };
+
+    widths[index] = Width {
+        flat,
+        items: widths.len() - index,
+    };
+
+    flat
+}
+
+/// Write `item`, whose width is at `widths[*next]`, moving `next` past
+/// everything it's made of.
+fn write_diagnostic(
+    item: &Item,
+    indent: usize,
+    widths: &[Width],
+    next: &mut usize,
+    out: &mut String,
+) {
+    let width = widths[*next];
+
+    if indent * 2 + width.flat <= LINE_WIDTH {
+        write_compact(item, out);
+        *next += width.items;
+        return;
+    }
+
+    *next += 1;
+
+    let pad = "  ".repeat(indent + 1);
+
+    match &item.kind {
+        Kind::Array { items, indefinite } => {
+            out.push_str(if *indefinite { "[_\n" } else { "[\n" });
+            for (i, item) in items.iter().enumerate() {
+                out.push_str(&pad);
+                write_diagnostic(item, indent + 1, widths, next, out);
+                out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&"  ".repeat(indent));
+            out.push(']');
+        }
+        Kind::Map {
+            entries,
+            indefinite,
+        } => {
+            out.push_str(if *indefinite { "{_\n" } else { "{\n" });
+            for (i, (key, value)) in entries.iter().enumerate() {
+                out.push_str(&pad);
+                write_diagnostic(key, indent + 1, widths, next, out);
+                out.push_str(": ");
+                write_diagnostic(value, indent + 1, widths, next, out);
+                out.push_str(if i + 1 < entries.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&"  ".repeat(indent));
+            out.push('}');
+        }
+        Kind::Tag(tag, item) => {
+            write!(out, "{tag}(").unwrap();
+            write_diagnostic(item, indent, widths, next, out);
+            out.push(')');
+        }
+        _ => {
+            write_compact(item, out);
+            *next += width.items - 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(cbor: &str) -> Item {
+        decode_sequence(&hex::decode(cbor).unwrap())
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn sequences_keep_every_item_and_where_it_starts() {
+        // 1, -500, "a"
+        let items = decode_sequence(&hex::decode("013901f36161").unwrap()).unwrap();
+
+        assert_eq!(
+            items,
+            [
+                Item {
+                    offset: 0,
+                    kind: Kind::Unsigned(1)
+                },
+                Item {
+                    offset: 1,
+                    kind: Kind::Negative(499)
+                },
+                Item {
+                    offset: 4,
+                    kind: Kind::Text("a".to_string())
+                },
+            ]
+        );
+        assert_eq!(compact(&items[1]), "-500");
+    }
+
+    #[test]
+    fn half_floats() {
+        assert_eq!(half(0x3c00), 1.0);
+        assert_eq!(half(0xc400), -4.0);
+        // The smallest subnormal
+        assert_eq!(half(0x0001), 2f64.powi(-24));
+        assert_eq!(half(0x7c00), f64::INFINITY);
+        assert!(half(0x7e00).is_nan());
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_byte() {
+        let error = |cbor: &str| decode_sequence(&hex::decode(cbor).unwrap()).unwrap_err();
+
+        // An array of two with only one item
+        assert_eq!(error("8201").offset, 2);
+        assert_eq!(error("8201").message, "unexpected end of input");
+        // A break outside of anything indefinite
+        assert_eq!(error("82ff").offset, 1);
+        assert_eq!(error("82ff").message, "unexpected break");
+        // A text chunk in an indefinite byte string
+        assert_eq!(error("5f4101616100ff").offset, 3);
+        assert_eq!(error("1c").message, "reserved additional info 28");
+        assert_eq!(error("62c328").message, "invalid utf-8 in text string");
+    }
+
+    #[test]
+    fn measured_widths_match_what_gets_written() {
+        // [_ {1: "a", h'01': [2, -3]}, 24(h'00'), (_ "ab", "c"), 1.5]
+        let item = item("9fa20161614101820222d81841007f6261626163fff93e00ff");
+        let mut widths = vec![];
+
+        measure(&item, &mut widths);
+
+        assert_eq!(
+            compact(&item),
+            r#"[_ {1: "a", h'01': [2, -3]}, 24(h'00'), (_ "ab", "c"), 1.5]"#
+        );
+        assert_eq!(widths[0].flat, compact(&item).len());
+        assert_eq!(widths[0].items, widths.len());
+        assert_eq!(diagnostic(&item), compact(&item));
+    }
+
+    #[test]
+    fn long_items_break_over_lines() {
+        // Thirty zero bytes, then an array of them again and a 1
+        let bytes = format!("581e{}", "00".repeat(30));
+        let item = item(&format!("82{bytes}82{bytes}01"));
+        let zeros = "0".repeat(60);
+
+        assert_eq!(
+            diagnostic(&item),
+            format!("[\n  h'{zeros}',\n  [h'{zeros}', 1]\n]")
+        );
+    }
+
+    #[test]
+    fn text_is_escaped_like_json() {
+        // An a, an escape character and a double quote
+        assert_eq!(diagnostic(&item("63611b22")), r#""a\u001b\"""#);
+    }
+}
@@ -0,0 +1,8 @@
+mod cbor;
+mod state;
+mod update;
+mod view;
+
+pub use cbor::{compact, decode_sequence, Item, Kind};
+pub use state::*;
+pub use update::*;
@@ -0,0 +1,45 @@
+use std::{collections::HashSet, fmt::Display};
+
+use iced::widget::text_editor;
+
+use crate::{
+    encoding::{Detected, Encoding},
+    tree,
+};
+
+use super::cbor::Item;
+
+#[derive(Debug, Default)]
+pub struct State {
+    pub contents: text_editor::Content,
+    pub encoding: Option<Encoding>,
+    pub detected: Detected,
+    pub mode: Mode,
+    pub items: Vec<Item>,
+    pub warning: Option<String>,
+    pub collapsed: HashSet<tree::Path>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Mode {
+    #[default]
+    Tree,
+    Diagnostic,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 2] = [Mode::Tree, Mode::Diagnostic];
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Mode::Tree => "Tree",
+                Mode::Diagnostic => "Diagnostic",
+            }
+        )
+    }
+}
@@ -0,0 +1,85 @@
+use iced::{clipboard, widget::text_editor, Task};
+
+use crate::{
+    encoding::{Detected, Encoding},
+    tree,
+};
+
+use super::{
+    cbor::{decode_sequence, diagnostic},
+    Mode, State,
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    ContentsChanged(text_editor::Action),
+    EncodingSet(Encoding),
+    ModeSet(Mode),
+    ToggleNode(tree::Path),
+    CopyDiagnostic,
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            ContentsChanged(action) => {
+                self.contents.perform(action);
+                self.decode();
+                Task::none()
+            }
+            EncodingSet(enc) => {
+                self.encoding = Some(enc);
+                self.decode();
+                Task::none()
+            }
+            ModeSet(mode) => {
+                self.mode = mode;
+                Task::none()
+            }
+            ToggleNode(path) => {
+                if !self.collapsed.remove(&path) {
+                    self.collapsed.insert(path);
+                }
+                Task::none()
+            }
+            CopyDiagnostic => clipboard::write(self.diagnostic()),
+        }
+    }
+
+    pub fn diagnostic(&self) -> String {
+        self.items
+            .iter()
+            .map(diagnostic)
+            .collect::<Vec<_>>()
+            .join(",\n")
+    }
+
+    fn decode(&mut self) {
+        self.warning = None;
+        self.items.clear();
+        self.collapsed.clear();
+
+        let contents = self.contents.text();
+        let contents = contents.trim();
+
+        self.detected = Detected::new(contents, self.encoding.as_ref());
+
+        if contents.is_empty() {
+            return;
+        }
+
+        let bytes = match self.detected.encoding().decode(contents) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.warning = Some(e);
+                return;
+            }
+        };
+
+        match decode_sequence(&bytes) {
+            Ok(items) => self.items = items,
+            Err(e) => self.warning = Some(format!("Invalid CBOR: {e}")),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use iced::{
+    padding,
+    widget::{button, column, container, pick_list, row, scrollable, text, text_editor, Space},
+    Border, Element, Font,
+    Length::{Fill, FillPortion},
+    Theme,
+};
+use iced_font_awesome::fa_icon_solid;
+
+use super::{Message, Mode, State};
+use crate::{
+    encoding::encoding_picker,
+    tree::{tree, Node},
+};
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        let decoded: Element<_> = match (self.mode, self.items.as_slice()) {
+            (_, []) => text("nothing to decode").into(),
+            (Mode::Tree, [item]) => tree(&item.tree("#0"), &self.collapsed, Message::ToggleNode),
+            (Mode::Tree, items) => tree(
+                &Node::list(
+                    "sequence",
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(i, item)| item.tree(format!("#{i}"))),
+                ),
+                &self.collapsed,
+                Message::ToggleNode,
+            ),
+            (Mode::Diagnostic, _) => row![
+                text(self.diagnostic())
+                    .font(Font::MONOSPACE)
+                    .size(14)
+                    .width(Fill),
+                button(fa_icon_solid("clone").size(14.))
+                    .on_press(Message::CopyDiagnostic)
+                    .style(button::text)
+                    .padding(5)
+            ]
+            .into(),
+        };
+
+        row![
+            column![
+                encoding_picker(&self.detected, Message::EncodingSet)
+                    .push(Space::new(Fill, Fill))
+                    .push(pick_list(&Mode::ALL[..], Some(self.mode), Message::ModeSet)),
+                text_editor(&self.contents)
+                    .placeholder("paste some cbor...")
+                    .on_action(Message::ContentsChanged)
+                    .height(Fill)
+                    .wrapping(text::Wrapping::Glyph)
+                    .style(|theme: &Theme, status| {
+                        let mut defaults = text_editor::default(theme, status);
+
+                        defaults.border.radius = 4.0.into();
+
+                        defaults
+                    }),
+            ]
+            .spacing(5)
+            .width(FillPortion(1))
+            .padding(5),
+            column![container(scrollable(decoded).width(Fill))
+                .width(Fill)
+                .height(Fill)
+                .style(|theme: &Theme| {
+                    let palette = theme.extended_palette();
+
+                    container::Style {
+                        border: Border {
+                            width: 1.0,
+                            radius: 4.0.into(),
+                            color: palette.background.strong.color,
+                        },
+                        ..Default::default()
+                    }
+                })
+                .padding(10)]
+            .push_maybe(
+                self.warning
+                    .as_ref()
+                    .map(|warning| text(warning).color([1.0, 0.0, 0.0]))
+            )
+            .spacing(5)
+            .padding(5)
+            .width(FillPortion(2))
+        ]
+        .padding(padding::top(20))
+        .into()
+    }
+}
@@ -0,0 +1,71 @@
+use iced::{
+    futures::{channel::mpsc, SinkExt, Stream},
+    stream,
+};
+use utxorpc::{
+    spec::sync::{follow_tip_response::Action, BlockRef, FollowTipRequest},
+    CardanoSyncClient,
+};
+
+use crate::rpc;
+
+use super::{Event, Follow, Message};
+
+/// Stream every roll forward and rollback from the endpoint, starting at the
+/// requested intersection or at the current tip.
+pub fn follow(follow: Follow) -> impl Stream<Item = Message> {
+    stream::channel(100, |mut output| async move {
+        let result = run(follow, &mut output).await;
+
+        // The tool stops following when it gets this, ending the subscription
+        let _ = output
+            .send(Message::Stopped(result.err().unwrap_or_default()))
+            .await;
+    })
+}
+
+async fn run(follow: Follow, output: &mut mpsc::Sender<Message>) -> Result<(), String> {
+    let mut client: CardanoSyncClient = rpc::connect(&follow.endpoint, &follow.api_key).await?;
+
+    let intersect = match follow.intersect {
+        Some((slot, hash)) => vec![BlockRef {
+            index: slot,
+            hash: hash.to_vec().into(),
+        }],
+        None => client
+            .read_tip()
+            .await
+            .map_err(|e| rpc::error(&e))?
+            .into_iter()
+            .collect(),
+    };
+
+    // Not using `LiveTip` as it spins forever once the server hangs up
+    let mut stream = client
+        .inner
+        .follow_tip(FollowTipRequest {
+            intersect,
+            field_mask: None,
+        })
+        .await
+        .map_err(|e| rpc::status_error(&e))?
+        .into_inner();
+
+    while let Some(response) = stream.message().await.map_err(|e| rpc::status_error(&e))? {
+        let event = match response.action {
+            Some(Action::Apply(block)) => {
+                Event::block(super::Action::RollForward, &block.native_bytes)
+            }
+            Some(Action::Undo(block)) => Event::block(super::Action::RollBack, &block.native_bytes),
+            Some(Action::Reset(point)) => Event::reset(point.index, &point.hash),
+            None => continue,
+        };
+
+        if output.send(Message::Event(Box::new(event))).await.is_err() {
+            // Nobody is listening anymore
+            return Ok(());
+        }
+    }
+
+    Err("the endpoint closed the stream".to_string())
+}
@@ -0,0 +1,7 @@
+mod follow;
+mod state;
+mod update;
+mod view;
+
+pub use state::*;
+pub use update::*;
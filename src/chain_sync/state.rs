@@ -0,0 +1,159 @@
+use std::collections::VecDeque;
+
+use pallas::{crypto::hash::Hash, ledger::traverse::Era};
+
+use crate::{block_inspector::Block, clock};
+
+/// Older events are dropped so a long running follow doesn't grow forever.
+pub const MAX_EVENTS: usize = 500;
+
+#[derive(Debug, Default)]
+pub struct State {
+    pub endpoint: String,
+    pub api_key: String,
+    pub intersect: String,
+    pub following: Option<Follow>,
+    /// Bumped on every start, so following the same point again reconnects
+    pub session: u64,
+    pub events: VecDeque<Event>,
+    pub warning: Option<String>,
+}
+
+/// Everything the subscription needs to connect, doubling as its identity.
+#[derive(Debug, Clone, Hash)]
+pub struct Follow {
+    pub endpoint: String,
+    pub api_key: String,
+    pub intersect: Option<(u64, Hash<32>)>,
+    pub session: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    RollForward,
+    RollBack,
+    /// The server moved us to another point, usually the intersection
+    Reset,
+}
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub action: Action,
+    pub received: String,
+    pub slot: Option<u64>,
+    pub hash: Option<Hash<32>>,
+    pub height: Option<u64>,
+    pub era: Option<Era>,
+    pub transactions: Option<usize>,
+    pub size: usize,
+}
+
+impl Event {
+    pub fn block(action: Action, cbor: &[u8]) -> Self {
+        let block = Block::decode(cbor).ok();
+
+        Self {
+            action,
+            received: clock::now(),
+            slot: block.as_ref().map(|b| b.header.slot),
+            hash: block.as_ref().map(|b| b.header.hash),
+            height: block.as_ref().map(|b| b.header.height),
+            era: block.as_ref().map(|b| b.era),
+            transactions: block.as_ref().map(|b| b.transactions.len()),
+            size: cbor.len(),
+        }
+    }
+
+    pub fn reset(slot: u64, hash: &[u8]) -> Self {
+        Self {
+            action: Action::Reset,
+            received: clock::now(),
+            slot: Some(slot),
+            hash: <[u8; 32]>::try_from(hash).ok().map(Hash::from),
+            height: None,
+            era: None,
+            transactions: None,
+            size: 0,
+        }
+    }
+}
+
+/// Parse an intersection point written as `slot,hash`, `slot.hash` or with
+/// any whitespace in between.
+pub fn parse_point(point: &str) -> Result<Option<(u64, Hash<32>)>, String> {
+    let point = point.trim();
+
+    if point.is_empty() {
+        return Ok(None);
+    }
+
+    let (slot, hash) = point
+        .split_once(|c: char| c == ',' || c == '.' || c.is_whitespace())
+        .ok_or("intersect must be a slot and a block hash")?;
+
+    let slot = slot
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid slot: {e}"))?;
+
+    let hash = hash
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid block hash: {e}"))?;
+
+    Ok(Some((slot, hash)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "9e4a7e2fa1a38ef2e4e4e49ab47d1e4b74f1ee1b6b0fbf3ad4a7e1a6c1bc28a6";
+
+    #[test]
+    fn points_can_be_written_several_ways() {
+        let point = Some((1000, HASH.parse().unwrap()));
+
+        for written in [
+            format!("1000,{HASH}"),
+            format!("1000.{HASH}"),
+            format!(" 1000 {HASH}\n"),
+            format!("1000, {HASH}"),
+        ] {
+            assert_eq!(parse_point(&written), Ok(point));
+        }
+
+        assert_eq!(parse_point("  "), Ok(None));
+    }
+
+    #[test]
+    fn bad_points_say_what_is_wrong() {
+        assert_eq!(
+            parse_point("1000"),
+            Err("intersect must be a slot and a block hash".to_string())
+        );
+        assert!(parse_point(&format!("tip,{HASH}"))
+            .unwrap_err()
+            .starts_with("invalid slot"));
+        assert!(parse_point("1000,abcd")
+            .unwrap_err()
+            .starts_with("invalid block hash"));
+    }
+
+    #[test]
+    fn resets_only_keep_well_formed_hashes() {
+        let hash: Hash<32> = HASH.parse().unwrap();
+
+        assert_eq!(Event::reset(5, hash.as_ref()).hash, Some(hash));
+        assert_eq!(Event::reset(5, &[1, 2, 3]).hash, None);
+    }
+
+    #[test]
+    fn undecodable_blocks_are_still_listed() {
+        let event = Event::block(Action::RollForward, &[0x80]);
+
+        assert_eq!(event.size, 1);
+        assert_eq!(event.slot, None);
+        assert_eq!(event.transactions, None);
+    }
+}
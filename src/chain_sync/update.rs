@@ -0,0 +1,116 @@
+use iced::{Subscription, Task};
+
+use super::{follow::follow, parse_point, Event, Follow, State, MAX_EVENTS};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    EndpointChanged(String),
+    ApiKeyChanged(String),
+    IntersectChanged(String),
+    Start,
+    Stop,
+    Clear,
+    Event(Box<Event>),
+    Stopped(String),
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            EndpointChanged(endpoint) => self.endpoint = endpoint,
+            ApiKeyChanged(api_key) => self.api_key = api_key,
+            IntersectChanged(intersect) => self.intersect = intersect,
+            Start => {
+                self.warning = None;
+
+                match parse_point(&self.intersect) {
+                    Ok(intersect) => {
+                        self.session += 1;
+                        self.following = Some(Follow {
+                            endpoint: self.endpoint.clone(),
+                            api_key: self.api_key.clone(),
+                            intersect,
+                            session: self.session,
+                        });
+                    }
+                    Err(e) => self.warning = Some(e),
+                }
+            }
+            Stop => self.following = None,
+            Clear => self.events.clear(),
+            Event(event) => {
+                self.events.push_front(*event);
+                self.events.truncate(MAX_EVENTS);
+            }
+            Stopped(e) => {
+                self.following = None;
+
+                if !e.is_empty() {
+                    self.warning = Some(e);
+                }
+            }
+        }
+
+        Task::none()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        match &self.following {
+            Some(following) => {
+                Subscription::run_with_id(following.clone(), follow(following.clone()))
+            }
+            None => Subscription::none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_start_is_a_new_session() {
+        let mut state = State::default();
+
+        let _ = state.update(Message::Start);
+        let _ = state.update(Message::Start);
+
+        assert_eq!(state.following.as_ref().map(|f| f.session), Some(2));
+        assert_eq!(state.warning, None);
+
+        let _ = state.update(Message::Stopped("the endpoint closed the stream".into()));
+
+        assert!(state.following.is_none());
+        assert!(state.warning.is_some());
+    }
+
+    #[test]
+    fn a_bad_intersect_doesnt_start() {
+        let mut state = State {
+            intersect: "1000".to_string(),
+            ..Default::default()
+        };
+
+        let _ = state.update(Message::Start);
+
+        assert!(state.following.is_none());
+        assert!(state.warning.is_some());
+    }
+
+    #[test]
+    fn only_the_latest_events_are_kept() {
+        let mut state = State::default();
+
+        for slot in 0..MAX_EVENTS as u64 + 10 {
+            let _ = state.update(Message::Event(Box::new(Event::reset(slot, &[]))));
+        }
+
+        assert_eq!(state.events.len(), MAX_EVENTS);
+        assert_eq!(
+            state.events.front().unwrap().slot,
+            Some(MAX_EVENTS as u64 + 9)
+        );
+        assert_eq!(state.events.back().unwrap().slot, Some(10));
+    }
+}
@@ -0,0 +1,109 @@
+use iced::{
+    padding,
+    widget::{button, column, row, scrollable, text, text_input},
+    Color, Element, Font,
+    Length::Fill,
+};
+use iced_font_awesome::fa_icon_solid;
+
+use super::{Action, Event, Message, State};
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        let connection = row![
+            text_input(
+                "utxo rpc endpoint, e.g. http://localhost:50051",
+                &self.endpoint
+            )
+            .on_input_maybe(self.following.is_none().then_some(Message::EndpointChanged)),
+            text_input("api key", &self.api_key)
+                .on_input_maybe(self.following.is_none().then_some(Message::ApiKeyChanged))
+                .secure(true)
+                .width(160),
+            text_input("intersect slot,hash (defaults to the tip)", &self.intersect)
+                .on_input_maybe(
+                    self.following
+                        .is_none()
+                        .then_some(Message::IntersectChanged)
+                ),
+            match self.following {
+                Some(_) => button(text("Stop"))
+                    .style(button::danger)
+                    .on_press(Message::Stop),
+                None => button(text("Follow"))
+                    .on_press_maybe((!self.endpoint.trim().is_empty()).then_some(Message::Start)),
+            },
+            button(text("Clear"))
+                .style(button::secondary)
+                .on_press_maybe((!self.events.is_empty()).then_some(Message::Clear)),
+        ]
+        .spacing(5);
+
+        let events: Element<_> = if self.events.is_empty() {
+            text(match self.following {
+                Some(_) => "waiting for blocks...",
+                None => "not following",
+            })
+            .into()
+        } else {
+            scrollable(
+                column(self.events.iter().map(event))
+                    .spacing(4)
+                    .padding(padding::right(15)),
+            )
+            .height(Fill)
+            .into()
+        };
+
+        column![connection]
+            .push_maybe(
+                self.warning
+                    .as_ref()
+                    .map(|warning| text(warning).color([1.0, 0.0, 0.0])),
+            )
+            .push(events)
+            .spacing(10)
+            .padding(padding::top(30).left(10).right(10).bottom(10))
+            .into()
+    }
+}
+
+fn event(event: &Event) -> Element<'_, Message> {
+    let (icon, label, color) = match event.action {
+        Action::RollForward => ("forward", "roll forward", Color::from_rgb(0.4, 0.8, 0.4)),
+        Action::RollBack => ("backward", "roll back", Color::from_rgb(1.0, 0.6, 0.2)),
+        Action::Reset => (
+            "location-crosshairs",
+            "reset",
+            Color::from_rgb(0.5, 0.7, 1.0),
+        ),
+    };
+
+    let or_dash = |value: Option<String>| value.unwrap_or("-".to_string());
+
+    row![
+        text(&event.received).width(70),
+        row![
+            fa_icon_solid(icon).size(14.).color(color),
+            text(label).color(color)
+        ]
+        .spacing(5)
+        .width(120),
+        text(or_dash(event.era.map(|era| format!("{era:?}")))).width(70),
+        text(or_dash(event.slot.map(|slot| format!("slot {slot}")))).width(120),
+        text(or_dash(event.height.map(|height| format!("#{height}")))).width(90),
+        text(or_dash(event.hash.map(|hash| hash.to_string())))
+            .font(Font::MONOSPACE)
+            .size(14)
+            .width(Fill),
+        text(or_dash(event.transactions.map(|n| format!("{n} txs")))).width(70),
+        text(if event.size > 0 {
+            format!("{} bytes", event.size)
+        } else {
+            "-".to_string()
+        })
+        .width(90),
+    ]
+    .spacing(10)
+    .into()
+}
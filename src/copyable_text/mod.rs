@@ -6,15 +6,15 @@ use iced::{
 };
 use iced_font_awesome::fa_icon_solid;
 
-pub fn copyable_text<Message>(
+pub fn copyable_text<'a, Message>(
     content: &str,
     on_copy: impl Fn(String) -> Message + 'static,
-) -> Element<Message>
+) -> Element<'a, Message>
 where
     Message: Clone + 'static,
 {
     row![
-        container(text(content).size(16).width(Fill).center())
+        container(text(content.to_string()).size(16).width(Fill).center())
             .style(container::bordered_box)
             .center_y(24),
         button(
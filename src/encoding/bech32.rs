@@ -0,0 +1,219 @@
+//! Bech32 as Cardano uses it, with the human readable prefixes from CIP-5
+//! and checksum errors narrowed down to the character that's wrong.
+
+use ::bech32::{FromBase32, ToBase32, Variant};
+
+use super::looks_hex;
+
+const SEPARATOR: char = '1';
+const CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const CHECKSUM_LENGTH: usize = 6;
+
+/// The human readable part and the bytes of the data part.
+pub fn decode_bech32(s: &str) -> Result<(String, Vec<u8>), String> {
+    let s = s.trim();
+
+    let (hrp, data, _) = ::bech32::decode(s).map_err(|e| match e {
+        ::bech32::Error::InvalidChecksum => checksum_error(s),
+        // Whatever's in the prefix is allowed, so look in the data first
+        ::bech32::Error::InvalidChar(c) => match data_position(s, c).or_else(|| s.find(c)) {
+            Some(i) => format!("Invalid bech32: '{c}' at position {} isn't allowed", i + 1),
+            None => format!("Invalid bech32: '{c}' isn't allowed"),
+        },
+        e => format!("Invalid bech32: {e}"),
+    })?;
+
+    let bytes = Vec::<u8>::from_base32(&data).map_err(|e| format!("Invalid bech32: {e}"))?;
+
+    Ok((hrp, bytes))
+}
+
+/// Where `c` first appears after the separator.
+fn data_position(s: &str, c: char) -> Option<usize> {
+    let separator = s.rfind(SEPARATOR)?;
+
+    s[separator + 1..].find(c).map(|i| separator + 1 + i)
+}
+
+pub fn encode_bech32(hrp: &str, bytes: &[u8]) -> String {
+    ::bech32::encode(hrp, bytes.to_base32(), Variant::Bech32).unwrap_or_else(|e| e.to_string())
+}
+
+/// Whether the checksum checks out, without looking for what's wrong if not.
+pub fn is_valid_bech32(s: &str) -> bool {
+    ::bech32::decode(s.trim()).is_ok()
+}
+
+/// Whether the string is shaped like bech32, checksum aside so that a typo
+/// still gets reported as one. Hex hashes often have a `1` followed by
+/// enough bech32 characters, those only count when the checksum holds.
+pub fn looks_bech32(s: impl AsRef<str>) -> bool {
+    let s = s.as_ref().trim();
+
+    if looks_hex(s) && !is_valid_bech32(s) {
+        return false;
+    }
+
+    if s != s.to_lowercase() && s != s.to_uppercase() {
+        return false;
+    }
+
+    let s = s.to_lowercase();
+
+    let Some((hrp, data)) = s.rsplit_once(SEPARATOR) else {
+        return false;
+    };
+
+    !hrp.is_empty()
+        && hrp.chars().all(|c| c.is_ascii_graphic())
+        && data.len() >= CHECKSUM_LENGTH
+        && data.chars().all(|c| CHARSET.contains(c))
+}
+
+/// What a CIP-5 prefix stands for.
+pub fn describe_hrp(hrp: &str) -> Option<&'static str> {
+    Some(match hrp {
+        "addr" => "mainnet address",
+        "addr_test" => "testnet address",
+        "stake" => "mainnet reward address",
+        "stake_test" => "testnet reward address",
+        "pool" => "stake pool id",
+        "drep" => "DRep id",
+        "cc_hot" => "constitutional committee hot credential",
+        "cc_cold" => "constitutional committee cold credential",
+        "gov_action" => "governance action id",
+        "asset" => "CIP-14 asset fingerprint",
+        "script" => "script hash",
+        "addr_vk" | "stake_vk" | "drep_vk" | "pool_vk" | "ed25519_pk" => "verification key",
+        "addr_xvk" | "stake_xvk" | "acct_xvk" | "root_xvk" | "drep_xvk" => {
+            "extended verification key"
+        }
+        "addr_sk" | "stake_sk" | "drep_sk" | "pool_sk" | "ed25519_sk" => "signing key",
+        "addr_xsk" | "stake_xsk" | "acct_xsk" | "root_xsk" | "drep_xsk" | "ed25519e_sk" => {
+            "extended signing key"
+        }
+        "addr_vkh" | "stake_vkh" | "drep_vkh" | "cc_hot_vkh" | "cc_cold_vkh" => {
+            "verification key hash"
+        }
+        "vrf_vk" => "VRF verification key",
+        "vrf_sk" => "VRF signing key",
+        "kes_vk" => "KES verification key",
+        "kes_sk" => "KES signing key",
+        "ed25519_sig" => "signature",
+        "datum" => "datum hash",
+        _ => return None,
+    })
+}
+
+/// The prefix and what it stands for, for showing next to decoded input.
+pub fn hrp_label(s: &str) -> Option<String> {
+    let (hrp, _) = s.trim().rsplit_once(SEPARATOR)?;
+    let hrp = hrp.to_lowercase();
+
+    Some(match describe_hrp(&hrp) {
+        Some(description) => format!("{hrp} ({description})"),
+        None => hrp,
+    })
+}
+
+/// Bech32's checksum can tell a single wrong character apart from anything
+/// worse, so try every replacement to point at it. The search only covers
+/// the data, so with no single fix there only have to be several wrong
+/// characters when the prefix itself is a known one.
+fn checksum_error(s: &str) -> String {
+    let lower = s.to_lowercase();
+
+    let Some(separator) = lower.rfind(SEPARATOR) else {
+        return "Invalid bech32 checksum".to_string();
+    };
+
+    let mut candidates = vec![];
+
+    for (i, c) in lower.char_indices().skip(separator + 1) {
+        for replacement in CHARSET.chars().filter(|r| *r != c) {
+            let mut candidate = lower.clone();
+            candidate.replace_range(i..i + 1, &replacement.to_string());
+
+            if matches!(::bech32::decode(&candidate), Ok((_, _, Variant::Bech32))) {
+                candidates.push((i, c, replacement));
+            }
+        }
+    }
+
+    match candidates.as_slice() {
+        [(i, c, replacement)] => format!(
+            "Invalid bech32 checksum, '{c}' at position {} should probably be '{replacement}'",
+            i + 1
+        ),
+        _ if describe_hrp(&lower[..separator]).is_some() => {
+            "Invalid bech32 checksum, more than one character is wrong".to_string()
+        }
+        _ => format!(
+            "Invalid bech32 checksum, the prefix '{}' or the separator may be wrong",
+            &lower[..separator]
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn substitute(s: &str, i: usize) -> String {
+        let c = s.as_bytes()[i] as char;
+        let replacement = CHARSET.chars().find(|r| *r != c).unwrap();
+
+        format!("{}{replacement}{}", &s[..i], &s[i + 1..])
+    }
+
+    #[test]
+    fn a_single_substitution_is_pointed_at() {
+        let valid = encode_bech32("addr_vk", &[42; 32]);
+
+        for i in [valid.find(SEPARATOR).unwrap() + 1, 20, valid.len() - 1] {
+            let c = valid.as_bytes()[i] as char;
+            let error = decode_bech32(&substitute(&valid, i)).unwrap_err();
+
+            assert!(
+                error.contains(&format!("at position {} should probably be '{c}'", i + 1)),
+                "{error}"
+            );
+        }
+    }
+
+    #[test]
+    fn a_wrong_prefix_is_not_taken_for_several_wrong_characters() {
+        let valid = encode_bech32("addr_vk", &[42; 32]);
+
+        let error = decode_bech32(&valid.replacen("addr_vk", "addr_vx", 1)).unwrap_err();
+        assert!(error.contains("prefix 'addr_vx'"), "{error}");
+
+        let error = decode_bech32(&substitute(&substitute(&valid, 20), 30)).unwrap_err();
+        assert!(error.contains("more than one character"), "{error}");
+    }
+
+    #[test]
+    fn invalid_characters_are_found_in_the_data() {
+        let valid = encode_bech32("addr_vk", &[42; 32]);
+        let i = valid.len() - 10;
+        let invalid = format!("{}b{}", &valid[..i], &valid[i + 1..]);
+
+        // There's also a 'b' in "bad", before the one that's wrong
+        let error = decode_bech32(&format!("bad{invalid}")).unwrap_err();
+
+        assert!(
+            error.contains(&format!("at position {} isn't allowed", i + 4)),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn hex_hashes_dont_look_like_bech32() {
+        // The last '1' is followed by plenty of bech32 characters
+        let hash = "3f1c2e7d9a0c4e6f8a2d1e3c5a7f9d0e2c4a6e8f0d2c4e6a8c0e2d4f6a8c0e2d";
+
+        assert!(looks_hex(hash));
+        assert!(!looks_bech32(hash));
+        assert!(looks_bech32(encode_bech32("addr_vk", &[42; 32])));
+    }
+}
@@ -1,16 +1,82 @@
 use std::fmt::Display;
 
+use base58::{FromBase58, FromBase58Error};
+use base64::{
+    prelude::{BASE64_STANDARD, BASE64_URL_SAFE_NO_PAD},
+    Engine,
+};
+use pallas::ledger::addresses::ByronAddress;
 use regex::Regex;
 
+use crate::cbor_explorer::decode_sequence;
+
+mod bech32;
+mod picker;
+mod text_envelope;
+
+pub use self::bech32::*;
+pub use picker::*;
+pub use text_envelope::*;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Encoding {
     UTF8,
     Hex,
     Base64,
+    /// The URL and filename safe alphabet, padded or not
+    Base64Url,
+    /// Byron addresses, mostly
+    Base58,
+    /// The data part, the human readable part only says what it is
+    Bech32,
+    /// cardano-cli's JSON, unwrapped according to its type
+    TextEnvelope,
 }
 
 impl Encoding {
-    pub const ALL: [Encoding; 3] = [Encoding::Hex, Encoding::Base64, Encoding::UTF8];
+    pub const ALL: [Encoding; 7] = [
+        Encoding::Hex,
+        Encoding::Base64,
+        Encoding::Base64Url,
+        Encoding::Base58,
+        Encoding::Bech32,
+        Encoding::UTF8,
+        Encoding::TextEnvelope,
+    ];
+
+    /// The bytes a pasted string stands for in this encoding.
+    pub fn decode(&self, s: &str) -> Result<Vec<u8>, String> {
+        match self {
+            Encoding::Hex => {
+                hex::decode(s.replace('\n', "")).map_err(|e| format!("Invalid hex: {}", e))
+            }
+            Encoding::Base64 => BASE64_STANDARD
+                .decode(s.replace('\n', ""))
+                .map_err(|e| format!("Invalid base64: {}", e)),
+            Encoding::Base64Url => BASE64_URL_SAFE_NO_PAD
+                .decode(s.replace('\n', "").trim_end_matches('='))
+                .map_err(|e| format!("Invalid base64url: {}", e)),
+            Encoding::Base58 => s.trim().from_base58().map_err(|e| match e {
+                FromBase58Error::InvalidBase58Character(c, i) => {
+                    format!("Invalid base58: '{c}' at position {} isn't allowed", i + 1)
+                }
+                FromBase58Error::InvalidBase58Length => "Invalid base58: bad length".to_string(),
+            }),
+            Encoding::UTF8 => Ok(s.as_bytes().to_vec()),
+            Encoding::Bech32 => decode_bech32(s).map(|(_, bytes)| bytes),
+            Encoding::TextEnvelope => TextEnvelope::parse(s)?.decode(),
+        }
+    }
+
+    /// What the input says about itself besides its bytes: the prefix of
+    /// bech32 and the type of a TextEnvelope.
+    pub fn label(&self, s: &str) -> Option<String> {
+        match self {
+            Encoding::Bech32 => hrp_label(s),
+            Encoding::TextEnvelope => TextEnvelope::parse(s).ok().map(|envelope| envelope.kind),
+            _ => None,
+        }
+    }
 }
 
 impl Display for Encoding {
@@ -22,21 +88,229 @@ impl Display for Encoding {
                 Encoding::UTF8 => "UTF8",
                 Encoding::Hex => "Hex",
                 Encoding::Base64 => "Base64",
+                Encoding::Base64Url => "Base64url",
+                Encoding::Base58 => "Base58",
+                Encoding::Bech32 => "Bech32",
+                Encoding::TextEnvelope => "TextEnvelope",
             }
         )
     }
 }
 
-pub fn detect_encoding(s: impl AsRef<str>) -> Encoding {
-    if looks_hex(&s) {
-        Encoding::Hex
-    } else if looks_base64(&s) {
-        Encoding::Base64
-    } else {
-        Encoding::UTF8
+/// How well an encoding fits the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Confidence::Low => "unlikely",
+                Confidence::Medium => "possible",
+                Confidence::High => "likely",
+            }
+        )
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub encoding: Encoding,
+    pub confidence: Confidence,
+    /// What the decoded input turned out to be, e.g. CBOR
+    pub note: Option<&'static str>,
+}
+
+impl Candidate {
+    fn new(encoding: Encoding, confidence: Confidence, note: Option<&'static str>) -> Self {
+        Self {
+            encoding,
+            confidence,
+            note,
+        }
+    }
+}
+
+/// Every encoding the input could be in, most likely first. Anything is
+/// UTF8, so there's always at least one.
+pub fn detect_encoding(s: impl AsRef<str>) -> Vec<Candidate> {
+    let s = s.as_ref();
+    let mut candidates = vec![];
+
+    if looks_text_envelope(s) {
+        candidates.push(Candidate::new(
+            Encoding::TextEnvelope,
+            Confidence::High,
+            None,
+        ));
+    }
+
+    let hex = looks_hex(s);
+
+    if hex {
+        // A lone item is hard to hit by chance, unlike a sequence of them
+        let cbor = hex::decode(s.replace('\n', ""))
+            .ok()
+            .is_some_and(|bytes| decode_sequence(&bytes).is_ok_and(|items| items.len() == 1));
+
+        // Plain numbers are as likely to be meant as text
+        let confidence = if s.chars().all(|c| c.is_ascii_digit() || c == '\n') {
+            Confidence::Medium
+        } else {
+            Confidence::High
+        };
+
+        candidates.push(Candidate::new(
+            Encoding::Hex,
+            confidence,
+            cbor.then_some("CBOR"),
+        ));
+    }
+
+    if looks_bech32(s) {
+        let (confidence, note) = if is_valid_bech32(s) {
+            (Confidence::High, None)
+        } else {
+            (Confidence::Medium, Some("bad checksum"))
+        };
+
+        candidates.push(Candidate::new(Encoding::Bech32, confidence, note));
+    }
+
+    if looks_base58(s) {
+        let (confidence, note) = match ByronAddress::from_base58(s.trim()) {
+            Ok(_) => (Confidence::High, Some("Byron address")),
+            Err(_) => (Confidence::Low, None),
+        };
+
+        candidates.push(Candidate::new(Encoding::Base58, confidence, note));
+    }
+
+    let base64 = looks_base64(s);
+
+    if base64 {
+        // Hex digits are all base64 digits too, but it's rarely meant that way
+        let confidence = if hex {
+            Confidence::Low
+        } else {
+            Confidence::Medium
+        };
+
+        candidates.push(Candidate::new(Encoding::Base64, confidence, None));
+    }
+
+    // Only worth telling apart from plain base64 by the characters it swaps in
+    if looks_base64url(s) && (!base64 || s.contains(['-', '_'])) {
+        let confidence = if s.contains(['-', '_']) {
+            Confidence::Medium
+        } else {
+            Confidence::Low
+        };
+
+        candidates.push(Candidate::new(Encoding::Base64Url, confidence, None));
+    }
+
+    // Text is as plausible as the best of the rest, short of a sure thing
+    let confidence = match candidates.iter().map(|c| c.confidence).max() {
+        Some(Confidence::High) => Confidence::Low,
+        Some(Confidence::Medium) => Confidence::Medium,
+        _ => Confidence::High,
+    };
+
+    candidates.push(Candidate::new(Encoding::UTF8, confidence, None));
+
+    // Stable, so equally likely encodings keep the order they're checked in
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.confidence));
+    candidates
+}
+
+/// The best guess at the input's encoding.
+pub fn likely_encoding(s: impl AsRef<str>) -> Encoding {
+    detect_encoding(s).remove(0).encoding
+}
+
+/// The other encodings that fit about as well as the best guess, to point
+/// out before it's silently relied on.
+pub fn ambiguity(choices: &[Choice]) -> Option<String> {
+    let others: Vec<_> = choices
+        .iter()
+        .skip(1)
+        .filter(|choice| {
+            choice
+                .fit
+                .as_ref()
+                .is_some_and(|fit| fit.confidence > Confidence::Low)
+        })
+        .map(|choice| choice.encoding.to_string())
+        .collect();
+
+    (!others.is_empty()).then(|| format!("could also be {}", others.join(" or ")))
+}
+
+/// An encoding as offered in a pick list, with how well it fits the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Choice {
+    pub encoding: Encoding,
+    pub fit: Option<Candidate>,
+}
+
+impl Display for Choice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.fit {
+            Some(Candidate {
+                confidence,
+                note: Some(note),
+                ..
+            }) => write!(f, "{} ({note}, {confidence})", self.encoding),
+            Some(Candidate { confidence, .. }) => write!(f, "{} ({confidence})", self.encoding),
+            None => write!(f, "{}", self.encoding),
+        }
+    }
+}
+
+/// The pick list options for the input, the encodings that fit first, and
+/// the one to show as picked: `chosen`, or else the best guess.
+pub fn choices(s: impl AsRef<str>, chosen: Option<&Encoding>) -> (Vec<Choice>, Choice) {
+    let candidates = detect_encoding(s);
+
+    let picked = chosen.unwrap_or(&candidates[0].encoding).clone();
+
+    let rest = Encoding::ALL
+        .into_iter()
+        .filter(|encoding| !candidates.iter().any(|c| c.encoding == *encoding))
+        .map(|encoding| Choice {
+            encoding,
+            fit: None,
+        })
+        .collect::<Vec<_>>();
+
+    let choices: Vec<_> = candidates
+        .into_iter()
+        .map(|candidate| Choice {
+            encoding: candidate.encoding.clone(),
+            fit: Some(candidate),
+        })
+        .chain(rest)
+        .collect();
+
+    let picked = choices
+        .iter()
+        .find(|choice| choice.encoding == picked)
+        .cloned()
+        .unwrap_or(Choice {
+            encoding: picked,
+            fit: None,
+        });
+
+    (choices, picked)
+}
+
 pub fn looks_base64(s: impl AsRef<str>) -> bool {
     let s = s.as_ref().replace('\n', "");
     Regex::new(r"^([A-Za-z0-9+/]{4})*([A-Za-z0-9+/]{3}=|[A-Za-z0-9+/]{2}==)?$")
@@ -56,3 +330,53 @@ pub fn looks_hex(s: impl AsRef<str>) -> bool {
     }
     true
 }
+
+pub fn looks_base64url(s: impl AsRef<str>) -> bool {
+    let s = s.as_ref().replace('\n', "");
+    let s = s.trim_end_matches('=');
+
+    !s.is_empty()
+        && s.len() % 4 != 1
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub fn looks_base58(s: impl AsRef<str>) -> bool {
+    let s = s.as_ref().trim();
+
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fit(s: &str, encoding: Encoding) -> Option<Candidate> {
+        detect_encoding(s)
+            .into_iter()
+            .find(|candidate| candidate.encoding == encoding)
+    }
+
+    #[test]
+    fn hex_beats_base64_and_notes_a_lone_cbor_item() {
+        // A 30 byte byte string, a single item 64 hex digits long
+        let cbor = format!("581e{}", "ab".repeat(30));
+        // Not CBOR, just maps nested in maps until the input runs out
+        let hash = "a1".repeat(32);
+
+        for s in [&cbor, &hash] {
+            assert_eq!(s.len(), 64);
+            assert_eq!(likely_encoding(s), Encoding::Hex);
+            assert_eq!(fit(s, Encoding::Hex).unwrap().confidence, Confidence::High);
+            assert_eq!(
+                fit(s, Encoding::Base64).unwrap().confidence,
+                Confidence::Low
+            );
+        }
+
+        assert_eq!(fit(&cbor, Encoding::Hex).unwrap().note, Some("CBOR"));
+        assert_eq!(fit(&hash, Encoding::Hex).unwrap().note, None);
+    }
+}
@@ -0,0 +1,68 @@
+use iced::{
+    widget::{pick_list, row, text, Row, Space},
+    Length::{Fill, Shrink},
+};
+
+use super::{ambiguity, choices, Choice, Encoding};
+
+/// Everything the encoding picker shows for some input, worked out when the
+/// input or the chosen encoding changes rather than on every frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detected {
+    pub choices: Vec<Choice>,
+    pub picked: Choice,
+    /// What the picked encoding says about the input, e.g. a bech32 prefix
+    pub label: Option<String>,
+    /// What else the input could be, until an encoding is chosen
+    pub ambiguity: Option<String>,
+}
+
+impl Detected {
+    pub fn new(contents: &str, chosen: Option<&Encoding>) -> Self {
+        let (choices, picked) = choices(contents, chosen);
+        let label = picked.encoding.label(contents);
+        let ambiguity = chosen.is_none().then(|| ambiguity(&choices)).flatten();
+
+        Self {
+            choices,
+            picked,
+            label,
+            ambiguity,
+        }
+    }
+
+    /// The encoding to decode the input with.
+    pub fn encoding(&self) -> &Encoding {
+        &self.picked.encoding
+    }
+}
+
+impl Default for Detected {
+    fn default() -> Self {
+        Self::new("", None)
+    }
+}
+
+/// The encoding pick list, along with what the picked encoding says about
+/// the input and what else it could be.
+pub fn encoding_picker<'a, Message>(
+    detected: &'a Detected,
+    on_pick: impl Fn(Encoding) -> Message + 'a,
+) -> Row<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    row![
+        "Encoding?",
+        Space::new(10, Fill),
+        pick_list(
+            &detected.choices[..],
+            Some(detected.picked.clone()),
+            move |choice: Choice| on_pick(choice.encoding)
+        ),
+    ]
+    .push_maybe(detected.label.as_deref().map(text))
+    .push_maybe(detected.ambiguity.as_deref().map(text))
+    .spacing(10)
+    .height(Shrink)
+}
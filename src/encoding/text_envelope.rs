@@ -0,0 +1,157 @@
+//! The JSON cardano-cli reads and writes keys, scripts, certificates and txs
+//! as, with the cbor in hex and a type saying what it is.
+
+use pallas::codec::minicbor::{Decoder, Encoder};
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEnvelope {
+    pub kind: String,
+    pub description: String,
+    pub cbor_hex: String,
+}
+
+/// What an envelope holds, going by its type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Contents {
+    /// A key wrapped in a cbor byte string
+    Key,
+    /// A Plutus script wrapped in a cbor byte string
+    Script,
+    Tx,
+    /// Certificates, witnesses, native scripts and the like, plain cbor
+    Other,
+}
+
+impl TextEnvelope {
+    pub fn new(kind: impl Into<String>, description: impl Into<String>, cbor: &[u8]) -> Self {
+        Self {
+            kind: kind.into(),
+            description: description.into(),
+            cbor_hex: hex::encode(cbor),
+        }
+    }
+
+    /// An envelope around `bytes` wrapped in a cbor byte string, the way keys
+    /// and scripts are stored.
+    pub fn wrapping(kind: impl Into<String>, description: impl Into<String>, bytes: &[u8]) -> Self {
+        let mut e = Encoder::new(vec![]);
+        e.bytes(bytes).unwrap();
+
+        Self::new(kind, description, &e.into_writer())
+    }
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let json: Value =
+            serde_json::from_str(s).map_err(|e| format!("Invalid TextEnvelope: {e}"))?;
+
+        let field = |name: &str| json.get(name).and_then(Value::as_str).map(str::to_string);
+
+        Ok(Self {
+            kind: field("type").ok_or("The TextEnvelope has no type")?,
+            description: field("description").unwrap_or_default(),
+            cbor_hex: field("cborHex").ok_or("The TextEnvelope has no cborHex")?,
+        })
+    }
+
+    pub fn contents(&self) -> Contents {
+        let kind = self.kind.as_str();
+
+        if kind.contains("SigningKey") || kind.contains("VerificationKey") {
+            Contents::Key
+        } else if kind.starts_with("PlutusScript") {
+            Contents::Script
+        } else if [
+            "Tx ",
+            "Witnessed Tx ",
+            "Unwitnessed Tx ",
+            "TxSigned",
+            "TxUnsigned",
+        ]
+        .iter()
+        .any(|prefix| kind.starts_with(prefix))
+        {
+            Contents::Tx
+        } else {
+            Contents::Other
+        }
+    }
+
+    /// The bytes the envelope stands for: keys and scripts come out of their
+    /// byte string, everything else is the cbor as is.
+    pub fn decode(&self) -> Result<Vec<u8>, String> {
+        let cbor =
+            hex::decode(self.cbor_hex.trim()).map_err(|e| format!("Invalid cborHex: {e}"))?;
+
+        match self.contents() {
+            Contents::Key | Contents::Script => Decoder::new(&cbor)
+                .bytes()
+                .map(|bytes| bytes.to_vec())
+                .map_err(|e| format!("A {} should be a cbor byte string: {e}", self.kind)),
+            Contents::Tx | Contents::Other => Ok(cbor),
+        }
+    }
+
+    /// Formatted the way cardano-cli writes it.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\n    \"type\": {},\n    \"description\": {},\n    \"cborHex\": \"{}\"\n}}",
+            Value::from(self.kind.as_str()),
+            Value::from(self.description.as_str()),
+            self.cbor_hex
+        )
+    }
+
+    /// Write the envelope to `path`, never over an existing signing key
+    /// since losing one can mean losing whatever it holds.
+    pub async fn save(self, path: String) -> Result<String, String> {
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .create_new(self.kind.contains("SigningKey"))
+            .open(&path)
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::AlreadyExists => {
+                    format!("{path} already exists, refusing to overwrite a signing key")
+                }
+                _ => format!("Could not write {path}: {e}"),
+            })?;
+
+        file.write_all((self.to_json() + "\n").as_bytes())
+            .await
+            .map_err(|e| format!("Could not write {path}: {e}"))?;
+
+        Ok(path)
+    }
+}
+
+pub fn looks_text_envelope(s: impl AsRef<str>) -> bool {
+    let s = s.as_ref().trim();
+
+    s.starts_with('{') && s.contains("\"cborHex\"") && TextEnvelope::parse(s).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn signing_keys_are_never_overwritten() {
+        let dir = std::env::temp_dir().join(format!("pluton-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let path = |name: &str| dir.join(name).to_string_lossy().into_owned();
+        let key = TextEnvelope::wrapping("PaymentSigningKeyShelley_ed25519", "", &[1; 32]);
+        let tx = TextEnvelope::new("Witnessed Tx ConwayEra", "", &[0x80]);
+
+        assert!(key.clone().save(path("payment.skey")).await.is_ok());
+        assert!(key.save(path("payment.skey")).await.is_err());
+        assert!(tx.clone().save(path("tx.signed")).await.is_ok());
+        assert!(tx.save(path("tx.signed")).await.is_ok());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
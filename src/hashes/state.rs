@@ -1,11 +1,12 @@
 use iced::widget::text_editor;
 
-use crate::encoding::Encoding;
+use crate::encoding::{Detected, Encoding};
 
 #[derive(Debug)]
 pub struct State {
     pub contents: text_editor::Content,
     pub encoding: Option<Encoding>,
+    pub detected: Detected,
     pub warning: Option<String>,
     pub blake2b_256: String,
     pub blake2b_224: String,
@@ -34,6 +35,7 @@ impl Default for State {
         Self {
             contents: text_editor::Content::default(),
             encoding: None,
+            detected: Detected::default(),
             warning: None,
             blake2b_256: "".to_string(),
             blake2b_224: "".to_string(),
@@ -1,10 +1,9 @@
-use base64::{prelude::BASE64_STANDARD, Engine};
 use iced::{clipboard, widget::text_editor, Task};
 use pallas::crypto::hash::Hasher;
 
 use sha2::Digest;
 
-use crate::encoding::{detect_encoding, Encoding};
+use crate::encoding::{Detected, Encoding};
 
 use super::State;
 
@@ -38,30 +37,21 @@ impl State {
     fn update_hashes(&mut self) {
         let contents = self.text();
 
+        self.detected = Detected::new(&contents, self.encoding.as_ref());
+
         if contents.is_empty() {
             self.clear_hashes();
             self.warning = Some("Empty String".to_string());
             return;
         }
 
-        let contents = match self.encoding.clone().unwrap_or(detect_encoding(&contents)) {
-            Encoding::Hex => match hex::decode(contents.replace('\n', "")) {
-                Ok(raw) => raw,
-                Err(e) => {
-                    self.warning = Some(format!("Invalid hex: {}", e));
-                    self.clear_hashes();
-                    return;
-                }
-            },
-            Encoding::Base64 => match BASE64_STANDARD.decode(contents.replace('\n', "")) {
-                Ok(raw) => raw,
-                Err(e) => {
-                    self.warning = Some(format!("Invalid base64: {}", e));
-                    self.clear_hashes();
-                    return;
-                }
-            },
-            Encoding::UTF8 => contents.into_bytes(),
+        let contents = match self.detected.encoding().decode(&contents) {
+            Ok(raw) => raw,
+            Err(e) => {
+                self.warning = Some(e);
+                self.clear_hashes();
+                return;
+            }
         };
 
         self.blake2b_224 = hex::encode(Hasher::<224>::hash(&contents));
@@ -1,31 +1,18 @@
 use iced::{
-    widget::{column, pick_list, row, text, text_editor, Space},
+    widget::{column, row, text, text_editor},
     Element,
-    Length::{Fill, FillPortion, Shrink},
+    Length::{Fill, FillPortion},
     Theme,
 };
 
 use super::{Message, State};
-use crate::{
-    copyable_text::copyable_text,
-    encoding::{detect_encoding, Encoding},
-};
+use crate::{copyable_text::copyable_text, encoding::encoding_picker};
 
 impl State {
-    pub fn view(&self) -> Element<Message> {
-        let contents = self.text();
+    pub fn view(&self) -> Element<'_, Message> {
         row![
             column![
-                row![
-                    "Encoding?",
-                    Space::new(10, Fill),
-                    pick_list(
-                        &Encoding::ALL[..],
-                        Some(self.encoding.clone().unwrap_or(detect_encoding(contents))),
-                        Message::EncodingSet,
-                    )
-                ]
-                .height(Shrink),
+                encoding_picker(&self.detected, Message::EncodingSet),
                 text_editor(&self.contents)
                     .placeholder("paste some text...")
                     .on_action(Message::ContentsChanged)
@@ -0,0 +1,320 @@
+use std::fmt::Display;
+
+use pallas::{
+    crypto::hash::{Hash, Hasher},
+    ledger::addresses::{
+        Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart, StakeAddress,
+    },
+    wallet::keystore::hd::Bip32PrivateKey,
+};
+
+use crate::encoding::{decode_bech32, encode_bech32, looks_bech32, looks_hex};
+
+const HARDENED: u32 = 0x8000_0000;
+
+/// CIP-1852's purpose and Cardano's coin type, both hardened.
+const PURPOSE: u32 = 1852;
+const COIN_TYPE: u32 = 1815;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Role {
+    #[default]
+    External,
+    Internal,
+    Stake,
+}
+
+impl Role {
+    pub const ALL: [Role; 3] = [Role::External, Role::Internal, Role::Stake];
+
+    fn index(&self) -> u32 {
+        match self {
+            Role::External => 0,
+            Role::Internal => 1,
+            Role::Stake => 2,
+        }
+    }
+
+    /// CIP-5 prefix for keys of this role.
+    fn prefix(&self) -> &'static str {
+        match self {
+            Role::External | Role::Internal => "addr",
+            Role::Stake => "stake",
+        }
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::External => write!(f, "0 external"),
+            Role::Internal => write!(f, "1 internal (change)"),
+            Role::Stake => write!(f, "2 stake"),
+        }
+    }
+}
+
+/// A derived key pair in every form wallets and cardano-cli deal in.
+#[derive(Debug, Clone)]
+pub struct Key {
+    pub path: String,
+    pub xsk: String,
+    pub xvk: String,
+    pub vk: String,
+    pub hash: Hash<28>,
+    /// The hash in bech32, for the roles CIP-5 has a prefix for
+    pub vkh: Option<String>,
+}
+
+impl Key {
+    fn new(key: &Bip32PrivateKey, path: String, prefix: &str, hashed: bool) -> Self {
+        let public = key.to_public();
+        let xvk = public.as_bytes();
+        let vk = &xvk[..32];
+        let hash = Hasher::<224>::hash(vk);
+
+        Self {
+            path,
+            xsk: encode_bech32(&format!("{prefix}_xsk"), &key.as_bytes()),
+            xvk: encode_bech32(&format!("{prefix}_xvk"), &xvk),
+            vk: encode_bech32(&format!("{prefix}_vk"), vk),
+            hash,
+            vkh: hashed.then(|| encode_bech32(&format!("{prefix}_vkh"), hash.as_ref())),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Derived {
+    pub root: Key,
+    pub account: Key,
+    pub key: Key,
+    /// The account's first stake key, which payment keys are paired with in
+    /// base addresses
+    pub stake: Option<Key>,
+    pub addresses: Vec<(String, String)>,
+}
+
+/// The root key from either a BIP39 mnemonic, as Icarus wallets derive it,
+/// or an already derived root key in bech32 or hex.
+pub fn root(input: &str, passphrase: &str) -> Result<Bip32PrivateKey, String> {
+    let words: Vec<_> = input.split_whitespace().collect();
+
+    if words.len() > 1 {
+        return Bip32PrivateKey::from_bip39_mnenomic(words.join(" "), passphrase.to_string())
+            .map_err(|e| e.to_string());
+    }
+
+    let input = input.trim();
+
+    let bytes = if looks_hex(input) {
+        hex::decode(input).map_err(|e| format!("Invalid hex: {e}"))?
+    } else if looks_bech32(input) {
+        match decode_bech32(input)? {
+            (hrp, bytes) if hrp == "root_xsk" || hrp == "xprv" => bytes,
+            (hrp, _) => return Err(format!("Expected a root_xsk or xprv key, got {hrp}")),
+        }
+    } else {
+        return Err("Expected a mnemonic, a root_xsk or xprv key, or hex".to_string());
+    };
+
+    let bytes = bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("Root keys are 96 bytes, got {}", bytes.len()))?;
+
+    Bip32PrivateKey::from_bytes(bytes).map_err(|e| e.to_string())
+}
+
+/// Derive `m/1852'/1815'/account'/role/index` along with the account's stake
+/// key and the addresses they make.
+pub fn derive(
+    root: &Bip32PrivateKey,
+    account: u32,
+    role: Role,
+    index: u32,
+) -> Result<Derived, String> {
+    if account >= HARDENED || index >= HARDENED {
+        return Err(format!("Indexes must be below {HARDENED}"));
+    }
+
+    let account_key = root
+        .derive(HARDENED + PURPOSE)
+        .derive(HARDENED + COIN_TYPE)
+        .derive(HARDENED + account);
+
+    let account_path = format!("m/{PURPOSE}'/{COIN_TYPE}'/{account}'");
+
+    let role_key = |role: Role, index| {
+        Key::new(
+            &account_key.derive(role.index()).derive(index),
+            format!("{account_path}/{}/{index}", role.index()),
+            role.prefix(),
+            true,
+        )
+    };
+
+    let key = role_key(role, index);
+    let stake = (role != Role::Stake).then(|| role_key(Role::Stake, 0));
+
+    let addresses = [Network::Mainnet, Network::Testnet]
+        .into_iter()
+        .flat_map(|network| match &stake {
+            Some(stake) => addresses(network, Some(key.hash), stake.hash),
+            None => addresses(network, None, key.hash),
+        })
+        .collect();
+
+    Ok(Derived {
+        root: Key::new(root, "m".to_string(), "root", false),
+        account: Key::new(&account_key, account_path, "acct", false),
+        key,
+        stake,
+        addresses,
+    })
+}
+
+fn addresses(
+    network: Network,
+    payment: Option<Hash<28>>,
+    stake: Hash<28>,
+) -> Vec<(String, String)> {
+    let name = match network {
+        Network::Mainnet => "mainnet",
+        _ => "testnet",
+    };
+
+    let bech32 = |address: ShelleyAddress| address.to_bech32().unwrap_or_else(|e| e.to_string());
+
+    let mut addresses = vec![];
+
+    if let Some(payment) = payment {
+        let payment = ShelleyPaymentPart::Key(payment);
+
+        addresses.push((
+            format!("{name} base"),
+            bech32(ShelleyAddress::new(
+                network,
+                payment.clone(),
+                ShelleyDelegationPart::Key(stake),
+            )),
+        ));
+        addresses.push((
+            format!("{name} enterprise"),
+            bech32(ShelleyAddress::new(
+                network,
+                payment,
+                ShelleyDelegationPart::Null,
+            )),
+        ));
+    }
+
+    // Reward addresses can only be made from the delegation part of an
+    // address
+    let reward = StakeAddress::try_from(ShelleyAddress::new(
+        network,
+        ShelleyPaymentPart::Key(stake),
+        ShelleyDelegationPart::Key(stake),
+    ));
+
+    if let Ok(reward) = reward {
+        addresses.push((
+            format!("{name} reward"),
+            reward.to_bech32().unwrap_or_else(|e| e.to_string()),
+        ));
+    }
+
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::ledger::addresses::Address;
+
+    use super::*;
+
+    // The wallet CIP-19's payment key test vectors are derived from
+    const MNEMONIC: &str = "test walk nut penalty hip pave soap entry language right filter choice";
+
+    fn address<'a>(derived: &'a Derived, label: &str) -> &'a str {
+        derived
+            .addresses
+            .iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, address)| address.as_str())
+            .unwrap()
+    }
+
+    #[test]
+    fn cip_19_keys_and_addresses() {
+        let root = root(MNEMONIC, "").unwrap();
+        let derived = derive(&root, 0, Role::External, 0).unwrap();
+        let stake = derived.stake.as_ref().unwrap();
+
+        assert_eq!(derived.key.path, "m/1852'/1815'/0'/0/0");
+        assert_eq!(
+            derived.key.vk,
+            "addr_vk1w0l2sr2zgfm26ztc6nl9xy8ghsk5sh6ldwemlpmp9xylzy4dtf7st80zhd"
+        );
+        assert_eq!(
+            address(&derived, "mainnet enterprise"),
+            "addr1vx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzers66hrl8"
+        );
+
+        // CIP-19's stake key comes from elsewhere, so only check that base
+        // and reward addresses are made from the account's first stake key
+        assert_eq!(stake.path, "m/1852'/1815'/0'/2/0");
+
+        let Ok(Address::Shelley(base)) = Address::from_bech32(address(&derived, "mainnet base"))
+        else {
+            panic!("expected a shelley address");
+        };
+
+        assert_eq!(base.payment(), &ShelleyPaymentPart::Key(derived.key.hash));
+        assert_eq!(base.delegation(), &ShelleyDelegationPart::Key(stake.hash));
+        assert_eq!(
+            StakeAddress::try_from(base).unwrap().to_bech32().unwrap(),
+            address(&derived, "mainnet reward")
+        );
+        assert!(address(&derived, "testnet base").starts_with("addr_test1q"));
+    }
+
+    #[test]
+    fn stake_keys_only_make_reward_addresses() {
+        let root = root(MNEMONIC, "").unwrap();
+        let derived = derive(&root, 0, Role::Stake, 0).unwrap();
+
+        assert!(derived.stake.is_none());
+        assert!(derived.key.vk.starts_with("stake_vk1"));
+
+        let labels: Vec<_> = derived.addresses.iter().map(|(l, _)| l.as_str()).collect();
+
+        assert_eq!(labels, ["mainnet reward", "testnet reward"]);
+    }
+
+    #[test]
+    fn root_keys_can_be_pasted_instead_of_a_mnemonic() {
+        let from_mnemonic = root(MNEMONIC, "").unwrap();
+        let bytes = from_mnemonic.as_bytes();
+
+        for input in [hex::encode(&bytes), encode_bech32("root_xsk", &bytes)] {
+            assert_eq!(root(&input, "").unwrap().as_bytes(), bytes);
+        }
+
+        assert_eq!(
+            root(&encode_bech32("addr_xsk", &bytes), "").unwrap_err(),
+            "Expected a root_xsk or xprv key, got addr_xsk"
+        );
+        assert_eq!(
+            root("abcd", "").unwrap_err(),
+            "Root keys are 96 bytes, got 2"
+        );
+    }
+
+    #[test]
+    fn indexes_must_not_be_hardened() {
+        let root = root(MNEMONIC, "").unwrap();
+
+        assert!(derive(&root, HARDENED, Role::External, 0).is_err());
+        assert!(derive(&root, 0, Role::External, HARDENED).is_err());
+    }
+}
@@ -0,0 +1,8 @@
+mod keys;
+mod state;
+mod update;
+mod view;
+
+pub use keys::*;
+pub use state::*;
+pub use update::*;
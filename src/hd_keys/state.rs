@@ -0,0 +1,19 @@
+use iced::widget::text_editor;
+use pallas::wallet::keystore::hd::Bip32PrivateKey;
+
+use super::{Derived, Role};
+
+#[derive(Debug, Default)]
+pub struct State {
+    /// A mnemonic or a root key
+    pub secret: text_editor::Content,
+    pub passphrase: String,
+    pub account: String,
+    pub role: Role,
+    pub index: String,
+    /// Kept apart from the derived keys since stretching a mnemonic into it
+    /// is by far the slowest part
+    pub root: Option<Bip32PrivateKey>,
+    pub derived: Option<Box<Derived>>,
+    pub warning: Option<String>,
+}
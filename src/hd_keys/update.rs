@@ -0,0 +1,98 @@
+use iced::{clipboard, widget::text_editor, Task};
+
+use super::{derive, root, Role, State};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    SecretChanged(text_editor::Action),
+    PassphraseChanged(String),
+    AccountChanged(String),
+    RoleSet(Role),
+    IndexChanged(String),
+    Copy(String),
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            SecretChanged(action) => {
+                let edit = action.is_edit();
+
+                self.secret.perform(action);
+
+                if edit {
+                    self.derive_root();
+                }
+            }
+            PassphraseChanged(passphrase) => {
+                self.passphrase = passphrase;
+                self.derive_root();
+            }
+            AccountChanged(account) => {
+                self.account = account;
+                self.derive();
+            }
+            RoleSet(role) => {
+                self.role = role;
+                self.derive();
+            }
+            IndexChanged(index) => {
+                self.index = index;
+                self.derive();
+            }
+            Copy(s) => return clipboard::write(s),
+        }
+
+        Task::none()
+    }
+
+    fn derive_root(&mut self) {
+        self.root = None;
+
+        let secret = self.secret.text();
+
+        if !secret.trim().is_empty() {
+            match root(&secret, &self.passphrase) {
+                Ok(root) => self.root = Some(root),
+                Err(e) => {
+                    self.derived = None;
+                    self.warning = Some(e);
+                    return;
+                }
+            }
+        }
+
+        self.derive();
+    }
+
+    fn derive(&mut self) {
+        self.derived = None;
+        self.warning = None;
+
+        let Some(root) = &self.root else {
+            return;
+        };
+
+        let index = |index: &str, name: &str| {
+            let index = index.trim();
+
+            if index.is_empty() {
+                return Ok(0);
+            }
+
+            index
+                .parse()
+                .map_err(|_| format!("{name} must be a whole number"))
+        };
+
+        let derived = index(&self.account, "The account")
+            .and_then(|account| Ok((account, index(&self.index, "The index")?)))
+            .and_then(|(account, index)| derive(root, account, self.role, index));
+
+        match derived {
+            Ok(derived) => self.derived = Some(derived.into()),
+            Err(e) => self.warning = Some(e),
+        }
+    }
+}
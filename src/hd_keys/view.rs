@@ -0,0 +1,106 @@
+use iced::{
+    padding,
+    widget::{column, pick_list, row, scrollable, text, text_editor, text_input, Column},
+    Color, Element, Font,
+    Length::{Fill, FillPortion},
+    Theme,
+};
+
+use super::{Key, Message, Role, State};
+use crate::copyable_text::copyable_text;
+
+const RED: Color = Color::from_rgb(1.0, 0.0, 0.0);
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        row![
+            column![
+                text_editor(&self.secret)
+                    .placeholder("bip39 mnemonic or root_xsk...")
+                    .on_action(Message::SecretChanged)
+                    .height(Fill)
+                    .wrapping(text::Wrapping::Word)
+                    .style(|theme: &Theme, status| {
+                        let mut defaults = text_editor::default(theme, status);
+
+                        defaults.border.radius = 4.0.into();
+
+                        defaults
+                    }),
+                text_input("passphrase", &self.passphrase)
+                    .on_input(Message::PassphraseChanged)
+                    .secure(true),
+                row![
+                    text_input("account (0)", &self.account).on_input(Message::AccountChanged),
+                    pick_list(&Role::ALL[..], Some(self.role), Message::RoleSet),
+                    text_input("index (0)", &self.index).on_input(Message::IndexChanged),
+                ]
+                .spacing(5),
+            ]
+            .push_maybe(
+                self.warning
+                    .as_ref()
+                    .map(|warning| text(warning).color(RED))
+            )
+            .spacing(5)
+            .width(FillPortion(1))
+            .padding(5),
+            scrollable(self.view_keys().padding(padding::right(15)))
+                .width(FillPortion(2))
+                .height(Fill)
+        ]
+        .spacing(5)
+        .padding(padding::top(20).left(5).right(10).bottom(10))
+        .into()
+    }
+
+    fn view_keys(&self) -> Column<'_, Message> {
+        let Some(derived) = &self.derived else {
+            return column![text("nothing to derive")];
+        };
+
+        let field = |label: String, value: Element<'static, Message>| {
+            row![text(label).width(160), value].spacing(10)
+        };
+
+        let mut content = column![]
+            .push(view_key("root", &derived.root))
+            .push(view_key("account", &derived.account))
+            .push(view_key("key", &derived.key))
+            .push_maybe(derived.stake.as_ref().map(|stake| view_key("stake", stake)))
+            .push(text("addresses").size(18))
+            .spacing(20);
+
+        for (name, address) in &derived.addresses {
+            content = content.push(field(name.clone(), copyable_text(address, Message::Copy)));
+        }
+
+        content
+    }
+}
+
+fn view_key<'a>(name: &'a str, key: &'a Key) -> Column<'a, Message> {
+    let field =
+        |label, value: Element<'a, Message>| row![text(label).width(160), value].spacing(10);
+
+    column![
+        row![
+            text(name).size(18),
+            text(&key.path).font(Font::MONOSPACE).size(14)
+        ]
+        .spacing(10),
+        field("signing key", copyable_text(&key.xsk, Message::Copy)),
+        field("verification key", copyable_text(&key.xvk, Message::Copy)),
+        field("public key", copyable_text(&key.vk, Message::Copy)),
+        field(
+            "key hash",
+            copyable_text(&key.hash.to_string(), Message::Copy)
+        ),
+    ]
+    .push_maybe(
+        key.vkh
+            .as_ref()
+            .map(|vkh| field("", copyable_text(vkh, Message::Copy))),
+    )
+    .spacing(5)
+}
@@ -1,10 +1,25 @@
+mod address_inspector;
 mod block_inspector;
+mod blueprint;
+mod cbor_explorer;
+mod chain_sync;
 mod copyable_text;
 mod hashes;
+mod hd_keys;
+mod mempool;
+mod native_script;
+mod plutus_data;
+mod script_address;
 mod signatures;
 mod tabbed_pane_grid;
+mod tree;
+mod tx_builder;
+mod tx_diff;
+mod uplc_debugger;
 
+mod clock;
 mod encoding;
+mod rpc;
 mod settings;
 mod state;
 mod subscription;
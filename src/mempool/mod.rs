@@ -0,0 +1,7 @@
+mod state;
+mod update;
+mod view;
+mod watch;
+
+pub use state::*;
+pub use update::*;
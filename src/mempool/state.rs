@@ -0,0 +1,81 @@
+use pallas::{
+    crypto::hash::{Hash, Hasher},
+    ledger::traverse,
+};
+use utxorpc::spec::submit::Stage;
+
+use crate::clock;
+
+/// Older txs are dropped so a long running watch doesn't grow forever.
+pub const MAX_TXS: usize = 500;
+
+#[derive(Debug, Default)]
+pub struct State {
+    pub endpoint: String,
+    pub api_key: String,
+    pub watching: Option<Watch>,
+    /// Bumped on every start, so watching the same endpoint again reconnects
+    pub session: u64,
+    /// Newest first
+    pub txs: Vec<MempoolTx>,
+    pub warning: Option<String>,
+}
+
+/// Everything the subscription needs to connect, doubling as its identity.
+#[derive(Debug, Clone, Hash)]
+pub struct Watch {
+    pub endpoint: String,
+    pub api_key: String,
+    pub session: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct MempoolTx {
+    pub hash: Hash<32>,
+    pub arrived: String,
+    pub stage: Stage,
+    pub fee: Option<u64>,
+    pub cbor: Vec<u8>,
+    /// Whether the bytes decoded as a tx of any era
+    pub decoded: bool,
+}
+
+impl MempoolTx {
+    pub fn new(reference: &[u8], cbor: Vec<u8>, stage: Stage) -> Self {
+        let decoded = traverse::MultiEraTx::decode(&cbor).ok();
+
+        // Servers are supposed to reference txs by hash, but only trust that
+        // when the tx itself can't be decoded. Failing both, the hash of the
+        // bytes still tells undecodable txs apart.
+        let hash = decoded
+            .as_ref()
+            .map(|tx| tx.hash())
+            .or_else(|| <[u8; 32]>::try_from(reference).ok().map(Hash::from))
+            .unwrap_or_else(|| Hasher::<256>::hash(&cbor));
+
+        Self {
+            hash,
+            arrived: clock::now(),
+            stage,
+            fee: decoded.as_ref().and_then(|tx| tx.fee()),
+            decoded: decoded.is_some(),
+            cbor,
+        }
+    }
+}
+
+impl State {
+    pub fn tx(&self, hash: &Hash<32>) -> Option<&MempoolTx> {
+        self.txs.iter().find(|tx| tx.hash == *hash)
+    }
+}
+
+pub fn stage(stage: Stage) -> &'static str {
+    match stage {
+        Stage::Unspecified => "unknown",
+        Stage::Acknowledged => "acknowledged",
+        Stage::Mempool => "mempool",
+        Stage::Network => "network",
+        Stage::Confirmed => "confirmed",
+    }
+}
@@ -0,0 +1,66 @@
+use iced::{Subscription, Task};
+use pallas::crypto::hash::Hash;
+
+use super::{watch::watch, MempoolTx, State, Watch, MAX_TXS};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    EndpointChanged(String),
+    ApiKeyChanged(String),
+    Start,
+    Stop,
+    Clear,
+    Tx(Box<MempoolTx>),
+    Stopped(String),
+    /// Handled by the workspace, which opens the tx in a block inspector
+    Open(Hash<32>),
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            EndpointChanged(endpoint) => self.endpoint = endpoint,
+            ApiKeyChanged(api_key) => self.api_key = api_key,
+            Start => {
+                self.warning = None;
+                self.session += 1;
+                self.watching = Some(Watch {
+                    endpoint: self.endpoint.clone(),
+                    api_key: self.api_key.clone(),
+                    session: self.session,
+                });
+            }
+            Stop => self.watching = None,
+            Clear => self.txs.clear(),
+            Tx(tx) => {
+                // Txs come back whenever their stage changes, keep them
+                // where they first showed up
+                match self.txs.iter_mut().find(|known| known.hash == tx.hash) {
+                    Some(known) => known.stage = tx.stage,
+                    None => {
+                        self.txs.insert(0, *tx);
+                        self.txs.truncate(MAX_TXS);
+                    }
+                }
+            }
+            Stopped(e) => {
+                self.watching = None;
+
+                if !e.is_empty() {
+                    self.warning = Some(e);
+                }
+            }
+            Open(_) => {}
+        }
+
+        Task::none()
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        match &self.watching {
+            Some(watching) => Subscription::run_with_id(watching.clone(), watch(watching.clone())),
+            None => Subscription::none(),
+        }
+    }
+}
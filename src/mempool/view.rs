@@ -0,0 +1,87 @@
+use iced::{
+    padding,
+    widget::{button, column, row, scrollable, text, text_input},
+    Element, Font,
+    Length::Fill,
+};
+
+use super::{stage, MempoolTx, Message, State};
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        let connection = row![
+            text_input(
+                "utxo rpc endpoint, e.g. http://localhost:50051",
+                &self.endpoint
+            )
+            .on_input_maybe(self.watching.is_none().then_some(Message::EndpointChanged)),
+            text_input("api key", &self.api_key)
+                .on_input_maybe(self.watching.is_none().then_some(Message::ApiKeyChanged))
+                .secure(true)
+                .width(160),
+            match self.watching {
+                Some(_) => button(text("Stop"))
+                    .style(button::danger)
+                    .on_press(Message::Stop),
+                None => button(text("Watch"))
+                    .on_press_maybe((!self.endpoint.trim().is_empty()).then_some(Message::Start)),
+            },
+            button(text("Clear"))
+                .style(button::secondary)
+                .on_press_maybe((!self.txs.is_empty()).then_some(Message::Clear)),
+        ]
+        .spacing(5);
+
+        let txs: Element<_> = if self.txs.is_empty() {
+            text(match self.watching {
+                Some(_) => "waiting for txs...",
+                None => "not watching",
+            })
+            .into()
+        } else {
+            scrollable(
+                column(self.txs.iter().map(tx))
+                    .spacing(4)
+                    .padding(padding::right(15)),
+            )
+            .height(Fill)
+            .into()
+        };
+
+        column![connection]
+            .push_maybe(
+                self.warning
+                    .as_ref()
+                    .map(|warning| text(warning).color([1.0, 0.0, 0.0])),
+            )
+            .push(txs)
+            .spacing(10)
+            .padding(padding::top(30).left(10).right(10).bottom(10))
+            .into()
+    }
+}
+
+fn tx(tx: &MempoolTx) -> Element<'_, Message> {
+    button(
+        row![
+            text(&tx.arrived).width(70),
+            text(stage(tx.stage)).width(100),
+            text(tx.hash.to_string())
+                .font(Font::MONOSPACE)
+                .size(14)
+                .width(Fill),
+            text(format!("{} bytes", tx.cbor.len())).width(100),
+            text(match tx.fee {
+                Some(fee) => format!("{fee} lovelace"),
+                None if !tx.decoded => "undecoded".to_string(),
+                None => "-".to_string(),
+            })
+            .width(140),
+        ]
+        .spacing(10),
+    )
+    .style(button::text)
+    .padding(0)
+    .on_press(Message::Open(tx.hash))
+    .into()
+}
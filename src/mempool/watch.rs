@@ -0,0 +1,54 @@
+use iced::{
+    futures::{channel::mpsc, SinkExt, Stream},
+    stream,
+};
+use utxorpc::{
+    spec::submit::{Stage, WatchMempoolRequest},
+    CardanoSubmitClient,
+};
+
+use crate::rpc;
+
+use super::{MempoolTx, Message, Watch};
+
+/// Stream every tx entering the endpoint's mempool, and every later change of
+/// stage of those txs.
+pub fn watch(watch: Watch) -> impl Stream<Item = Message> {
+    stream::channel(100, |mut output| async move {
+        let result = run(watch, &mut output).await;
+
+        // The tool stops watching when it gets this, ending the subscription
+        let _ = output
+            .send(Message::Stopped(result.err().unwrap_or_default()))
+            .await;
+    })
+}
+
+async fn run(watch: Watch, output: &mut mpsc::Sender<Message>) -> Result<(), String> {
+    let mut client: CardanoSubmitClient = rpc::connect(&watch.endpoint, &watch.api_key).await?;
+
+    let mut stream = client
+        .watch_mempool(WatchMempoolRequest {
+            predicate: None,
+            field_mask: None,
+        })
+        .await
+        .map_err(|e| rpc::status_error(&e))?
+        .into_inner();
+
+    while let Some(response) = stream.message().await.map_err(|e| rpc::status_error(&e))? {
+        let Some(tx) = response.tx else {
+            continue;
+        };
+
+        let stage = Stage::try_from(tx.stage).unwrap_or_default();
+        let tx = MempoolTx::new(&tx.r#ref, tx.native_bytes.to_vec(), stage);
+
+        if output.send(Message::Tx(Box::new(tx))).await.is_err() {
+            // Nobody is listening anymore
+            return Ok(());
+        }
+    }
+
+    Err("the endpoint closed the stream".to_string())
+}
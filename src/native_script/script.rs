@@ -0,0 +1,252 @@
+use pallas::{
+    codec::minicbor,
+    crypto::hash::{Hash, Hasher},
+    ledger::primitives::alonzo::NativeScript,
+};
+use serde_json::{json, Value};
+
+use crate::{encoding::likely_encoding, tree::Node};
+
+/// A native script given as cardano-cli's JSON or as CBOR, along with the
+/// CBOR it's hashed as: the input itself when it was CBOR, since a
+/// re-encoding might not hash the same.
+pub fn parse(input: &str) -> Result<(NativeScript, Vec<u8>), String> {
+    let input = input.trim();
+
+    if input.starts_with('{') {
+        let json = serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {e}"))?;
+        let script = from_json(&json)?;
+        let cbor = minicbor::to_vec(&script).map_err(|e| e.to_string())?;
+
+        return Ok((script, cbor));
+    }
+
+    let cbor = likely_encoding(input).decode(input)?;
+    let mut decoder = minicbor::Decoder::new(&cbor);
+
+    let script = decoder
+        .decode::<NativeScript>()
+        .map_err(|e| format!("Not a native script: {e}"))?;
+
+    if decoder.position() != cbor.len() {
+        return Err(format!(
+            "Not a native script: {} trailing bytes",
+            cbor.len() - decoder.position()
+        ));
+    }
+
+    Ok((script, cbor))
+}
+
+/// The script hash, which is also the policy id of the assets it mints.
+pub fn hash(cbor: &[u8]) -> Hash<28> {
+    let mut tagged = vec![0];
+
+    tagged.extend_from_slice(cbor);
+
+    Hasher::<224>::hash(&tagged)
+}
+
+/// cardano-cli's simple script JSON, e.g.
+/// `{ "type": "atLeast", "required": 1, "scripts": [...] }`.
+pub fn from_json(json: &Value) -> Result<NativeScript, String> {
+    from_json_at(json, "$")
+}
+
+fn from_json_at(json: &Value, path: &str) -> Result<NativeScript, String> {
+    let field = |name: &str| {
+        json.get(name)
+            .ok_or_else(|| format!("{path}: missing \"{name}\""))
+    };
+
+    let number = |name: &str| {
+        field(name)?
+            .as_u64()
+            .ok_or_else(|| format!("{path}.{name}: expected a non-negative integer"))
+    };
+
+    let scripts = || {
+        field("scripts")?
+            .as_array()
+            .ok_or_else(|| format!("{path}.scripts: expected an array"))?
+            .iter()
+            .enumerate()
+            .map(|(i, script)| from_json_at(script, &format!("{path}.scripts[{i}]")))
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    match field("type")?.as_str() {
+        Some("sig") => {
+            let key_hash = field("keyHash")?
+                .as_str()
+                .and_then(|key_hash| key_hash.parse().ok())
+                .ok_or_else(|| format!("{path}.keyHash: expected a 28 byte hex key hash"))?;
+
+            Ok(NativeScript::ScriptPubkey(key_hash))
+        }
+        Some("all") => Ok(NativeScript::ScriptAll(scripts()?)),
+        Some("any") => Ok(NativeScript::ScriptAny(scripts()?)),
+        Some("atLeast") => {
+            let required = number("required")?
+                .try_into()
+                .map_err(|_| format!("{path}.required: too large"))?;
+
+            Ok(NativeScript::ScriptNOfK(required, scripts()?))
+        }
+        Some("after") => Ok(NativeScript::InvalidBefore(number("slot")?)),
+        Some("before") => Ok(NativeScript::InvalidHereafter(number("slot")?)),
+        _ => Err(format!(
+            "{path}.type: expected one of \"sig\", \"all\", \"any\", \"atLeast\", \"after\" \
+             or \"before\""
+        )),
+    }
+}
+
+pub fn to_json(script: &NativeScript) -> Value {
+    let scripts = |scripts: &[NativeScript]| scripts.iter().map(to_json).collect::<Vec<_>>();
+
+    match script {
+        NativeScript::ScriptPubkey(key_hash) => {
+            json!({ "type": "sig", "keyHash": key_hash.to_string() })
+        }
+        NativeScript::ScriptAll(all) => json!({ "type": "all", "scripts": scripts(all) }),
+        NativeScript::ScriptAny(any) => json!({ "type": "any", "scripts": scripts(any) }),
+        NativeScript::ScriptNOfK(required, of) => {
+            json!({ "type": "atLeast", "required": required, "scripts": scripts(of) })
+        }
+        NativeScript::InvalidBefore(slot) => json!({ "type": "after", "slot": slot }),
+        NativeScript::InvalidHereafter(slot) => json!({ "type": "before", "slot": slot }),
+    }
+}
+
+/// What a tx offers to satisfy a script: the key hashes of its vkey
+/// witnesses and its validity interval.
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    pub signers: Vec<Hash<28>>,
+    pub valid_from: Option<u64>,
+    /// The tx's ttl, the first slot it's no longer valid in
+    pub valid_until: Option<u64>,
+}
+
+impl Context {
+    /// The ledger's rules for timelock scripts since Allegra. Time locks
+    /// only hold when the tx's validity interval proves them for every slot
+    /// it could land in, so an open interval satisfies none of them.
+    pub fn satisfies(&self, script: &NativeScript) -> bool {
+        match script {
+            NativeScript::ScriptPubkey(key_hash) => self.signers.contains(key_hash),
+            NativeScript::ScriptAll(all) => all.iter().all(|script| self.satisfies(script)),
+            NativeScript::ScriptAny(any) => any.iter().any(|script| self.satisfies(script)),
+            NativeScript::ScriptNOfK(required, of) => {
+                of.iter().filter(|script| self.satisfies(script)).count() >= *required as usize
+            }
+            NativeScript::InvalidBefore(slot) => self.valid_from.is_some_and(|from| *slot <= from),
+            NativeScript::InvalidHereafter(slot) => {
+                self.valid_until.is_some_and(|until| until <= *slot)
+            }
+        }
+    }
+}
+
+/// The script as a tree, with whether each part is satisfied when there's
+/// a context to check it against.
+pub fn tree(label: impl Into<String>, script: &NativeScript, context: Option<&Context>) -> Node {
+    let children = |scripts: &[NativeScript]| {
+        scripts
+            .iter()
+            .enumerate()
+            .map(|(i, script)| tree(format!("#{i}"), script, context))
+            .collect::<Vec<_>>()
+    };
+
+    let (description, children) = match script {
+        NativeScript::ScriptPubkey(key_hash) => (format!("signed by {key_hash}"), vec![]),
+        NativeScript::ScriptAll(all) => (format!("all of {}", all.len()), children(all)),
+        NativeScript::ScriptAny(any) => (format!("any of {}", any.len()), children(any)),
+        NativeScript::ScriptNOfK(required, of) => {
+            (format!("at least {required} of {}", of.len()), children(of))
+        }
+        NativeScript::InvalidBefore(slot) => (format!("from slot {slot}"), vec![]),
+        NativeScript::InvalidHereafter(slot) => (format!("before slot {slot}"), vec![]),
+    };
+
+    let value = match context {
+        Some(context) if context.satisfies(script) => format!("{description} (satisfied)"),
+        Some(_) => format!("{description} (not satisfied)"),
+        None => description,
+    };
+
+    Node::branch(label, children).value(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> Hash<28> {
+        Hash::new([byte; 28])
+    }
+
+    fn interval(valid_from: Option<u64>, valid_until: Option<u64>) -> Context {
+        Context {
+            valid_from,
+            valid_until,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn after_holds_from_its_slot_on() {
+        let after = NativeScript::InvalidBefore(100);
+
+        assert!(!interval(Some(99), None).satisfies(&after));
+        assert!(interval(Some(100), None).satisfies(&after));
+        assert!(interval(Some(101), None).satisfies(&after));
+        assert!(!interval(None, Some(200)).satisfies(&after));
+    }
+
+    #[test]
+    fn before_holds_up_to_its_slot() {
+        let before = NativeScript::InvalidHereafter(100);
+
+        assert!(interval(None, Some(99)).satisfies(&before));
+        assert!(interval(None, Some(100)).satisfies(&before));
+        assert!(!interval(None, Some(101)).satisfies(&before));
+        assert!(!interval(Some(0), None).satisfies(&before));
+    }
+
+    #[test]
+    fn nested_at_least_counts_satisfied_scripts() {
+        let script = NativeScript::ScriptNOfK(
+            2,
+            vec![
+                NativeScript::ScriptPubkey(key(1)),
+                NativeScript::ScriptPubkey(key(2)),
+                NativeScript::ScriptAll(vec![
+                    NativeScript::ScriptPubkey(key(3)),
+                    NativeScript::ScriptNOfK(
+                        1,
+                        vec![
+                            NativeScript::InvalidBefore(10),
+                            NativeScript::ScriptPubkey(key(4)),
+                        ],
+                    ),
+                ]),
+            ],
+        );
+
+        let signed = |signers: &[u8], valid_from| Context {
+            signers: signers.iter().copied().map(key).collect(),
+            valid_from,
+            valid_until: None,
+        };
+
+        assert!(signed(&[1, 2], None).satisfies(&script));
+        assert!(!signed(&[1], None).satisfies(&script));
+        assert!(!signed(&[1, 3], None).satisfies(&script));
+        assert!(signed(&[1, 3], Some(10)).satisfies(&script));
+        assert!(signed(&[2, 3, 4], None).satisfies(&script));
+        assert!(!signed(&[3, 4], Some(10)).satisfies(&script));
+    }
+}
@@ -0,0 +1,21 @@
+use std::collections::HashSet;
+
+use iced::widget::text_editor;
+use pallas::ledger::primitives::alonzo::NativeScript;
+
+use crate::tree;
+
+#[derive(Debug, Default)]
+pub struct State {
+    pub input: text_editor::Content,
+    pub script: Option<NativeScript>,
+    /// The CBOR the script is hashed as
+    pub cbor: Vec<u8>,
+    pub json: String,
+    /// Key hashes or verification keys, separated by whitespace or commas
+    pub signers: String,
+    pub valid_from: String,
+    pub valid_until: String,
+    pub warning: Option<String>,
+    pub collapsed: HashSet<tree::Path>,
+}
@@ -0,0 +1,118 @@
+use iced::{clipboard, widget::text_editor, Task};
+use pallas::crypto::hash::{Hash, Hasher};
+
+use crate::tree;
+
+use super::{parse, to_json, Context, State};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    InputChanged(text_editor::Action),
+    SignersChanged(String),
+    ValidFromChanged(String),
+    ValidUntilChanged(String),
+    ToggleNode(tree::Path),
+    Copy(String),
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            InputChanged(action) => {
+                self.input.perform(action);
+                self.decode();
+                Task::none()
+            }
+            SignersChanged(signers) => {
+                self.signers = signers;
+                Task::none()
+            }
+            ValidFromChanged(slot) => {
+                self.valid_from = slot;
+                Task::none()
+            }
+            ValidUntilChanged(slot) => {
+                self.valid_until = slot;
+                Task::none()
+            }
+            ToggleNode(path) => {
+                if !self.collapsed.remove(&path) {
+                    self.collapsed.insert(path);
+                }
+                Task::none()
+            }
+            Copy(s) => clipboard::write(s),
+        }
+    }
+
+    fn decode(&mut self) {
+        self.script = None;
+        self.cbor.clear();
+        self.json.clear();
+        self.warning = None;
+        self.collapsed.clear();
+
+        let input = self.input.text();
+
+        if input.trim().is_empty() {
+            return;
+        }
+
+        match parse(&input) {
+            Ok((script, cbor)) => {
+                self.json = serde_json::to_string_pretty(&to_json(&script)).unwrap_or_default();
+                self.script = Some(script);
+                self.cbor = cbor;
+            }
+            Err(e) => self.warning = Some(e),
+        }
+    }
+
+    /// The signers and validity interval to check the script against, or
+    /// nothing when none were given.
+    pub fn context(&self) -> Result<Option<Context>, String> {
+        let signers = self
+            .signers
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|signer| !signer.is_empty())
+            .map(|signer| {
+                let bytes = hex::decode(signer).map_err(|e| format!("Invalid signer: {e}"))?;
+
+                // Verification keys are hashed to the key hash scripts use
+                match bytes.len() {
+                    28 => Ok(Hash::<28>::from(&bytes[..])),
+                    32 => Ok(Hasher::<224>::hash(&bytes)),
+                    n => Err(format!(
+                        "Signers must be 28 byte key hashes or 32 byte verification keys, got \
+                         {n} bytes"
+                    )),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let slot = |slot: &str, name: &str| {
+            let slot = slot.trim();
+
+            if slot.is_empty() {
+                return Ok(None);
+            }
+
+            slot.parse()
+                .map(Some)
+                .map_err(|_| format!("{name} must be a slot number"))
+        };
+
+        let context = Context {
+            signers,
+            valid_from: slot(&self.valid_from, "Valid from")?,
+            valid_until: slot(&self.valid_until, "Valid until")?,
+        };
+
+        let empty = context.signers.is_empty()
+            && context.valid_from.is_none()
+            && context.valid_until.is_none();
+
+        Ok((!empty).then_some(context))
+    }
+}
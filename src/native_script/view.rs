@@ -0,0 +1,107 @@
+use iced::{
+    padding,
+    widget::{button, column, row, scrollable, text, text_editor, text_input},
+    Color, Element, Font,
+    Length::{Fill, FillPortion},
+    Theme,
+};
+use iced_font_awesome::fa_icon_solid;
+
+use super::{hash, tree, Message, State};
+use crate::{copyable_text::copyable_text, tree};
+
+const RED: Color = Color::from_rgb(1.0, 0.0, 0.0);
+const GREEN: Color = Color::from_rgb(0.0, 0.7, 0.0);
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        row![
+            column![
+                text_editor(&self.input)
+                    .placeholder("native script json or cbor...")
+                    .on_action(Message::InputChanged)
+                    .height(Fill)
+                    .wrapping(text::Wrapping::Glyph)
+                    .style(|theme: &Theme, status| {
+                        let mut defaults = text_editor::default(theme, status);
+
+                        defaults.border.radius = 4.0.into();
+
+                        defaults
+                    }),
+                text_input("signers: key hashes or verification keys", &self.signers)
+                    .on_input(Message::SignersChanged),
+                row![
+                    text_input("valid from slot", &self.valid_from)
+                        .on_input(Message::ValidFromChanged),
+                    text_input("valid until slot (ttl)", &self.valid_until)
+                        .on_input(Message::ValidUntilChanged),
+                ]
+                .spacing(5),
+            ]
+            .push_maybe(
+                self.warning
+                    .as_ref()
+                    .map(|warning| text(warning).color(RED))
+            )
+            .spacing(5)
+            .width(FillPortion(1))
+            .padding(5),
+            scrollable(self.view_script().padding(padding::right(15)))
+                .width(FillPortion(2))
+                .height(Fill)
+        ]
+        .spacing(5)
+        .padding(padding::top(20).left(5).right(10).bottom(10))
+        .into()
+    }
+
+    fn view_script(&self) -> iced::widget::Column<'_, Message> {
+        let Some(script) = &self.script else {
+            return column![text("nothing to decode")];
+        };
+
+        let field = |label, value: Element<'static, Message>| row![text(label).width(160), value];
+
+        let context = self.context();
+
+        let satisfaction = match &context {
+            Ok(Some(context)) if context.satisfies(script) => Some(text("satisfied").color(GREEN)),
+            Ok(Some(_)) => Some(text("not satisfied").color(RED)),
+            Ok(None) => None,
+            Err(e) => Some(text(e.clone()).color(RED)),
+        };
+
+        let copyable = |value: String| {
+            row![
+                text(value.clone())
+                    .font(Font::MONOSPACE)
+                    .size(14)
+                    .width(Fill),
+                button(fa_icon_solid("clone").size(14.))
+                    .on_press(Message::Copy(value))
+                    .style(button::text)
+                    .padding(5)
+            ]
+        };
+
+        column![
+            field(
+                "policy id",
+                copyable_text(&hash(&self.cbor).to_string(), Message::Copy)
+            ),
+            field("size", text(format!("{} bytes", self.cbor.len())).into()),
+        ]
+        .push_maybe(satisfaction.map(|satisfaction| field("context", satisfaction.into())))
+        .push(tree::tree(
+            &tree("script", script, context.ok().flatten().as_ref()),
+            &self.collapsed,
+            Message::ToggleNode,
+        ))
+        .push(text("cbor"))
+        .push(copyable(hex::encode(&self.cbor)))
+        .push(text("json"))
+        .push(copyable(self.json.clone()))
+        .spacing(10)
+    }
+}
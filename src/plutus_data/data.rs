@@ -0,0 +1,465 @@
+use pallas::{
+    codec::{
+        minicbor,
+        utils::{Int, KeyValuePairs, MaybeIndefArray},
+    },
+    ledger::primitives::{BigInt, BoundedBytes, Constr, PlutusData},
+};
+use serde_json::{json, Map, Number, Value};
+
+use crate::{encoding::likely_encoding, tree::Node};
+
+/// Data given either as detailed schema JSON or as CBOR in any of the text
+/// encodings, along with the CBOR bytes when it was given as such.
+pub fn parse(input: &str) -> Result<(PlutusData, Option<Vec<u8>>), String> {
+    let input = input.trim();
+
+    // Detailed schema JSON is always an object, CBOR never starts with a
+    // brace in any of the text encodings
+    if input.starts_with('{') {
+        let json = serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {e}"))?;
+
+        return Ok((from_json(&json)?, None));
+    }
+
+    let bytes = likely_encoding(input).decode(input)?;
+
+    minicbor::decode::<PlutusData>(&bytes)
+        .map(|data| (data, Some(bytes)))
+        .map_err(|e| format!("Invalid plutus data: {e}"))
+}
+
+/// The constructor index, from either the compact tags or the general form
+/// with the index spelled out.
+pub fn constructor_index(constr: &Constr<PlutusData>) -> Option<u64> {
+    match constr.tag {
+        121..=127 => Some(constr.tag - 121),
+        1280..=1400 => Some(constr.tag - 1280 + 7),
+        102 => constr.any_constructor,
+        _ => None,
+    }
+}
+
+/// Data in the same notation as untyped plutus core, e.g.
+/// `Constr 0 [B #abcd, I 42]`.
+pub fn notation(data: &PlutusData) -> String {
+    let list = |items: &[PlutusData]| items.iter().map(notation).collect::<Vec<_>>().join(", ");
+
+    match data {
+        PlutusData::Constr(constr) => format!(
+            "Constr {} [{}]",
+            constructor_index(constr).map_or("?".to_string(), |i| i.to_string()),
+            list(&constr.fields)
+        ),
+        PlutusData::Map(entries) => format!(
+            "Map [{}]",
+            entries
+                .iter()
+                .map(|(k, v)| format!("({}, {})", notation(k), notation(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        PlutusData::BigInt(int) => format!("I {}", integer(int)),
+        PlutusData::BoundedBytes(bytes) => format!("B #{}", hex::encode(bytes.as_slice())),
+        PlutusData::Array(items) => format!("List [{}]", list(items)),
+    }
+}
+
+pub fn tree(label: impl Into<String>, data: &PlutusData) -> Node {
+    match data {
+        PlutusData::Constr(constr) => Node::branch(label, children(&constr.fields)).value(format!(
+            "Constr {}",
+            constructor_index(constr).map_or("?".to_string(), |i| i.to_string())
+        )),
+        PlutusData::Map(entries) => Node::branch(
+            label,
+            entries.iter().enumerate().map(|(i, (k, v))| {
+                Node::branch(format!("#{i}"), [tree("key", k), tree("value", v)])
+            }),
+        )
+        .value(format!("Map [{}]", entries.len())),
+        PlutusData::BigInt(int) => Node::leaf(label, format!("I {}", integer(int))),
+        PlutusData::BoundedBytes(bytes) => {
+            Node::leaf(label, format!("B #{}", hex::encode(bytes.as_slice())))
+        }
+        PlutusData::Array(items) => Node::list(label, children(items)),
+    }
+}
+
+fn children(items: &[PlutusData]) -> Vec<Node> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| tree(format!("#{i}"), item))
+        .collect()
+}
+
+/// cardano-cli's detailed schema JSON, which has no way to write a
+/// constructor whose tag doesn't say its index.
+pub fn to_json(data: &PlutusData) -> Result<Value, String> {
+    let list = |items: &[PlutusData]| items.iter().map(to_json).collect::<Result<Vec<_>, _>>();
+
+    Ok(match data {
+        PlutusData::Constr(constr) => json!({
+            "constructor": constructor_index(constr).ok_or_else(|| format!(
+                "Constructor tag {} has no index to write as JSON",
+                constr.tag
+            ))?,
+            "fields": list(&constr.fields)?,
+        }),
+        PlutusData::Map(entries) => json!({
+            "map": entries
+                .iter()
+                .map(|(k, v)| Ok(json!({ "k": to_json(k)?, "v": to_json(v)? })))
+                .collect::<Result<Vec<_>, String>>()?,
+        }),
+        // The number is kept as written, so integers of any size survive
+        PlutusData::BigInt(int) => json!({
+            "int": integer(int).parse::<Number>().unwrap_or(Number::from(0)),
+        }),
+        PlutusData::BoundedBytes(bytes) => json!({ "bytes": hex::encode(bytes.as_slice()) }),
+        PlutusData::Array(items) => json!({ "list": list(items)? }),
+    })
+}
+
+pub fn from_json(value: &Value) -> Result<PlutusData, String> {
+    from_json_at(value, "$")
+}
+
+fn from_json_at(value: &Value, path: &str) -> Result<PlutusData, String> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| format!("{path}: expected an object"))?;
+
+    let field = |name: &str| {
+        object
+            .get(name)
+            .ok_or_else(|| format!("{path}: missing \"{name}\""))
+    };
+
+    let array = |name: &str| {
+        field(name)?
+            .as_array()
+            .ok_or_else(|| format!("{path}.{name}: expected an array"))
+    };
+
+    let items = |name: &str| {
+        array(name)?
+            .iter()
+            .enumerate()
+            .map(|(i, item)| from_json_at(item, &format!("{path}.{name}[{i}]")))
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    expect_keys(object, path)?;
+
+    if object.contains_key("constructor") {
+        let index = field("constructor")?
+            .as_u64()
+            .ok_or_else(|| format!("{path}.constructor: expected a non-negative integer"))?;
+
+        Ok(constr(index, items("fields")?))
+    } else if object.contains_key("map") {
+        let entries = array("map")?
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let path = format!("{path}.map[{i}]");
+                let entry = entry
+                    .as_object()
+                    .ok_or_else(|| format!("{path}: expected an object with \"k\" and \"v\""))?;
+
+                let get = |name: &str| {
+                    let value = entry
+                        .get(name)
+                        .ok_or_else(|| format!("{path}: missing \"{name}\""))?;
+
+                    from_json_at(value, &format!("{path}.{name}"))
+                };
+
+                Ok((get("k")?, get("v")?))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(PlutusData::Map(KeyValuePairs::Def(entries)))
+    } else if object.contains_key("list") {
+        Ok(PlutusData::Array(array_of(items("list")?)))
+    } else if let Some(int) = object.get("int") {
+        let Value::Number(int) = int else {
+            return Err(format!("{path}.int: expected an integer"));
+        };
+
+        parse_integer(&int.to_string())
+            .map(PlutusData::BigInt)
+            .ok_or_else(|| format!("{path}.int: expected an integer, got {int}"))
+    } else if let Some(bytes) = object.get("bytes") {
+        let bytes = bytes
+            .as_str()
+            .ok_or_else(|| format!("{path}.bytes: expected a hex string"))?;
+
+        hex::decode(bytes)
+            .map(|bytes| PlutusData::BoundedBytes(bytes.into()))
+            .map_err(|e| format!("{path}.bytes: invalid hex: {e}"))
+    } else {
+        Err(format!(
+            "{path}: expected one of \"constructor\", \"map\", \"list\", \"int\" or \"bytes\""
+        ))
+    }
+}
+
+/// Each schema object has a fixed set of keys, anything else is most likely
+/// a typo that would otherwise be silently ignored.
+fn expect_keys(object: &Map<String, Value>, path: &str) -> Result<(), String> {
+    let allowed: &[&str] = if object.contains_key("constructor") {
+        &["constructor", "fields"]
+    } else if object.contains_key("map") {
+        &["map"]
+    } else if object.contains_key("list") {
+        &["list"]
+    } else if object.contains_key("int") {
+        &["int"]
+    } else {
+        &["bytes"]
+    };
+
+    match object.keys().find(|key| !allowed.contains(&key.as_str())) {
+        Some(key) => Err(format!("{path}: unexpected \"{key}\"")),
+        None => Ok(()),
+    }
+}
+
+/// Encode a constructor the way the ledger does: the compact tags where
+/// they fit and the general form otherwise.
+fn constr(index: u64, fields: Vec<PlutusData>) -> PlutusData {
+    let (tag, any_constructor) = match index {
+        0..=6 => (121 + index, None),
+        7..=127 => (1280 + index - 7, None),
+        _ => (102, Some(index)),
+    };
+
+    PlutusData::Constr(Constr {
+        tag,
+        any_constructor,
+        fields: array_of(fields),
+    })
+}
+
+/// The node encodes non-empty lists as indefinite arrays, and hashes of
+/// datums depend on it.
+fn array_of(items: Vec<PlutusData>) -> MaybeIndefArray<PlutusData> {
+    if items.is_empty() {
+        MaybeIndefArray::Def(items)
+    } else {
+        MaybeIndefArray::Indef(items)
+    }
+}
+
+/// Integers in decimal, including the ones too big for a CBOR integer that
+/// are encoded as big-endian bytes.
+fn integer(int: &BigInt) -> String {
+    match int {
+        BigInt::Int(int) => i128::from(int.0).to_string(),
+        BigInt::BigUInt(bytes) => decimal(bytes),
+        // Stored as -1 - n
+        BigInt::BigNInt(bytes) => format!("-{}", decimal(&increment(bytes))),
+    }
+}
+
+fn parse_integer(s: &str) -> Option<BigInt> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    if let Ok(int) = s.parse::<i128>() {
+        if let Ok(int) = minicbor_int(int) {
+            return Some(BigInt::Int(int));
+        }
+    }
+
+    // Big-endian bytes of the magnitude
+    let mut bytes: Vec<u8> = vec![];
+
+    for digit in digits.bytes().map(|b| b - b'0') {
+        let mut carry = digit as u32;
+
+        for byte in bytes.iter_mut().rev() {
+            let value = *byte as u32 * 10 + carry;
+
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    Some(if negative {
+        BigInt::BigNInt(decrement(&bytes).into())
+    } else {
+        BigInt::BigUInt(bytes.into())
+    })
+}
+
+fn minicbor_int(int: i128) -> Result<Int, ()> {
+    pallas::codec::minicbor::data::Int::try_from(int)
+        .map(Int)
+        .map_err(|_| ())
+}
+
+fn decimal(bytes: &[u8]) -> String {
+    let mut number: Vec<u8> = bytes.iter().copied().skip_while(|b| *b == 0).collect();
+    let mut digits = vec![];
+
+    while !number.is_empty() {
+        let mut remainder = 0u32;
+        let mut quotient = vec![];
+
+        for byte in &number {
+            let value = remainder * 256 + *byte as u32;
+
+            if !quotient.is_empty() || value >= 10 {
+                quotient.push((value / 10) as u8);
+            }
+
+            remainder = value % 10;
+        }
+
+        digits.push(b'0' + remainder as u8);
+        number = quotient;
+    }
+
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+
+    digits.iter().rev().map(|d| *d as char).collect()
+}
+
+fn increment(bytes: &BoundedBytes) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+
+    for byte in bytes.iter_mut().rev() {
+        let (value, overflow) = byte.overflowing_add(1);
+
+        *byte = value;
+
+        if !overflow {
+            return bytes;
+        }
+    }
+
+    bytes.insert(0, 1);
+    bytes
+}
+
+/// One less than a non-zero magnitude, without the leading zero byte a power
+/// of 256 would leave, so that it encodes the way the node does.
+fn decrement(bytes: &[u8]) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+
+    for byte in bytes.iter_mut().rev() {
+        let (value, underflow) = byte.overflowing_sub(1);
+
+        *byte = value;
+
+        if !underflow {
+            break;
+        }
+    }
+
+    let zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    bytes.drain(..zeros);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cbor(data: &PlutusData) -> String {
+        hex::encode(minicbor::to_vec(data).unwrap())
+    }
+
+    fn round_trip(json: &str) {
+        let json: Value = serde_json::from_str(json).unwrap();
+        let data = from_json(&json).unwrap();
+
+        assert_eq!(to_json(&data).unwrap(), json);
+
+        let decoded: PlutusData = minicbor::decode(&minicbor::to_vec(&data).unwrap()).unwrap();
+
+        assert_eq!(decoded, data);
+        assert_eq!(to_json(&decoded).unwrap(), json);
+    }
+
+    #[test]
+    fn integers_round_trip() {
+        for int in [
+            "0",
+            "-1",
+            "18446744073709551615",
+            "18446744073709551616",
+            "-18446744073709551616",
+            "-18446744073709551617",
+            "-4722366482869645213696",
+            "123456789012345678901234567890123456789012345678901234567890",
+            "-123456789012345678901234567890123456789012345678901234567890",
+        ] {
+            round_trip(&format!(r#"{{"int": {int}}}"#));
+        }
+    }
+
+    #[test]
+    fn nested_data_round_trips() {
+        round_trip(
+            r#"{
+                "constructor": 0,
+                "fields": [
+                    { "bytes": "deadbeef" },
+                    { "list": [{ "int": -340282366920938463463374607431768211457 }, { "list": [] }] },
+                    { "map": [{ "k": { "bytes": "" }, "v": { "constructor": 7, "fields": [] } }] },
+                    { "constructor": 128, "fields": [{ "int": 42 }] }
+                ]
+            }"#,
+        );
+    }
+
+    #[test]
+    fn constructors_use_the_compact_tags() {
+        assert_eq!(cbor(&constr(0, vec![])), "d87980");
+        assert_eq!(cbor(&constr(7, vec![])), "d9050080");
+        assert_eq!(cbor(&constr(128, vec![])), "d86682188080");
+    }
+
+    #[test]
+    fn negative_bignums_are_canonical() {
+        // -2^72 is stored as 2^72 - 1, nine bytes rather than ten
+        let data = PlutusData::BigInt(parse_integer("-4722366482869645213696").unwrap());
+
+        assert_eq!(cbor(&data), "c349ffffffffffffffffff");
+
+        // -2^64 - 1 is stored as 2^64, still nine bytes
+        let data = PlutusData::BigInt(parse_integer("-18446744073709551617").unwrap());
+
+        assert_eq!(cbor(&data), "c349010000000000000000");
+    }
+
+    #[test]
+    fn constructors_without_an_index_have_no_json() {
+        let data = PlutusData::Constr(Constr {
+            tag: 1401,
+            any_constructor: None,
+            fields: array_of(vec![]),
+        });
+
+        assert!(to_json(&data).is_err());
+        assert!(to_json(&PlutusData::Array(array_of(vec![data]))).is_err());
+    }
+}
@@ -0,0 +1,8 @@
+mod data;
+mod state;
+mod update;
+mod view;
+
+pub use data::*;
+pub use state::*;
+pub use update::*;
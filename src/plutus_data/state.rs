@@ -0,0 +1,50 @@
+use std::{collections::HashSet, fmt::Display, sync::Arc};
+
+use iced::widget::text_editor;
+use pallas::ledger::primitives::PlutusData;
+
+use crate::{blueprint::Blueprint, tree};
+
+#[derive(Debug, Default)]
+pub struct State {
+    pub input: text_editor::Content,
+    pub mode: Mode,
+    pub data: Option<PlutusData>,
+    /// The data encoded again, which only matches the input CBOR when it was
+    /// encoded the way the node does it.
+    pub cbor: Vec<u8>,
+    pub reencoded_differs: bool,
+    pub json: String,
+    pub warning: Option<String>,
+    pub collapsed: HashSet<tree::Path>,
+    /// The last blueprint loaded anywhere, to name the fields of the data
+    pub blueprint: Option<Arc<Blueprint>>,
+    /// The data as the blueprint sees it, when some validator takes it
+    pub annotated: Option<tree::Node>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Mode {
+    #[default]
+    Tree,
+    Notation,
+    Json,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 3] = [Mode::Tree, Mode::Notation, Mode::Json];
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Mode::Tree => "Tree",
+                Mode::Notation => "Notation",
+                Mode::Json => "Detailed schema JSON",
+            }
+        )
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use iced::{clipboard, widget::text_editor, Task};
+use pallas::codec::minicbor;
+
+use crate::{
+    blueprint::{Blueprint, Purpose},
+    tree,
+};
+
+use super::{parse, to_json, Mode, State};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    InputChanged(text_editor::Action),
+    ModeSet(Mode),
+    ToggleNode(tree::Path),
+    Copy(String),
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            InputChanged(action) => {
+                self.input.perform(action);
+                self.decode();
+                Task::none()
+            }
+            ModeSet(mode) => {
+                self.mode = mode;
+                Task::none()
+            }
+            ToggleNode(path) => {
+                if !self.collapsed.remove(&path) {
+                    self.collapsed.insert(path);
+                }
+                Task::none()
+            }
+            Copy(s) => clipboard::write(s),
+        }
+    }
+
+    pub fn set_blueprint(&mut self, blueprint: Option<Arc<Blueprint>>) {
+        self.blueprint = blueprint;
+        self.annotate();
+    }
+
+    fn annotate(&mut self) {
+        self.annotated =
+            self.blueprint
+                .as_ref()
+                .zip(self.data.as_ref())
+                .and_then(|(blueprint, data)| {
+                    Purpose::ALL
+                        .into_iter()
+                        .find_map(|purpose| blueprint.annotate_for("data", purpose, data, None))
+                });
+    }
+
+    fn decode(&mut self) {
+        self.data = None;
+        self.annotated = None;
+        self.cbor.clear();
+        self.reencoded_differs = false;
+        self.json.clear();
+        self.warning = None;
+        self.collapsed.clear();
+
+        let input = self.input.text();
+        let input = input.trim();
+
+        if input.is_empty() {
+            return;
+        }
+
+        let (data, original) = match parse(input) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.warning = Some(e);
+                return;
+            }
+        };
+
+        match minicbor::to_vec(&data) {
+            Ok(cbor) => self.cbor = cbor,
+            Err(e) => self.warning = Some(format!("Could not encode plutus data: {e}")),
+        }
+
+        self.reencoded_differs = original.is_some_and(|original| original != self.cbor);
+        match to_json(&data) {
+            Ok(json) => self.json = serde_json::to_string_pretty(&json).unwrap_or_default(),
+            Err(e) => self.warning = Some(e),
+        }
+
+        self.data = Some(data);
+        self.annotate();
+    }
+}
@@ -0,0 +1,105 @@
+use iced::{
+    padding,
+    widget::{button, column, container, pick_list, row, scrollable, text, text_editor, Space},
+    Border, Element, Font,
+    Length::{Fill, FillPortion, Shrink},
+    Theme,
+};
+use iced_font_awesome::fa_icon_solid;
+
+use super::{notation, tree, Message, Mode, State};
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        let decoded: Element<_> = match (self.mode, &self.data) {
+            (_, None) => text("nothing to decode").into(),
+            (Mode::Tree, Some(data)) => match &self.annotated {
+                Some(annotated) => {
+                    crate::tree::tree(annotated, &self.collapsed, Message::ToggleNode)
+                }
+                None => {
+                    crate::tree::tree(&tree("data", data), &self.collapsed, Message::ToggleNode)
+                }
+            },
+            (Mode::Notation, Some(data)) => copyable(notation(data)),
+            (Mode::Json, Some(_)) => copyable(self.json.clone()),
+        };
+
+        row![
+            column![
+                row![
+                    text("CBOR or detailed schema JSON"),
+                    Space::new(Fill, Fill),
+                    pick_list(&Mode::ALL[..], Some(self.mode), Message::ModeSet),
+                ]
+                .height(Shrink),
+                text_editor(&self.input)
+                    .placeholder("paste some plutus data...")
+                    .on_action(Message::InputChanged)
+                    .height(Fill)
+                    .wrapping(text::Wrapping::Glyph)
+                    .style(|theme: &Theme, status| {
+                        let mut defaults = text_editor::default(theme, status);
+
+                        defaults.border.radius = 4.0.into();
+
+                        defaults
+                    }),
+            ]
+            .spacing(5)
+            .width(FillPortion(1))
+            .padding(5),
+            column![container(scrollable(decoded).width(Fill))
+                .width(Fill)
+                .height(Fill)
+                .style(|theme: &Theme| {
+                    let palette = theme.extended_palette();
+
+                    container::Style {
+                        border: Border {
+                            width: 1.0,
+                            radius: 4.0.into(),
+                            color: palette.background.strong.color,
+                        },
+                        ..Default::default()
+                    }
+                })
+                .padding(10)]
+            .push_maybe(
+                self.data.as_ref().map(|_| column![
+                    text("CBOR"),
+                    copyable(hex::encode(&self.cbor))
+                ]
+                .spacing(5))
+            )
+            .push_maybe(self.reencoded_differs.then(|| {
+                text("The input encodes the same data differently than the node would")
+                    .color([1.0, 0.6, 0.0])
+            }))
+            .push_maybe(
+                self.warning
+                    .as_ref()
+                    .map(|warning| text(warning).color([1.0, 0.0, 0.0]))
+            )
+            .spacing(5)
+            .padding(5)
+            .width(FillPortion(2))
+        ]
+        .padding(padding::top(20))
+        .into()
+    }
+}
+
+fn copyable<'a>(content: String) -> Element<'a, Message> {
+    row![
+        text(content.clone())
+            .font(Font::MONOSPACE)
+            .size(14)
+            .width(Fill),
+        button(fa_icon_solid("clone").size(14.))
+            .on_press(Message::Copy(content))
+            .style(button::text)
+            .padding(5)
+    ]
+    .into()
+}
@@ -0,0 +1,70 @@
+//! A UTxO RPC query service serving a fixed set of outputs, shared by the
+//! `mock_utxorpc` example and the tests resolving inputs against it.
+
+use std::collections::HashMap;
+
+use tonic::{Request, Response, Status};
+use utxorpc::spec::query::{
+    query_service_server::QueryService, AnyUtxoData, ReadDataRequest, ReadDataResponse,
+    ReadParamsRequest, ReadParamsResponse, ReadUtxosRequest, ReadUtxosResponse, SearchUtxosRequest,
+    SearchUtxosResponse, TxoRef,
+};
+
+/// Tx hash and output index
+pub type Input = (Vec<u8>, u32);
+
+pub struct Mock {
+    pub utxos: HashMap<Input, Vec<u8>>,
+}
+
+#[tonic::async_trait]
+impl QueryService for Mock {
+    async fn read_params(
+        &self,
+        _: Request<ReadParamsRequest>,
+    ) -> Result<Response<ReadParamsResponse>, Status> {
+        Err(Status::unimplemented("mock only serves utxos"))
+    }
+
+    async fn read_utxos(
+        &self,
+        request: Request<ReadUtxosRequest>,
+    ) -> Result<Response<ReadUtxosResponse>, Status> {
+        let items = request
+            .into_inner()
+            .keys
+            .into_iter()
+            .filter_map(|key| {
+                let output = self.utxos.get(&(key.hash.to_vec(), key.index))?;
+
+                Some(AnyUtxoData {
+                    native_bytes: output.clone().into(),
+                    txo_ref: Some(TxoRef {
+                        hash: key.hash,
+                        index: key.index,
+                    }),
+                    parsed_state: None,
+                })
+            })
+            .collect();
+
+        Ok(Response::new(ReadUtxosResponse {
+            items,
+            ledger_tip: None,
+        }))
+    }
+
+    async fn search_utxos(
+        &self,
+        _: Request<SearchUtxosRequest>,
+    ) -> Result<Response<SearchUtxosResponse>, Status> {
+        Err(Status::unimplemented("mock only serves utxos"))
+    }
+
+    async fn read_data(
+        &self,
+        _: Request<ReadDataRequest>,
+    ) -> Result<Response<ReadDataResponse>, Status> {
+        Err(Status::unimplemented("mock only serves utxos"))
+    }
+}
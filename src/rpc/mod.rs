@@ -0,0 +1,73 @@
+//! Shared plumbing for the tools talking to a UTxO RPC endpoint.
+
+#[cfg(test)]
+pub mod mock;
+
+use std::error::Error;
+
+use pallas::ledger::primitives::TransactionInput;
+use utxorpc::{spec::query::TxoRef, ClientBuilder, InnerService};
+
+/// Build a client for `endpoint`, sending `api_key` the way hosted endpoints
+/// expect it when there is one. Connecting is lazy, so a bad endpoint only
+/// shows up as an error on the first request.
+pub async fn connect<T>(endpoint: &str, api_key: &str) -> Result<T, String>
+where
+    T: From<InnerService>,
+{
+    let mut builder = ClientBuilder::new()
+        .uri(endpoint.trim())
+        .map_err(|e| error(&e))?;
+
+    let api_key = api_key.trim();
+
+    if !api_key.is_empty() {
+        // The builder panics on anything that can't go in a header
+        if !api_key.chars().all(|c| c.is_ascii_graphic()) {
+            return Err("api key must be printable ascii".to_string());
+        }
+
+        builder = builder
+            .metadata("dmtr-api-key", api_key)
+            .map_err(|e| error(&e))?;
+    }
+
+    Ok(builder.build::<T>().await)
+}
+
+/// The reference UTxO RPC knows `input` by. Its output indexes are 32 bits,
+/// any larger index can't name an output on chain.
+pub fn txo_ref(input: &TransactionInput) -> Result<TxoRef, String> {
+    let index = u32::try_from(input.index)
+        .map_err(|_| format!("output index {} is out of range", input.index))?;
+
+    Ok(TxoRef {
+        hash: input.transaction_id.to_vec().into(),
+        index,
+    })
+}
+
+pub fn error(e: &utxorpc::Error) -> String {
+    match e {
+        utxorpc::Error::GrpcError(status) => status_error(status),
+        e => error_chain(e),
+    }
+}
+
+pub fn status_error(status: &tonic::Status) -> String {
+    format!("{:?}: {}", status.code(), status.message())
+}
+
+/// Transport errors only say what went wrong in their sources.
+fn error_chain(e: &dyn Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+
+    while let Some(e) = source {
+        message.push_str(": ");
+        message.push_str(&e.to_string());
+        source = e.source();
+    }
+
+    message
+}
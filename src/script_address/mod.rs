@@ -0,0 +1,8 @@
+mod script;
+mod state;
+mod update;
+mod view;
+
+pub use script::*;
+pub use state::*;
+pub use update::*;
@@ -0,0 +1,306 @@
+use std::fmt::Display;
+
+use pallas::{
+    codec::{minicbor, utils::Bytes},
+    crypto::hash::{Hash, Hasher},
+    ledger::{
+        addresses::{
+            Address, Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+            StakeAddress, StakePayload,
+        },
+        primitives::alonzo::NativeScript,
+    },
+};
+
+use crate::{
+    encoding::likely_encoding,
+    native_script,
+    uplc_debugger::{unwrap_flat, Version},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Language {
+    Native,
+    Plutus(Version),
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::Native,
+        Language::Plutus(Version::V1),
+        Language::Plutus(Version::V2),
+        Language::Plutus(Version::V3),
+    ];
+
+    /// The tag prepended to the script before hashing it
+    fn tag(&self) -> u8 {
+        match self {
+            Language::Native => 0,
+            Language::Plutus(Version::V1) => 1,
+            Language::Plutus(Version::V2) => 2,
+            Language::Plutus(Version::V3) => 3,
+        }
+    }
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Language::Native => write!(f, "Native script"),
+            Language::Plutus(version) => write!(f, "{version}"),
+        }
+    }
+}
+
+/// A script as the ledger hashes it: native scripts as their CBOR, plutus
+/// scripts as their flat bytes wrapped in a CBOR bytestring.
+#[derive(Debug, Clone)]
+pub struct Script {
+    pub language: Language,
+    pub bytes: Vec<u8>,
+}
+
+impl Script {
+    /// Native scripts given as cardano-cli's JSON, or any script as CBOR.
+    /// Plutus scripts don't say which version they are, so they're taken to
+    /// be `language` or the latest one.
+    pub fn parse(input: &str, language: Option<Language>) -> Result<Self, String> {
+        let input = input.trim();
+
+        if input.starts_with('{') {
+            let json = serde_json::from_str(input).map_err(|e| format!("Invalid JSON: {e}"))?;
+            let script = native_script::from_json(&json)?;
+
+            return match language {
+                None | Some(Language::Native) => Ok(Self {
+                    language: Language::Native,
+                    bytes: minicbor::to_vec(&script).map_err(|e| e.to_string())?,
+                }),
+                Some(language) => Err(format!("JSON scripts are native scripts, not {language}")),
+            };
+        }
+
+        let bytes = likely_encoding(input).decode(input)?;
+
+        // Native scripts are arrays, plutus scripts are bytestrings, so
+        // there's no mistaking one for the other
+        let mut decoder = minicbor::Decoder::new(&bytes);
+        let native = decoder
+            .decode::<NativeScript>()
+            .ok()
+            .filter(|_| decoder.position() == bytes.len());
+
+        match (native, language) {
+            (Some(_), None | Some(Language::Native)) => Ok(Self {
+                language: Language::Native,
+                bytes,
+            }),
+            (None, Some(Language::Native)) => Err("Not a native script".to_string()),
+            (_, language) => {
+                let (flat, _) = unwrap_flat(&bytes)?;
+
+                Ok(Self {
+                    language: language.unwrap_or(Language::Plutus(Version::default())),
+                    bytes: minicbor::to_vec(Bytes::from(flat)).map_err(|e| e.to_string())?,
+                })
+            }
+        }
+    }
+
+    pub fn hash(&self) -> Hash<28> {
+        let mut tagged = vec![self.language.tag()];
+
+        tagged.extend_from_slice(&self.bytes);
+
+        Hasher::<224>::hash(&tagged)
+    }
+}
+
+/// A stake credential given as a reward or base address, or as a bare hash
+/// that `script` says is either a key or a script hash.
+pub fn stake_credential(input: &str, script: bool) -> Result<ShelleyDelegationPart, String> {
+    let input = input.trim();
+
+    if let Ok(hash) = input.parse::<Hash<28>>() {
+        return Ok(if script {
+            ShelleyDelegationPart::Script(hash)
+        } else {
+            ShelleyDelegationPart::Key(hash)
+        });
+    }
+
+    match Address::from_bech32(input) {
+        Ok(Address::Stake(address)) => Ok(match address.payload() {
+            StakePayload::Stake(hash) => ShelleyDelegationPart::Key(*hash),
+            StakePayload::Script(hash) => ShelleyDelegationPart::Script(*hash),
+        }),
+        Ok(Address::Shelley(address)) => match address.delegation() {
+            delegation @ (ShelleyDelegationPart::Key(_) | ShelleyDelegationPart::Script(_)) => {
+                Ok(delegation.clone())
+            }
+            _ => Err("The address has no stake credential".to_string()),
+        },
+        _ => Err("Expected a stake address, a base address or a 28 byte hex hash".to_string()),
+    }
+}
+
+/// The addresses a script hash makes on `network`: locking funds, locking
+/// them under a stake credential, and as a stake credential itself.
+pub fn addresses(
+    hash: Hash<28>,
+    network: Network,
+    stake: Option<&ShelleyDelegationPart>,
+) -> Vec<(String, String)> {
+    let name = match network {
+        Network::Mainnet => "mainnet",
+        _ => "testnet",
+    };
+
+    let bech32 = |address: ShelleyAddress| address.to_bech32().unwrap_or_else(|e| e.to_string());
+
+    let payment = ShelleyPaymentPart::Script(hash);
+
+    let mut addresses = vec![(
+        format!("{name} enterprise"),
+        bech32(ShelleyAddress::new(
+            network,
+            payment.clone(),
+            ShelleyDelegationPart::Null,
+        )),
+    )];
+
+    if let Some(stake) = stake {
+        addresses.push((
+            format!("{name} base"),
+            bech32(ShelleyAddress::new(network, payment.clone(), stake.clone())),
+        ));
+    }
+
+    // Reward addresses can only be made from the delegation part of an
+    // address
+    let reward = StakeAddress::try_from(ShelleyAddress::new(
+        network,
+        payment,
+        ShelleyDelegationPart::Script(hash),
+    ));
+
+    if let Ok(reward) = reward {
+        addresses.push((
+            format!("{name} reward"),
+            reward.to_bech32().unwrap_or_else(|e| e.to_string()),
+        ));
+    }
+
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::ledger::{primitives::PlutusScript, traverse::ComputeHash};
+
+    use super::*;
+
+    const KEY_HASH: &str = "337b62cfff6403a06a3acbc34f8c46003c69fe79a3628cefa9c47251";
+    // The identity program, wrapped once as the ledger stores it
+    const PLUTUS: &str = "450101002499";
+
+    #[test]
+    fn native_scripts_are_hashed_with_their_tag() {
+        let script = Script::parse(
+            &format!(r#"{{"type": "sig", "keyHash": "{KEY_HASH}"}}"#),
+            None,
+        )
+        .unwrap();
+        let native = NativeScript::ScriptPubkey(KEY_HASH.parse().unwrap());
+
+        assert_eq!(script.language, Language::Native);
+        assert_eq!(script.hash(), native.compute_hash());
+
+        // The same script as CBOR
+        let cbor = hex::encode(minicbor::to_vec(&native).unwrap());
+
+        assert_eq!(Script::parse(&cbor, None).unwrap().hash(), script.hash());
+    }
+
+    #[test]
+    fn plutus_scripts_are_hashed_with_their_version() {
+        let cbor = Bytes::from(hex::decode(PLUTUS).unwrap());
+        let hash = |language| Script::parse(PLUTUS, language).unwrap().hash();
+
+        assert_eq!(
+            hash(Some(Language::Plutus(Version::V1))),
+            PlutusScript::<1>(cbor.clone()).compute_hash()
+        );
+        assert_eq!(
+            hash(Some(Language::Plutus(Version::V2))),
+            PlutusScript::<2>(cbor.clone()).compute_hash()
+        );
+        assert_eq!(hash(None), PlutusScript::<3>(cbor).compute_hash());
+
+        // Wrapped twice, as in cardano-cli's text envelopes
+        let twice =
+            hex::encode(minicbor::to_vec(Bytes::from(hex::decode(PLUTUS).unwrap())).unwrap());
+
+        assert_eq!(Script::parse(&twice, None).unwrap().hash(), hash(None));
+    }
+
+    #[test]
+    fn scripts_must_be_the_language_asked_for() {
+        let json = format!(r#"{{"type": "sig", "keyHash": "{KEY_HASH}"}}"#);
+
+        assert_eq!(
+            Script::parse(&json, Some(Language::Plutus(Version::V2))).unwrap_err(),
+            "JSON scripts are native scripts, not Plutus V2"
+        );
+        assert_eq!(
+            Script::parse(PLUTUS, Some(Language::Native)).unwrap_err(),
+            "Not a native script"
+        );
+    }
+
+    #[test]
+    fn stake_credentials_come_from_hashes_or_addresses() {
+        let hash: Hash<28> = KEY_HASH.parse().unwrap();
+
+        assert_eq!(
+            stake_credential(KEY_HASH, true),
+            Ok(ShelleyDelegationPart::Script(hash))
+        );
+        // From CIP-19, both delegating to the same key
+        assert_eq!(
+            stake_credential(
+                "stake1uyehkck0lajq8gr28t9uxnuvgcqrc6070x3k9r8048z8y5gh6ffgw",
+                true
+            ),
+            Ok(ShelleyDelegationPart::Key(hash))
+        );
+        assert_eq!(
+            stake_credential("addr1qx2fxv2umyhttkxyxp8x0dlpdt3k6cwng5pxj3jhsydzer3n0d3vllmyqwsx5wktcd8cc3sq835lu7drv2xwl2wywfgse35a3x", false),
+            Ok(ShelleyDelegationPart::Key(hash))
+        );
+        assert!(stake_credential("abcd", false).is_err());
+    }
+
+    #[test]
+    fn script_hashes_make_enterprise_base_and_reward_addresses() {
+        let hash = Hash::new([1; 28]);
+        let stake = ShelleyDelegationPart::Key(KEY_HASH.parse().unwrap());
+
+        let made = addresses(hash, Network::Testnet, Some(&stake));
+        let labels: Vec<_> = made.iter().map(|(label, _)| label.as_str()).collect();
+
+        assert_eq!(
+            labels,
+            ["testnet enterprise", "testnet base", "testnet reward"]
+        );
+
+        for (_, address) in &made {
+            let address = Address::from_bech32(address).unwrap();
+
+            assert!(address.has_script());
+            assert_eq!(address.network(), Some(Network::Testnet));
+        }
+
+        assert_eq!(addresses(hash, Network::Mainnet, None).len(), 2);
+    }
+}
@@ -0,0 +1,16 @@
+use iced::widget::text_editor;
+
+use super::{Language, Script};
+
+#[derive(Debug, Default)]
+pub struct State {
+    pub input: text_editor::Content,
+    /// Picked by the user, otherwise detected from the input
+    pub language: Option<Language>,
+    pub script: Option<Script>,
+    /// Optional stake credential for base addresses
+    pub stake: String,
+    pub stake_is_script: bool,
+    pub addresses: Vec<(String, String)>,
+    pub warning: Option<String>,
+}
@@ -0,0 +1,82 @@
+use iced::{clipboard, widget::text_editor, Task};
+use pallas::ledger::addresses::Network;
+
+use super::{addresses, stake_credential, Language, Script, State};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    InputChanged(text_editor::Action),
+    LanguageSet(Language),
+    StakeChanged(String),
+    StakeIsScriptToggled(bool),
+    Copy(String),
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            InputChanged(action) => {
+                self.input.perform(action);
+                self.derive();
+                Task::none()
+            }
+            LanguageSet(language) => {
+                self.language = Some(language);
+                self.derive();
+                Task::none()
+            }
+            StakeChanged(stake) => {
+                self.stake = stake;
+                self.derive();
+                Task::none()
+            }
+            StakeIsScriptToggled(script) => {
+                self.stake_is_script = script;
+                self.derive();
+                Task::none()
+            }
+            Copy(s) => clipboard::write(s),
+        }
+    }
+
+    fn derive(&mut self) {
+        self.script = None;
+        self.addresses.clear();
+        self.warning = None;
+
+        let input = self.input.text();
+
+        if input.trim().is_empty() {
+            return;
+        }
+
+        let script = match Script::parse(&input, self.language) {
+            Ok(script) => script,
+            Err(e) => {
+                self.warning = Some(e);
+                return;
+            }
+        };
+
+        let stake = if self.stake.trim().is_empty() {
+            None
+        } else {
+            match stake_credential(&self.stake, self.stake_is_script) {
+                Ok(stake) => Some(stake),
+                Err(e) => {
+                    self.warning = Some(e);
+                    None
+                }
+            }
+        };
+
+        let hash = script.hash();
+
+        self.addresses = [Network::Mainnet, Network::Testnet]
+            .into_iter()
+            .flat_map(|network| addresses(hash, network, stake.as_ref()))
+            .collect();
+        self.script = Some(script);
+    }
+}
@@ -0,0 +1,90 @@
+use iced::{
+    padding,
+    widget::{checkbox, column, pick_list, row, scrollable, text, text_editor, text_input},
+    Element,
+    Length::{Fill, FillPortion},
+    Theme,
+};
+
+use super::{Language, Message, State};
+use crate::copyable_text::copyable_text;
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        let field = |label: String, value: String| {
+            row![text(label).width(160), copyable_text(&value, Message::Copy)]
+        };
+
+        let script = self.script.as_ref().map(|script| {
+            column![
+                field(
+                    script.language.to_string(),
+                    format!("{} bytes", script.bytes.len())
+                ),
+                field("script hash".to_string(), script.hash().to_string()),
+            ]
+            .spacing(5)
+        });
+
+        row![
+            column![
+                pick_list(
+                    &Language::ALL[..],
+                    self.language
+                        .or(self.script.as_ref().map(|script| script.language)),
+                    Message::LanguageSet
+                )
+                .placeholder("language")
+                .width(Fill),
+                text_editor(&self.input)
+                    .placeholder("script cbor or native script json...")
+                    .on_action(Message::InputChanged)
+                    .height(Fill)
+                    .wrapping(text::Wrapping::Glyph)
+                    .style(|theme: &Theme, status| {
+                        let mut defaults = text_editor::default(theme, status);
+
+                        defaults.border.radius = 4.0.into();
+
+                        defaults
+                    }),
+                text_input(
+                    "stake credential: stake1..., addr1... or a hash",
+                    &self.stake
+                )
+                .on_input(Message::StakeChanged),
+                checkbox("the stake hash is a script hash", self.stake_is_script)
+                    .on_toggle(Message::StakeIsScriptToggled)
+            ]
+            .spacing(5)
+            .width(FillPortion(1))
+            .padding(5),
+            column![]
+                .push_maybe(script)
+                .push_maybe(
+                    self.warning
+                        .as_ref()
+                        .map(|warning| text(warning).color([1.0, 0.0, 0.0]))
+                )
+                .push(
+                    scrollable(
+                        column(
+                            self.addresses.iter().map(|(label, address)| field(
+                                label.clone(),
+                                address.clone()
+                            )
+                            .into())
+                        )
+                        .spacing(5)
+                        .padding(padding::right(15))
+                    )
+                    .height(Fill)
+                )
+                .spacing(10)
+                .width(FillPortion(2))
+                .padding(5)
+        ]
+        .padding(padding::top(20).left(5).right(10).bottom(10))
+        .into()
+    }
+}
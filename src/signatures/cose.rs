@@ -0,0 +1,468 @@
+//! CIP-8 message signing, as returned by CIP-30's `signData`: a COSE_Sign1
+//! over the payload and the COSE_Key to check it with.
+
+use cryptoxide::ed25519;
+use pallas::{
+    codec::minicbor::Encoder,
+    crypto::hash::{Hash, Hasher},
+    ledger::addresses::{Address, ShelleyDelegationPart, ShelleyPaymentPart, StakePayload},
+};
+
+use crate::cbor_explorer::{decode_sequence, Item, Kind};
+
+use super::sign_with_edd25519;
+
+/// EdDSA, the only algorithm CIP-8 signs with.
+const EDDSA: i64 = -8;
+
+/// Header labels from RFC 8152.
+const ALGORITHM: i64 = 1;
+const KEY_ID: i64 = 4;
+
+/// COSE_Key labels and values for an Ed25519 public key.
+const KEY_TYPE: i64 = 1;
+const KEY_KEY_ID: i64 = 2;
+const KEY_ALGORITHM: i64 = 3;
+const CURVE: i64 = -1;
+const X: i64 = -2;
+const OKP: i64 = 1;
+const ED25519: i64 = 6;
+
+#[derive(Debug, Clone)]
+pub struct Sign1 {
+    /// The protected headers as signed, which aren't necessarily encoded the
+    /// way they'd be encoded again
+    pub protected: Vec<u8>,
+    pub algorithm: Option<i64>,
+    pub key_id: Option<Vec<u8>>,
+    pub address: Option<Vec<u8>>,
+    /// Whether the payload is the blake2b-224 hash of the message rather than
+    /// the message itself
+    pub hashed: bool,
+    /// Nothing when the payload is detached
+    pub payload: Option<Vec<u8>>,
+    pub signature: Vec<u8>,
+}
+
+impl Sign1 {
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let item = single(bytes)?;
+
+        // Tag 18 marks a COSE_Sign1, wallets tend to leave it out
+        let item = match &item.kind {
+            Kind::Tag(18, item) => item.as_ref(),
+            _ => &item,
+        };
+
+        let Kind::Array { items, .. } = &item.kind else {
+            return Err("A COSE_Sign1 is an array".to_string());
+        };
+
+        let [protected, unprotected, payload, signature] = items.as_slice() else {
+            return Err(format!(
+                "A COSE_Sign1 has 4 items, this one has {}",
+                items.len()
+            ));
+        };
+
+        let protected = bytes_of(protected).ok_or("The protected headers must be bytes")?;
+
+        let protected_headers = if protected.is_empty() {
+            vec![]
+        } else {
+            match single(&protected)?.kind {
+                Kind::Map { entries, .. } => entries,
+                _ => return Err("The protected headers must be a map".to_string()),
+            }
+        };
+
+        let Kind::Map {
+            entries: unprotected_headers,
+            ..
+        } = &unprotected.kind
+        else {
+            return Err("The unprotected headers must be a map".to_string());
+        };
+
+        let header = |label: &Label| {
+            lookup(&protected_headers, label).or_else(|| lookup(unprotected_headers, label))
+        };
+
+        let payload = match &payload.kind {
+            Kind::Null => None,
+            _ => Some(bytes_of(payload).ok_or("The payload must be bytes or null")?),
+        };
+
+        Ok(Self {
+            algorithm: header(&Label::Int(ALGORITHM)).and_then(int_of),
+            key_id: header(&Label::Int(KEY_ID)).and_then(bytes_of),
+            address: header(&Label::Text("address")).and_then(bytes_of),
+            hashed: matches!(
+                header(&Label::Text("hashed")).map(|item| &item.kind),
+                Some(Kind::Bool(true))
+            ),
+            protected,
+            payload,
+            signature: bytes_of(signature).ok_or("The signature must be bytes")?,
+        })
+    }
+
+    /// Untagged, the way CIP-30 wallets return it.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut e = Encoder::new(vec![]);
+
+        e.array(4).unwrap();
+        e.bytes(&self.protected).unwrap();
+        e.map(1)
+            .unwrap()
+            .str("hashed")
+            .unwrap()
+            .bool(self.hashed)
+            .unwrap();
+
+        match &self.payload {
+            Some(payload) => e.bytes(payload).unwrap(),
+            None => e.null().unwrap(),
+        };
+
+        e.bytes(&self.signature).unwrap();
+
+        e.into_writer()
+    }
+
+    /// What's actually signed: the protected headers and the payload,
+    /// without any external data.
+    pub fn sig_structure(protected: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut e = Encoder::new(vec![]);
+
+        e.array(4)
+            .unwrap()
+            .str("Signature1")
+            .unwrap()
+            .bytes(protected)
+            .unwrap()
+            .bytes(&[])
+            .unwrap()
+            .bytes(payload)
+            .unwrap();
+
+        e.into_writer()
+    }
+
+    /// Check the signature, against `detached` when the payload isn't in
+    /// the COSE_Sign1 itself.
+    pub fn verify(&self, public_key: &[u8], detached: &[u8]) -> Result<bool, String> {
+        let payload = match &self.payload {
+            Some(payload) => payload.clone(),
+            None if detached.is_empty() => {
+                return Err("The payload is detached, paste the message it was over".to_string())
+            }
+            None if self.hashed => Hasher::<224>::hash(detached).to_vec(),
+            None => detached.to_vec(),
+        };
+
+        let public_key: [u8; ed25519::PUBLIC_KEY_LENGTH] = public_key
+            .try_into()
+            .map_err(|_| format!("The public key must be 32 bytes, got {}", public_key.len()))?;
+
+        let signature: [u8; ed25519::SIGNATURE_LENGTH] =
+            self.signature.as_slice().try_into().map_err(|_| {
+                format!(
+                    "The signature must be 64 bytes, got {}",
+                    self.signature.len()
+                )
+            })?;
+
+        Ok(ed25519::verify(
+            &Self::sig_structure(&self.protected, &payload),
+            &public_key,
+            &signature,
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CoseKey {
+    pub key_type: Option<i64>,
+    pub key_id: Option<Vec<u8>>,
+    pub algorithm: Option<i64>,
+    pub curve: Option<i64>,
+    pub x: Vec<u8>,
+}
+
+impl CoseKey {
+    /// A COSE_Key, or a bare 32 byte public key for convenience.
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let entries = match decode_sequence(bytes).map(|mut items| (items.len(), items.pop())) {
+            Ok((
+                1,
+                Some(Item {
+                    kind: Kind::Map { entries, .. },
+                    ..
+                }),
+            )) => entries,
+            _ if bytes.len() == ed25519::PUBLIC_KEY_LENGTH => {
+                return Ok(Self {
+                    key_type: None,
+                    key_id: None,
+                    algorithm: None,
+                    curve: None,
+                    x: bytes.to_vec(),
+                })
+            }
+            _ => return Err("Expected a COSE_Key map or a 32 byte public key".to_string()),
+        };
+
+        let get = |label| lookup(&entries, &Label::Int(label));
+
+        Ok(Self {
+            key_type: get(KEY_TYPE).and_then(int_of),
+            key_id: get(KEY_KEY_ID).and_then(bytes_of),
+            algorithm: get(KEY_ALGORITHM).and_then(int_of),
+            curve: get(CURVE).and_then(int_of),
+            x: get(X)
+                .and_then(bytes_of)
+                .ok_or("The COSE_Key has no public key (-2)")?,
+        })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut e = Encoder::new(vec![]);
+
+        e.map(4)
+            .unwrap()
+            .i64(KEY_TYPE)
+            .unwrap()
+            .i64(OKP)
+            .unwrap()
+            .i64(KEY_ALGORITHM)
+            .unwrap()
+            .i64(EDDSA)
+            .unwrap()
+            .i64(CURVE)
+            .unwrap()
+            .i64(ED25519)
+            .unwrap()
+            .i64(X)
+            .unwrap()
+            .bytes(&self.x)
+            .unwrap();
+
+        e.into_writer()
+    }
+}
+
+/// Sign `message` the way CIP-30's `signData` does, with the address in the
+/// protected headers.
+pub fn sign(
+    private_key: Vec<u8>,
+    message: &[u8],
+    address: Option<Vec<u8>>,
+    hashed: bool,
+) -> Result<(Sign1, CoseKey), String> {
+    let mut e = Encoder::new(vec![]);
+
+    e.map(if address.is_some() { 2 } else { 1 })
+        .unwrap()
+        .i64(ALGORITHM)
+        .unwrap()
+        .i64(EDDSA)
+        .unwrap();
+
+    if let Some(address) = &address {
+        e.str("address").unwrap().bytes(address).unwrap();
+    }
+
+    let protected = e.into_writer();
+
+    let payload = if hashed {
+        Hasher::<224>::hash(message).to_vec()
+    } else {
+        message.to_vec()
+    };
+
+    let (public_key, signature) =
+        sign_with_edd25519(private_key, Sign1::sig_structure(&protected, &payload))?;
+
+    Ok((
+        Sign1 {
+            protected,
+            algorithm: Some(EDDSA),
+            key_id: None,
+            address,
+            hashed,
+            payload: Some(payload),
+            signature: signature.to_vec(),
+        },
+        CoseKey {
+            key_type: Some(OKP),
+            key_id: None,
+            algorithm: Some(EDDSA),
+            curve: Some(ED25519),
+            x: public_key.to_vec(),
+        },
+    ))
+}
+
+/// Which of the address's credentials the key hashes to, if any.
+pub fn key_role(address: &[u8], public_key: &[u8]) -> Option<&'static str> {
+    let hash = Hasher::<224>::hash(public_key);
+    let is = |other: &Hash<28>| *other == hash;
+
+    match Address::from_bytes(address).ok()? {
+        Address::Shelley(address) => {
+            if matches!(address.payment(), ShelleyPaymentPart::Key(key) if is(key)) {
+                Some("payment")
+            } else if matches!(address.delegation(), ShelleyDelegationPart::Key(key) if is(key)) {
+                Some("stake")
+            } else {
+                None
+            }
+        }
+        Address::Stake(address) => {
+            matches!(address.payload(), StakePayload::Stake(key) if is(key)).then_some("stake")
+        }
+        Address::Byron(_) => None,
+    }
+}
+
+pub fn algorithm_name(algorithm: i64) -> String {
+    match algorithm {
+        EDDSA => format!("{algorithm} (EdDSA)"),
+        -7 => format!("{algorithm} (ES256)"),
+        -47 => format!("{algorithm} (ES256K)"),
+        _ => algorithm.to_string(),
+    }
+}
+
+enum Label {
+    Int(i64),
+    Text(&'static str),
+}
+
+fn lookup<'a>(entries: &'a [(Item, Item)], label: &Label) -> Option<&'a Item> {
+    entries
+        .iter()
+        .find(|(key, _)| match (label, &key.kind) {
+            (Label::Int(label), _) => int_of(key) == Some(*label),
+            (Label::Text(label), Kind::Text(text)) => label == text,
+            _ => false,
+        })
+        .map(|(_, value)| value)
+}
+
+fn single(bytes: &[u8]) -> Result<Item, String> {
+    let mut items = decode_sequence(bytes).map_err(|e| format!("Invalid cbor: {e}"))?;
+
+    match items.len() {
+        1 => Ok(items.remove(0)),
+        n => Err(format!("Expected a single cbor item, got {n}")),
+    }
+}
+
+fn int_of(item: &Item) -> Option<i64> {
+    match item.kind {
+        Kind::Unsigned(n) => i64::try_from(n).ok(),
+        Kind::Negative(n) => i64::try_from(n).ok().map(|n| -1 - n),
+        _ => None,
+    }
+}
+
+fn bytes_of(item: &Item) -> Option<Vec<u8>> {
+    match &item.kind {
+        Kind::Bytes(bytes) => Some(bytes.clone()),
+        Kind::Chunks(chunks) => chunks
+            .iter()
+            .map(bytes_of)
+            .collect::<Option<Vec<_>>>()
+            .map(|chunks| chunks.concat()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::ledger::addresses::{Network, ShelleyAddress};
+
+    use super::*;
+
+    const SEED: [u8; 32] = [7; 32];
+
+    #[test]
+    fn the_sig_structure_is_a_signature1_array() {
+        // {1: -8} over "hello"
+        assert_eq!(
+            hex::encode(Sign1::sig_structure(&[0xa1, 0x01, 0x27], b"hello")),
+            "846a5369676e61747572653143a101274045".to_string() + &hex::encode("hello")
+        );
+    }
+
+    #[test]
+    fn signed_data_round_trips_and_verifies() {
+        let address = ShelleyAddress::new(
+            Network::Testnet,
+            ShelleyPaymentPart::Key(Hasher::<224>::hash(&ed25519::keypair(&SEED).1)),
+            ShelleyDelegationPart::Null,
+        )
+        .to_vec();
+
+        let (sign1, key) = sign(SEED.to_vec(), b"hello", Some(address.clone()), false).unwrap();
+
+        let decoded = Sign1::decode(&sign1.encode()).unwrap();
+        let key = CoseKey::decode(&key.encode()).unwrap();
+
+        assert_eq!(decoded.algorithm, Some(EDDSA));
+        assert_eq!(decoded.address.as_deref(), Some(address.as_slice()));
+        assert_eq!(decoded.payload.as_deref(), Some(&b"hello"[..]));
+        assert_eq!(decoded.verify(&key.x, &[]), Ok(true));
+        assert_eq!(key_role(&address, &key.x), Some("payment"));
+
+        let mut tampered = decoded;
+        tampered.payload = Some(b"hullo".to_vec());
+
+        assert_eq!(tampered.verify(&key.x, &[]), Ok(false));
+    }
+
+    #[test]
+    fn detached_payloads_are_checked_against_the_message() {
+        let (mut sign1, key) = sign(SEED.to_vec(), b"hello", None, true).unwrap();
+
+        sign1.payload = None;
+
+        assert_eq!(sign1.verify(&key.x, b"hello"), Ok(true));
+        assert_eq!(sign1.verify(&key.x, b"hullo"), Ok(false));
+        assert!(sign1.verify(&key.x, &[]).is_err());
+    }
+
+    #[test]
+    fn protected_headers_are_verified_as_they_were_signed() {
+        // {1: -8}, with the -8 needlessly taking two bytes
+        let protected = vec![0xa1, 0x01, 0x38, 0x07];
+        let (public_key, signature) =
+            sign_with_edd25519(SEED.to_vec(), Sign1::sig_structure(&protected, b"hello")).unwrap();
+
+        // Tagged, with the protected headers in a bytestring
+        let mut e = Encoder::new(vec![]);
+        e.tag(pallas::codec::minicbor::data::Tag::new(18))
+            .and_then(|e| e.array(4))
+            .and_then(|e| e.bytes(&protected))
+            .and_then(|e| e.map(0))
+            .and_then(|e| e.bytes(b"hello"))
+            .and_then(|e| e.bytes(&signature))
+            .unwrap();
+
+        let sign1 = Sign1::decode(&e.into_writer()).unwrap();
+
+        assert_eq!(sign1.algorithm, Some(EDDSA));
+        assert_eq!(sign1.protected, protected);
+        assert_eq!(sign1.verify(&public_key, &[]), Ok(true));
+    }
+
+    #[test]
+    fn keys_can_be_bare() {
+        let (_, public_key) = ed25519::keypair(&SEED);
+
+        assert_eq!(CoseKey::decode(&public_key).unwrap().x, public_key);
+        assert!(CoseKey::decode(&[0xa0]).is_err());
+    }
+}
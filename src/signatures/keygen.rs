@@ -0,0 +1,247 @@
+//! Fresh key pairs for each scheme, in the forms cardano-cli and wallets
+//! read them in.
+
+use std::fmt::Display;
+
+use cryptoxide::ed25519;
+use secp256k1::rand::{rngs::OsRng, RngCore};
+
+use crate::encoding::{encode_bech32, TextEnvelope};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum KeyKind {
+    #[default]
+    Ed25519,
+    /// The 64 byte expanded secret HD wallets derive, with a chain code
+    Ed25519Extended,
+    EcdsaSecp256k1,
+    SchnorrSecp256k1,
+}
+
+impl KeyKind {
+    pub const ALL: [KeyKind; 4] = [
+        KeyKind::Ed25519,
+        KeyKind::Ed25519Extended,
+        KeyKind::EcdsaSecp256k1,
+        KeyKind::SchnorrSecp256k1,
+    ];
+}
+
+impl Display for KeyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                KeyKind::Ed25519 => "Ed25519",
+                KeyKind::Ed25519Extended => "Ed25519 extended",
+                KeyKind::EcdsaSecp256k1 => "EcdsaSecp256k1",
+                KeyKind::SchnorrSecp256k1 => "SchnorrSecp256k1",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratedKey {
+    pub kind: KeyKind,
+    pub secret: Vec<u8>,
+    /// Every form of the key pair worth copying, by name
+    pub encodings: Vec<(&'static str, String)>,
+    /// The .skey and .vkey files cardano-cli would have written, for the
+    /// schemes it has types for
+    pub envelopes: Vec<(&'static str, TextEnvelope)>,
+}
+
+pub fn generate_key(kind: KeyKind) -> GeneratedKey {
+    match kind {
+        KeyKind::Ed25519 => {
+            let mut secret = [0; ed25519::PRIVATE_KEY_LENGTH];
+            OsRng.fill_bytes(&mut secret);
+
+            let (_, public) = ed25519::keypair(&secret);
+
+            GeneratedKey {
+                kind,
+                secret: secret.to_vec(),
+                encodings: vec![
+                    ("secret key", hex::encode(secret)),
+                    ("public key", hex::encode(public)),
+                    ("ed25519_sk", encode_bech32("ed25519_sk", &secret)),
+                    ("ed25519_pk", encode_bech32("ed25519_pk", &public)),
+                ],
+                envelopes: vec![
+                    (
+                        ".skey",
+                        TextEnvelope::wrapping(
+                            "PaymentSigningKeyShelley_ed25519",
+                            "Payment Signing Key",
+                            &secret,
+                        ),
+                    ),
+                    (
+                        ".vkey",
+                        TextEnvelope::wrapping(
+                            "PaymentVerificationKeyShelley_ed25519",
+                            "Payment Verification Key",
+                            &public,
+                        ),
+                    ),
+                ],
+            }
+        }
+        KeyKind::Ed25519Extended => {
+            let mut secret = [0; ed25519::EXTENDED_KEY_LENGTH];
+            let mut chain_code = [0; 32];
+            OsRng.fill_bytes(&mut secret);
+            OsRng.fill_bytes(&mut chain_code);
+
+            // Clamp the scalar as Ed25519-BIP32 does, including clearing the
+            // third highest bit so derived keys can't overflow
+            secret[0] &= 0b1111_1000;
+            secret[31] &= 0b0001_1111;
+            secret[31] |= 0b0100_0000;
+
+            let public = ed25519::extended_to_public(&secret);
+
+            GeneratedKey {
+                kind,
+                secret: secret.to_vec(),
+                encodings: vec![
+                    ("secret key", hex::encode(secret)),
+                    ("public key", hex::encode(public)),
+                    ("chain code", hex::encode(chain_code)),
+                    ("ed25519e_sk", encode_bech32("ed25519e_sk", &secret)),
+                    ("ed25519_pk", encode_bech32("ed25519_pk", &public)),
+                ],
+                envelopes: vec![
+                    (
+                        ".skey",
+                        TextEnvelope::wrapping(
+                            "PaymentExtendedSigningKeyShelley_ed25519_bip32",
+                            "Payment Signing Key",
+                            &[&secret[..], &public, &chain_code].concat(),
+                        ),
+                    ),
+                    (
+                        ".vkey",
+                        TextEnvelope::wrapping(
+                            "PaymentExtendedVerificationKeyShelley_ed25519_bip32",
+                            "Payment Verification Key",
+                            &[&public[..], &chain_code].concat(),
+                        ),
+                    ),
+                ],
+            }
+        }
+        KeyKind::EcdsaSecp256k1 | KeyKind::SchnorrSecp256k1 => {
+            let secp = secp256k1::Secp256k1::new();
+            let (secret, public) = secp.generate_keypair(&mut OsRng);
+
+            let public = match kind {
+                KeyKind::SchnorrSecp256k1 => public.x_only_public_key().0.serialize().to_vec(),
+                _ => public.serialize().to_vec(),
+            };
+
+            GeneratedKey {
+                kind,
+                secret: secret.secret_bytes().to_vec(),
+                encodings: vec![
+                    ("secret key", hex::encode(secret.secret_bytes())),
+                    ("public key", hex::encode(public)),
+                ],
+                envelopes: vec![],
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        encoding::decode_bech32,
+        signatures::{verify, Scheme, Verdict},
+    };
+
+    use super::*;
+
+    fn encoding<'a>(key: &'a GeneratedKey, name: &str) -> &'a str {
+        key.encodings
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, encoded)| encoded.as_str())
+            .unwrap()
+    }
+
+    fn envelope(key: &GeneratedKey, extension: &str) -> Vec<u8> {
+        key.envelopes
+            .iter()
+            .find(|(e, _)| *e == extension)
+            .map(|(_, envelope)| envelope.decode().unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn ed25519_keys_in_every_form_agree() {
+        let key = generate_key(KeyKind::Ed25519);
+        let (_, public) = ed25519::keypair(&key.secret.clone().try_into().unwrap());
+
+        assert_eq!(encoding(&key, "public key"), hex::encode(public));
+        assert_eq!(
+            decode_bech32(encoding(&key, "ed25519_sk")).unwrap(),
+            ("ed25519_sk".to_string(), key.secret.clone())
+        );
+        assert_eq!(envelope(&key, ".skey"), key.secret);
+        assert_eq!(envelope(&key, ".vkey"), public);
+    }
+
+    #[test]
+    fn extended_keys_are_clamped_and_stored_as_cardano_cli_does() {
+        let key = generate_key(KeyKind::Ed25519Extended);
+        let secret: [u8; 64] = key.secret.clone().try_into().unwrap();
+        let public = ed25519::extended_to_public(&secret);
+
+        assert_eq!(secret[0] & 0b0000_0111, 0);
+        assert_eq!(secret[31] & 0b1110_0000, 0b0100_0000);
+
+        // The secret, then the public key and the chain code
+        let skey = envelope(&key, ".skey");
+        let chain_code = hex::decode(encoding(&key, "chain code")).unwrap();
+
+        assert_eq!(skey.len(), 128);
+        assert_eq!(skey[..64], secret);
+        assert_eq!(skey[64..96], public);
+        assert_eq!(skey[96..], chain_code);
+        assert_eq!(envelope(&key, ".vkey"), [&public[..], &chain_code].concat());
+    }
+
+    #[test]
+    fn secp256k1_public_keys_are_the_form_the_builtins_take() {
+        let ecdsa = generate_key(KeyKind::EcdsaSecp256k1);
+        let schnorr = generate_key(KeyKind::SchnorrSecp256k1);
+
+        let public = |key: &GeneratedKey| hex::decode(encoding(key, "public key")).unwrap();
+
+        assert_eq!(public(&ecdsa).len(), secp256k1::constants::PUBLIC_KEY_SIZE);
+        assert_eq!(
+            public(&schnorr).len(),
+            secp256k1::constants::SCHNORR_PUBLIC_KEY_SIZE
+        );
+        assert!(ecdsa.envelopes.is_empty());
+
+        // Signed with the secret, the public key checks out
+        let secp = secp256k1::Secp256k1::new();
+        let keypair = secp256k1::Keypair::from_seckey_slice(&secp, &schnorr.secret).unwrap();
+        let signature = secp.sign_schnorr_no_aux_rand(b"message", &keypair);
+
+        assert_eq!(
+            verify(
+                Scheme::SchnorrSecp256k1,
+                &public(&schnorr),
+                b"message",
+                signature.as_ref()
+            ),
+            Verdict::Valid
+        );
+    }
+}
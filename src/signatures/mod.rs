@@ -1,6 +1,12 @@
+mod cose;
+mod keygen;
 mod state;
 mod update;
+mod verify;
 mod view;
 
+pub use cose::*;
+pub use keygen::*;
 pub use state::*;
 pub use update::*;
+pub use verify::*;
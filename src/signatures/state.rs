@@ -1,12 +1,27 @@
+use std::fmt::Display;
+
 use iced::widget::text_editor;
 
-use crate::encoding::Encoding;
+use crate::encoding::{Detected, Encoding};
+
+use super::{CoseKey, GeneratedKey, KeyKind, Scheme, Sign1, Verdict};
 
 #[derive(Debug)]
 pub struct State {
+    pub mode: Mode,
     pub message: text_editor::Content,
     pub private_key: text_editor::Content,
+    pub key_kind: KeyKind,
+    /// The last generated key, for as long as it's the one being signed with
+    pub generated: Option<GeneratedKey>,
+    /// Where to save the generated key's TextEnvelopes, without extension
+    pub key_path: String,
+    pub saved: Option<Result<String, String>>,
     pub encoding: Option<Encoding>,
+    pub key_encoding: Option<Encoding>,
+    /// The encodings the message and the key could be in
+    pub detected: Detected,
+    pub key_detected: Detected,
     pub warning: Option<String>,
     pub ed25519_pub: String,
     pub ed25519_sig: String,
@@ -14,6 +29,45 @@ pub struct State {
     pub ecdsa_secp256k1_sig: String,
     pub schnorr_secp256k1_pub: String,
     pub schnorr_secp256k1_sig: String,
+    pub public_key: text_editor::Content,
+    pub signature: text_editor::Content,
+    pub verdicts: Vec<(Scheme, Verdict)>,
+    /// Address to put in the protected headers of the COSE_Sign1 we sign
+    pub address: String,
+    pub hashed: bool,
+    /// The COSE_Sign1 and COSE_Key we signed, in hex
+    pub signed: Option<(String, String)>,
+    pub sign1: text_editor::Content,
+    pub cose_key: text_editor::Content,
+    pub decoded_sign1: Option<Result<Box<Sign1>, String>>,
+    pub decoded_key: Option<Result<Box<CoseKey>, String>>,
+    pub verified: Option<Result<bool, String>>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Mode {
+    #[default]
+    Sign,
+    Verify,
+    Cose,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 3] = [Mode::Sign, Mode::Verify, Mode::Cose];
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Mode::Sign => "Sign",
+                Mode::Verify => "Verify",
+                Mode::Cose => "COSE (CIP-8)",
+            }
+        )
+    }
 }
 
 impl State {
@@ -48,9 +102,17 @@ impl State {
 impl Default for State {
     fn default() -> Self {
         Self {
+            mode: Mode::default(),
             message: text_editor::Content::default(),
             private_key: text_editor::Content::default(),
+            key_kind: KeyKind::default(),
+            generated: None,
+            key_path: "".to_string(),
+            saved: None,
             encoding: None,
+            key_encoding: None,
+            detected: Detected::default(),
+            key_detected: Detected::default(),
             warning: None,
             ed25519_pub: "".to_string(),
             ed25519_sig: "".to_string(),
@@ -58,6 +120,17 @@ impl Default for State {
             ecdsa_secp256k1_sig: "".to_string(),
             schnorr_secp256k1_pub: "".to_string(),
             schnorr_secp256k1_sig: "".to_string(),
+            public_key: text_editor::Content::default(),
+            signature: text_editor::Content::default(),
+            verdicts: vec![],
+            address: "".to_string(),
+            hashed: false,
+            signed: None,
+            sign1: text_editor::Content::default(),
+            cose_key: text_editor::Content::default(),
+            decoded_sign1: None,
+            decoded_key: None,
+            verified: None,
         }
     }
 }
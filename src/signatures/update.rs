@@ -1,19 +1,36 @@
-use base64::{prelude::BASE64_STANDARD, Engine};
 use cryptoxide::ed25519::{self};
 use iced::{clipboard, widget::text_editor, Task};
-use secp256k1::rand::rngs::OsRng;
 
-use crate::encoding::{detect_encoding, Encoding};
+use crate::{
+    address_inspector,
+    encoding::{likely_encoding, Detected, Encoding, TextEnvelope},
+};
 
-use super::state::State;
+use super::{
+    cose, generate_key,
+    state::{Mode, State},
+    verify, CoseKey, KeyKind, Scheme, Sign1,
+};
 
 #[derive(Debug, Clone)]
 pub enum Message {
     ContentsChanged(text_editor::Action),
     KeyChanged(text_editor::Action),
+    KeyKindSet(KeyKind),
     GenerateKey,
+    KeyPathChanged(String),
+    SaveKey,
+    Saved(Result<String, String>),
     EncodingSet(Encoding),
+    KeyEncodingSet(Encoding),
     CopyText(String),
+    ModeSet(Mode),
+    PublicKeyChanged(text_editor::Action),
+    SignatureChanged(text_editor::Action),
+    AddressChanged(String),
+    HashedToggled(bool),
+    Sign1Changed(text_editor::Action),
+    CoseKeyChanged(text_editor::Action),
 }
 
 impl State {
@@ -24,32 +41,242 @@ impl State {
                 self.warning = None;
                 self.message.perform(action);
 
-                self.update_signatures();
+                self.refresh();
                 Task::none()
             }
             KeyChanged(action) => {
                 self.warning = None;
+
+                if action.is_edit() {
+                    self.generated = None;
+                }
+
                 self.private_key.perform(action);
-                self.update_signatures();
+                self.refresh();
                 Task::none()
             }
             EncodingSet(enc) => {
                 self.warning = None;
                 self.encoding = Some(enc);
-                self.update_signatures();
+                self.refresh();
+                Task::none()
+            }
+            KeyEncodingSet(enc) => {
+                self.warning = None;
+                self.key_encoding = Some(enc);
+                self.refresh();
                 Task::none()
             }
             CopyText(s) => clipboard::write(s.clone()),
+            KeyKindSet(kind) => {
+                self.key_kind = kind;
+                Task::none()
+            }
             GenerateKey => {
-                let mut rng = OsRng;
-                let sk = secp256k1::Secp256k1::new();
-                let x = sk.generate_keypair(&mut rng).0.secret_bytes();
+                let generated = generate_key(self.key_kind);
 
                 self.warning = None;
-                self.private_key = text_editor::Content::with_text(&hex::encode(x));
-                self.update_signatures();
+                self.key_encoding = None;
+                self.private_key = text_editor::Content::with_text(&hex::encode(&generated.secret));
+                self.generated = Some(generated);
+                self.saved = None;
+                self.refresh();
+                Task::none()
+            }
+            KeyPathChanged(path) => {
+                self.key_path = path;
+                Task::none()
+            }
+            SaveKey => {
+                let Some(generated) = &self.generated else {
+                    return Task::none();
+                };
+
+                Task::perform(
+                    save(
+                        self.key_path.trim().to_string(),
+                        generated.envelopes.clone(),
+                    ),
+                    Saved,
+                )
+            }
+            Saved(result) => {
+                self.saved = Some(result);
+                Task::none()
+            }
+            ModeSet(mode) => {
+                self.warning = None;
+                self.mode = mode;
+                self.refresh();
+                Task::none()
+            }
+            PublicKeyChanged(action) => {
+                self.warning = None;
+                self.public_key.perform(action);
+                self.update_verdicts();
+                Task::none()
+            }
+            SignatureChanged(action) => {
+                self.warning = None;
+                self.signature.perform(action);
+                self.update_verdicts();
+                Task::none()
+            }
+            AddressChanged(address) => {
+                self.warning = None;
+                self.address = address;
+                self.update_signed();
+                Task::none()
+            }
+            HashedToggled(hashed) => {
+                self.warning = None;
+                self.hashed = hashed;
+                self.update_signed();
+                Task::none()
+            }
+            Sign1Changed(action) => {
+                self.sign1.perform(action);
+                self.update_decoded();
                 Task::none()
             }
+            CoseKeyChanged(action) => {
+                self.cose_key.perform(action);
+                self.update_decoded();
+                Task::none()
+            }
+        }
+    }
+
+    /// Redo whatever the current mode shows, for changes both modes share.
+    fn refresh(&mut self) {
+        self.detected = Detected::new(&self.text_message(), self.encoding.as_ref());
+        self.key_detected = Detected::new(&self.text_key(), self.key_encoding.as_ref());
+
+        match self.mode {
+            Mode::Sign => self.update_signatures(),
+            Mode::Verify => self.update_verdicts(),
+            // The message doubles as the payload of detached COSE_Sign1s
+            Mode::Cose => {
+                self.update_signed();
+                self.update_decoded();
+            }
+        }
+    }
+
+    /// Sign the message into a COSE_Sign1 the way a CIP-30 wallet would.
+    fn update_signed(&mut self) {
+        self.signed = None;
+
+        let message = self.text_message();
+        let private_key = self.text_key();
+
+        if private_key.trim().is_empty() {
+            return;
+        }
+
+        let signed = self
+            .detected
+            .encoding()
+            .decode(&message)
+            .map_err(|e| format!("Message: {e}"))
+            .and_then(|message| {
+                let private_key = self
+                    .key_detected
+                    .encoding()
+                    .decode(private_key.trim())
+                    .map_err(|e| format!("Private key: {e}"))?;
+
+                let address = match self.address.trim() {
+                    "" => None,
+                    address => Some(
+                        address_inspector::parse(address)
+                            .ok_or("Address: expected bech32, base58 or hex")?
+                            .1
+                            .to_vec(),
+                    ),
+                };
+
+                cose::sign(private_key, &message, address, self.hashed)
+            });
+
+        match signed {
+            Ok((sign1, key)) => {
+                self.signed = Some((hex::encode(sign1.encode()), hex::encode(key.encode())))
+            }
+            Err(e) => self.warning = Some(e),
+        }
+    }
+
+    /// Decode the pasted COSE_Sign1 and COSE_Key, and check one against the
+    /// other.
+    fn update_decoded(&mut self) {
+        let decode = |content: &text_editor::Content| {
+            let s = content.text();
+            let s = s.trim();
+
+            (!s.is_empty()).then(|| likely_encoding(s).decode(s))
+        };
+
+        self.decoded_sign1 = decode(&self.sign1)
+            .map(|bytes| bytes.and_then(|bytes| Sign1::decode(&bytes).map(Box::new)));
+        self.decoded_key = decode(&self.cose_key)
+            .map(|bytes| bytes.and_then(|bytes| CoseKey::decode(&bytes).map(Box::new)));
+
+        let message = self.text_message();
+        let message = self
+            .detected
+            .encoding()
+            .decode(&message)
+            .unwrap_or_default();
+
+        self.verified = match (&self.decoded_sign1, &self.decoded_key) {
+            (Some(Ok(sign1)), Some(Ok(key))) => Some(sign1.verify(&key.x, &message)),
+            _ => None,
+        };
+    }
+
+    /// Check the signature with every scheme, the lengths of the key and the
+    /// signature usually rule out all but one.
+    fn update_verdicts(&mut self) {
+        self.verdicts.clear();
+
+        let message = self.text_message();
+        let public_key = self.public_key.text();
+        let signature = self.signature.text();
+
+        if public_key.trim().is_empty() || signature.trim().is_empty() {
+            return;
+        }
+
+        let decoded = self
+            .detected
+            .encoding()
+            .decode(&message)
+            .map_err(|e| format!("Message: {e}"))
+            .and_then(|message| {
+                let decode = |s: &str, what: &str| {
+                    let s = s.trim();
+
+                    likely_encoding(s)
+                        .decode(s)
+                        .map_err(|e| format!("{what}: {e}"))
+                };
+
+                Ok((
+                    message,
+                    decode(&public_key, "Public key")?,
+                    decode(&signature, "Signature")?,
+                ))
+            });
+
+        match decoded {
+            Ok((message, public_key, signature)) => {
+                self.verdicts = Scheme::ALL
+                    .into_iter()
+                    .map(|scheme| (scheme, verify(scheme, &public_key, &message, &signature)))
+                    .collect();
+            }
+            Err(e) => self.warning = Some(e),
         }
     }
 
@@ -63,88 +290,58 @@ impl State {
             return;
         }
 
-        let message = match self.encoding.clone().unwrap_or(detect_encoding(&message)) {
-            Encoding::Hex => match hex::decode(message.replace('\n', "")) {
-                Ok(raw) => raw,
-                Err(e) => {
-                    self.warning = Some(format!("Invalid hex: {}", e));
-                    self.clear_signatures();
-                    return;
-                }
-            },
-            Encoding::Base64 => match BASE64_STANDARD.decode(message.replace('\n', "")) {
-                Ok(raw) => raw,
-                Err(e) => {
-                    self.warning = Some(format!("Invalid base64: {}", e));
-                    self.clear_signatures();
-                    return;
-                }
-            },
-            Encoding::UTF8 => message.into_bytes(),
+        let message = match self.detected.encoding().decode(&message) {
+            Ok(raw) => raw,
+            Err(e) => {
+                self.warning = Some(e);
+                self.clear_signatures();
+                return;
+            }
         };
 
-        let private_key = match self
-            .encoding
-            .clone()
-            .unwrap_or(detect_encoding(&private_key))
-        {
-            Encoding::Hex => match hex::decode(private_key.replace('\n', "")) {
-                Ok(raw) => raw,
-                Err(e) => {
-                    self.warning = Some(format!("Invalid hex: {}", e));
-                    self.clear_signatures();
-                    return;
-                }
-            },
-            Encoding::Base64 => match BASE64_STANDARD.decode(private_key.replace('\n', "")) {
-                Ok(raw) => raw,
-                Err(e) => {
-                    self.warning = Some(format!("Invalid base64: {}", e));
-                    self.clear_signatures();
-                    return;
-                }
-            },
-            Encoding::UTF8 => private_key.into_bytes(),
+        let private_key = match self.key_detected.encoding().decode(&private_key) {
+            Ok(raw) => raw,
+            Err(e) => {
+                self.warning = Some(e);
+                self.clear_signatures();
+                return;
+            }
         };
 
+        self.clear_signatures();
+
+        let mut warnings = vec![];
+
         match sign_with_edd25519(private_key.clone(), message.clone()) {
             Ok((pub_key, sig)) => {
                 self.ed25519_pub = hex::encode(pub_key);
                 self.ed25519_sig = hex::encode(sig);
             }
-            Err(warn) => {
-                self.warning = Some(warn);
-                self.ed25519_pub = "".to_string();
-                self.ed25519_sig = "".to_string();
-                return;
-            }
+            Err(warn) => warnings.push(warn),
         }
 
-        match sign_with_ecdsa_secp256k1(private_key.clone(), message.clone()) {
-            Ok((pub_key, sig)) => {
-                self.ecdsa_secp256k1_pub = hex::encode(pub_key);
-                self.ecdsa_secp256k1_sig = hex::encode(sig);
-            }
-            Err(warn) => {
-                self.warning = Some(warn);
-                self.ecdsa_secp256k1_pub = "".to_string();
-                self.ecdsa_secp256k1_sig = "".to_string();
-                return;
+        // Extended ed25519 keys are longer than any secp256k1 key, only a
+        // 32 byte secret could be meant for either
+        if private_key.len() == secp256k1::constants::SECRET_KEY_SIZE {
+            match sign_with_ecdsa_secp256k1(private_key.clone(), message.clone()) {
+                Ok((pub_key, sig)) => {
+                    self.ecdsa_secp256k1_pub = hex::encode(pub_key);
+                    self.ecdsa_secp256k1_sig = hex::encode(sig);
+                }
+                Err(warn) => warnings.push(warn),
             }
-        }
 
-        match sign_with_schnorr_secp256k1(private_key, message) {
-            Ok((pub_key, sig)) => {
-                self.schnorr_secp256k1_pub = hex::encode(pub_key);
-                self.schnorr_secp256k1_sig = hex::encode(sig);
-            }
-            Err(warn) => {
-                self.warning = Some(warn);
-                self.schnorr_secp256k1_pub = "".to_string();
-                self.schnorr_secp256k1_sig = "".to_string();
-                return;
+            match sign_with_schnorr_secp256k1(private_key, message) {
+                Ok((pub_key, sig)) => {
+                    self.schnorr_secp256k1_pub = hex::encode(pub_key);
+                    self.schnorr_secp256k1_sig = hex::encode(sig);
+                }
+                Err(warn) => warnings.push(warn),
             }
         }
+
+        warnings.dedup();
+        self.warning = (!warnings.is_empty()).then(|| warnings.join(" "));
     }
 
     fn clear_signatures(&mut self) {
@@ -157,7 +354,24 @@ impl State {
     }
 }
 
-fn sign_with_edd25519(
+/// Write each envelope next to the others, named after `path`.
+async fn save(
+    path: String,
+    envelopes: Vec<(&'static str, TextEnvelope)>,
+) -> Result<String, String> {
+    let mut saved = vec![];
+
+    for (extension, envelope) in envelopes {
+        saved.push(envelope.save(format!("{path}{extension}")).await?);
+    }
+
+    Ok(saved.join(", "))
+}
+
+/// An extended signing key as cardano-cli stores it.
+const EXTENDED_KEY_FILE_LENGTH: usize = 128;
+
+pub fn sign_with_edd25519(
     private_key: Vec<u8>,
     message: Vec<u8>,
 ) -> Result<
@@ -167,13 +381,29 @@ fn sign_with_edd25519(
     ),
     String,
 > {
-    let private_key = &private_key
-        .try_into()
-        .map_err(|_| "Invalid private key length..".to_string())?;
+    // Either a 32 byte seed or the 64 byte expanded secret of an extended key,
+    // which cardano-cli follows with the public key and chain code
+    let (public_key, sig) = match private_key.len() {
+        ed25519::EXTENDED_KEY_LENGTH | EXTENDED_KEY_FILE_LENGTH => {
+            let private_key = private_key[..ed25519::EXTENDED_KEY_LENGTH]
+                .try_into()
+                .unwrap();
+
+            (
+                ed25519::extended_to_public(&private_key),
+                ed25519::signature_extended(&message, &private_key),
+            )
+        }
+        _ => {
+            let private_key = &private_key
+                .try_into()
+                .map_err(|_| "Invalid private key length..".to_string())?;
 
-    let (private_key, public_key) = ed25519::keypair(private_key);
+            let (private_key, public_key) = ed25519::keypair(private_key);
 
-    let sig = ed25519::signature(&message, &private_key);
+            (public_key, ed25519::signature(&message, &private_key))
+        }
+    };
 
     let valid = ed25519::verify(&message, &public_key, &sig);
 
@@ -255,3 +485,62 @@ fn sign_with_schnorr_secp256k1(
             "Something went horribly wrong. Sig did not verify with same message.".to_string()
         })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed_with(kind: KeyKind, message: &str) -> State {
+        let mut state = State {
+            message: text_editor::Content::with_text(message),
+            key_kind: kind,
+            ..Default::default()
+        };
+
+        let _ = state.update(Message::GenerateKey);
+        state
+    }
+
+    #[test]
+    fn generated_extended_keys_only_sign_with_ed25519() {
+        let state = signed_with(KeyKind::Ed25519Extended, "48656c6c6f");
+
+        assert_eq!(state.warning, None);
+        assert!(!state.ed25519_sig.is_empty());
+        assert!(state.ecdsa_secp256k1_sig.is_empty());
+        assert!(state.schnorr_secp256k1_sig.is_empty());
+    }
+
+    #[test]
+    fn a_failing_scheme_leaves_the_others_signed() {
+        // Too short for secp256k1, which only signs 32 byte digests
+        let state = signed_with(KeyKind::SchnorrSecp256k1, "48656c6c6f");
+
+        assert!(state.warning.is_some());
+        assert!(!state.ed25519_sig.is_empty());
+
+        let state = signed_with(KeyKind::SchnorrSecp256k1, &"ab".repeat(32));
+
+        assert_eq!(state.warning, None);
+        assert!(!state.ed25519_sig.is_empty());
+        assert!(!state.ecdsa_secp256k1_sig.is_empty());
+        assert!(!state.schnorr_secp256k1_sig.is_empty());
+    }
+
+    #[test]
+    fn the_key_and_the_message_have_their_own_encodings() {
+        let mut state = signed_with(KeyKind::Ed25519, "48656c6c6f");
+        let signature = state.ed25519_sig.clone();
+
+        let _ = state.update(Message::EncodingSet(Encoding::UTF8));
+
+        assert_eq!(state.key_detected.encoding(), &Encoding::Hex);
+        assert_ne!(state.ed25519_sig, signature);
+
+        let _ = state.update(Message::EncodingSet(Encoding::Hex));
+        let _ = state.update(Message::KeyEncodingSet(Encoding::Hex));
+
+        assert_eq!(state.detected.encoding(), &Encoding::Hex);
+        assert_eq!(state.ed25519_sig, signature);
+    }
+}
@@ -0,0 +1,293 @@
+use std::fmt::Display;
+
+use cryptoxide::ed25519;
+use secp256k1::{ecdsa, schnorr, Message, PublicKey, Secp256k1, XOnlyPublicKey};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scheme {
+    Ed25519,
+    EcdsaSecp256k1,
+    SchnorrSecp256k1,
+}
+
+impl Scheme {
+    pub const ALL: [Scheme; 3] = [
+        Scheme::Ed25519,
+        Scheme::EcdsaSecp256k1,
+        Scheme::SchnorrSecp256k1,
+    ];
+}
+
+impl Display for Scheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Scheme::Ed25519 => "Ed25519",
+                Scheme::EcdsaSecp256k1 => "EcdsaSecp256k1",
+                Scheme::SchnorrSecp256k1 => "SchnorrSecp256k1",
+            }
+        )
+    }
+}
+
+/// What the scheme's Plutus builtin would make of the inputs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Valid,
+    /// The builtin returns False, sometimes for a reason worth pointing out
+    Invalid(Option<String>),
+    /// The builtin fails the script rather than returning False
+    Error(String),
+}
+
+pub fn verify(scheme: Scheme, public_key: &[u8], message: &[u8], signature: &[u8]) -> Verdict {
+    let verdict = match scheme {
+        Scheme::Ed25519 => verify_ed25519(public_key, message, signature),
+        Scheme::EcdsaSecp256k1 => verify_ecdsa_secp256k1(public_key, message, signature),
+        Scheme::SchnorrSecp256k1 => verify_schnorr_secp256k1(public_key, message, signature),
+    };
+
+    verdict.unwrap_or_else(Verdict::Error)
+}
+
+fn length<const N: usize>(bytes: &[u8], what: &str) -> Result<[u8; N], String> {
+    bytes
+        .try_into()
+        .map_err(|_| format!("{what} must be {N} bytes, got {}", bytes.len()))
+}
+
+/// `verifyEd25519Signature`: a 32 byte key, a message of any length and a 64
+/// byte signature.
+fn verify_ed25519(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<Verdict, String> {
+    let public_key = length::<{ ed25519::PUBLIC_KEY_LENGTH }>(public_key, "The public key")?;
+    let signature = length::<{ ed25519::SIGNATURE_LENGTH }>(signature, "The signature")?;
+
+    Ok(if ed25519::verify(message, &public_key, &signature) {
+        Verdict::Valid
+    } else {
+        Verdict::Invalid(None)
+    })
+}
+
+/// `verifyEcdsaSecp256k1Signature`: a 33 byte compressed key, a 32 byte
+/// message hash and a 64 byte compact signature in low-s form.
+fn verify_ecdsa_secp256k1(
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<Verdict, String> {
+    if public_key.len() == secp256k1::constants::UNCOMPRESSED_PUBLIC_KEY_SIZE {
+        return Err(format!(
+            "The public key must be {} bytes in compressed form, this one is uncompressed",
+            secp256k1::constants::PUBLIC_KEY_SIZE
+        ));
+    }
+
+    let public_key =
+        length::<{ secp256k1::constants::PUBLIC_KEY_SIZE }>(public_key, "The public key")?;
+    let message = length::<32>(message, "The message, a hash that isn't hashed again,")?;
+    let signature =
+        length::<{ secp256k1::constants::COMPACT_SIGNATURE_SIZE }>(signature, "The signature")?;
+
+    let public_key = PublicKey::from_byte_array_compressed(&public_key)
+        .map_err(|_| "The public key isn't a point on the curve".to_string())?;
+
+    let signature = ecdsa::Signature::from_compact(&signature)
+        .map_err(|_| "The signature's r or s is out of range".to_string())?;
+
+    let message = Message::from_digest(message);
+    let secp = Secp256k1::verification_only();
+
+    if secp.verify_ecdsa(&message, &signature, &public_key).is_ok() {
+        return Ok(Verdict::Valid);
+    }
+
+    // The builtin only accepts the low-s half of the otherwise equally valid
+    // signatures
+    let mut normalized = signature;
+    normalized.normalize_s();
+
+    let high_s = normalized != signature
+        && secp
+            .verify_ecdsa(&message, &normalized, &public_key)
+            .is_ok();
+
+    Ok(Verdict::Invalid(high_s.then(|| {
+        format!(
+            "the signature is in high-s form, its low-s form {} would be valid",
+            hex::encode(normalized.serialize_compact())
+        )
+    })))
+}
+
+/// `verifySchnorrSecp256k1Signature`: a 32 byte x-only key, a message of any
+/// length and a 64 byte BIP-340 signature.
+fn verify_schnorr_secp256k1(
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> Result<Verdict, String> {
+    let public_key = length::<{ secp256k1::constants::SCHNORR_PUBLIC_KEY_SIZE }>(
+        public_key,
+        "The public key, x-only as in BIP-340,",
+    )?;
+    let signature =
+        length::<{ secp256k1::constants::SCHNORR_SIGNATURE_SIZE }>(signature, "The signature")?;
+
+    let public_key = XOnlyPublicKey::from_byte_array(&public_key)
+        .map_err(|_| "The public key isn't a point on the curve".to_string())?;
+
+    let signature = schnorr::Signature::from_byte_array(signature);
+
+    Ok(
+        match Secp256k1::verification_only().verify_schnorr(&signature, message, &public_key) {
+            Ok(()) => Verdict::Valid,
+            Err(_) => Verdict::Invalid(None),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use secp256k1::{Keypair, SecretKey};
+
+    use super::*;
+
+    const SECRET: [u8; 32] = [7; 32];
+    const MESSAGE: [u8; 32] = [42; 32];
+
+    /// The order of the secp256k1 group.
+    const ORDER: [u8; 32] = [
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36,
+        0x41, 0x41,
+    ];
+
+    fn ecdsa() -> ([u8; 33], [u8; 64]) {
+        let secp = Secp256k1::new();
+        let secret = SecretKey::from_byte_array(&SECRET).unwrap();
+        let signature = secp.sign_ecdsa(&Message::from_digest(MESSAGE), &secret);
+
+        (
+            secret.public_key(&secp).serialize(),
+            signature.serialize_compact(),
+        )
+    }
+
+    /// The other, high-s, signature for the same message: `s` becomes
+    /// `order - s`.
+    fn high_s(signature: [u8; 64]) -> [u8; 64] {
+        let mut high = signature;
+        let mut borrow = 0;
+
+        for i in (0..32).rev() {
+            let difference = i16::from(ORDER[i]) - i16::from(signature[32 + i]) - borrow;
+
+            high[32 + i] = difference.rem_euclid(256) as u8;
+            borrow = i16::from(difference < 0);
+        }
+
+        high
+    }
+
+    #[test]
+    fn ed25519_verdicts() {
+        let (secret, public) = ed25519::keypair(&SECRET);
+        let signature = ed25519::signature(b"hello", &secret);
+
+        assert_eq!(
+            verify(Scheme::Ed25519, &public, b"hello", &signature),
+            Verdict::Valid
+        );
+        assert_eq!(
+            verify(Scheme::Ed25519, &public, b"hullo", &signature),
+            Verdict::Invalid(None)
+        );
+        assert_eq!(
+            verify(Scheme::Ed25519, &public[..31], b"hello", &signature),
+            Verdict::Error("The public key must be 32 bytes, got 31".to_string())
+        );
+    }
+
+    #[test]
+    fn ecdsa_verdicts() {
+        let (public, signature) = ecdsa();
+
+        assert_eq!(
+            verify(Scheme::EcdsaSecp256k1, &public, &MESSAGE, &signature),
+            Verdict::Valid
+        );
+        assert_eq!(
+            verify(Scheme::EcdsaSecp256k1, &public, &[0; 32], &signature),
+            Verdict::Invalid(None)
+        );
+        assert!(matches!(
+            verify(Scheme::EcdsaSecp256k1, &public, b"not a hash", &signature),
+            Verdict::Error(e) if e.starts_with("The message, a hash that isn't hashed again,")
+        ));
+
+        let uncompressed = PublicKey::from_slice(&public)
+            .unwrap()
+            .serialize_uncompressed();
+
+        assert!(matches!(
+            verify(Scheme::EcdsaSecp256k1, &uncompressed, &MESSAGE, &signature),
+            Verdict::Error(e) if e.ends_with("this one is uncompressed")
+        ));
+    }
+
+    #[test]
+    fn high_s_signatures_are_invalid_but_pointed_out() {
+        let (public, signature) = ecdsa();
+
+        assert_eq!(
+            verify(
+                Scheme::EcdsaSecp256k1,
+                &public,
+                &MESSAGE,
+                &high_s(signature)
+            ),
+            Verdict::Invalid(Some(format!(
+                "the signature is in high-s form, its low-s form {} would be valid",
+                hex::encode(signature)
+            )))
+        );
+    }
+
+    #[test]
+    fn schnorr_verdicts() {
+        let secp = Secp256k1::new();
+        let keypair = Keypair::from_seckey_slice(&secp, &SECRET).unwrap();
+        let (public, _) = keypair.x_only_public_key();
+        let signature = secp.sign_schnorr_no_aux_rand(b"any length", &keypair);
+
+        assert_eq!(
+            verify(
+                Scheme::SchnorrSecp256k1,
+                &public.serialize(),
+                b"any length",
+                signature.as_ref()
+            ),
+            Verdict::Valid
+        );
+        assert_eq!(
+            verify(
+                Scheme::SchnorrSecp256k1,
+                &public.serialize(),
+                b"another one",
+                signature.as_ref()
+            ),
+            Verdict::Invalid(None)
+        );
+
+        // A compressed key has the parity byte x-only keys leave out
+        let (compressed, _) = ecdsa();
+
+        assert!(matches!(
+            verify(Scheme::SchnorrSecp256k1, &compressed, b"any length", signature.as_ref()),
+            Verdict::Error(e) if e.starts_with("The public key, x-only as in BIP-340,")
+        ));
+    }
+}
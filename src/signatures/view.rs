@@ -1,33 +1,27 @@
 use iced::{
-    widget::{button, column, pick_list, row, text, text_editor, Space},
-    Element,
-    Length::{Fill, FillPortion, Shrink},
+    widget::{
+        button, checkbox, column, pick_list, row, scrollable, text, text_editor, text_input, Column,
+    },
+    Color, Element,
+    Length::{Fill, FillPortion},
     Theme,
 };
 
-use crate::{
-    copyable_text::copyable_text,
-    encoding::{detect_encoding, Encoding},
-};
+use pallas::ledger::addresses::Address;
+
+use crate::{copyable_text::copyable_text, encoding::encoding_picker};
+
+use super::{algorithm_name, key_role, KeyKind, Message, Mode, State, Verdict};
 
-use super::{Message, State};
+const RED: Color = Color::from_rgb(1.0, 0.0, 0.0);
+const GREEN: Color = Color::from_rgb(0.0, 0.7, 0.0);
 
 impl State {
-    pub fn view(&self) -> Element<Message> {
-        let message = self.text_message();
-        let key = self.text_key();
+    pub fn view(&self) -> Element<'_, Message> {
         row![
             column![
-                row![
-                    "Encoding?",
-                    Space::new(10, Fill),
-                    pick_list(
-                        &Encoding::ALL[..],
-                        Some(self.encoding.clone().unwrap_or(detect_encoding(message))),
-                        Message::EncodingSet,
-                    )
-                ]
-                .height(Shrink),
+                pick_list(&Mode::ALL[..], Some(self.mode), Message::ModeSet),
+                encoding_picker(&self.detected, Message::EncodingSet),
                 text_editor(&self.message)
                     .placeholder("paste your message...")
                     .on_action(Message::ContentsChanged)
@@ -40,72 +34,349 @@ impl State {
 
                         defaults
                     }),
-                row![
-                    "Encoding?",
-                    Space::new(10, Fill),
-                    pick_list(
-                        &Encoding::ALL[..],
-                        Some(self.encoding.clone().unwrap_or(detect_encoding(key))),
-                        Message::EncodingSet,
-                    )
-                ]
-                .height(Shrink),
-                text_editor(&self.private_key)
-                    .placeholder("paste your key...")
-                    .on_action(Message::KeyChanged)
-                    .height(Fill)
-                    .wrapping(text::Wrapping::Glyph)
-                    .style(|theme: &Theme, status| {
-                        let mut defaults = text_editor::default(theme, status);
+            ]
+            .push(match self.mode {
+                Mode::Sign => self.view_key(),
+                Mode::Verify => self.view_verify_inputs(),
+                Mode::Cose => self.view_key().push(
+                    column![
+                        text_input("address for the protected headers", &self.address)
+                            .on_input(Message::AddressChanged),
+                        checkbox("sign the payload's blake2b-224 hash", self.hashed)
+                            .on_toggle(Message::HashedToggled),
+                    ]
+                    .spacing(5)
+                ),
+            })
+            .spacing(5)
+            .width(FillPortion(1))
+            .padding(5),
+            match self.mode {
+                Mode::Sign => self.view_signatures(),
+                Mode::Verify => self.view_verdicts(),
+                Mode::Cose => self.view_cose(),
+            }
+            .padding(5)
+            .width(FillPortion(3))
+        ]
+        .into()
+    }
 
-                        defaults.border.radius = 4.0.into();
+    fn view_signatures(&self) -> Column<'_, Message> {
+        column![
+            row![
+                "Ed25519 Pub: ",
+                copyable_text(&self.ed25519_pub, Message::CopyText)
+            ]
+            .wrap(),
+            row![
+                "Ed25519 Sig: ",
+                copyable_text(&self.ed25519_sig, Message::CopyText)
+            ]
+            .wrap(),
+            row![
+                "EcdsaSecp256k1 Pub: ",
+                copyable_text(&self.ecdsa_secp256k1_pub, Message::CopyText)
+            ]
+            .wrap(),
+            row![
+                "EcdsaSecp256k1 Sig: ",
+                copyable_text(&self.ecdsa_secp256k1_sig, Message::CopyText)
+            ]
+            .wrap(),
+            row![
+                "SchnorrSecp256k1 Pub: ",
+                copyable_text(&self.schnorr_secp256k1_pub, Message::CopyText)
+            ]
+            .wrap(),
+            row![
+                "SchnorrSecp256k1 Sig: ",
+                copyable_text(&self.schnorr_secp256k1_sig, Message::CopyText)
+            ]
+            .wrap(),
+        ]
+        .push_maybe(
+            self.warning
+                .as_ref()
+                .map(|warning| text(warning).color([1.0, 0.0, 0.0])),
+        )
+        .push_maybe(self.view_generated())
+    }
 
-                        defaults
-                    }),
+    fn view_generated(&self) -> Option<Element<'_, Message>> {
+        let generated = self.generated.as_ref()?;
+
+        let envelopes = generated
+            .envelopes
+            .iter()
+            .map(|(extension, envelope)| (*extension, envelope.to_json()));
+
+        let save = (!generated.envelopes.is_empty()).then(|| {
+            column![row![
+                text_input("path to save to, without .skey/.vkey", &self.key_path)
+                    .on_input(Message::KeyPathChanged)
+                    .on_submit(Message::SaveKey),
+                button("Save as TextEnvelope")
+                    .on_press_maybe((!self.key_path.trim().is_empty()).then_some(Message::SaveKey)),
+            ]
+            .spacing(5)]
+            .push_maybe(self.saved.as_ref().map(|saved| match saved {
+                Ok(paths) => text(format!("saved {paths}")).color(GREEN),
+                Err(e) => text(e).color(RED),
+            }))
+            .spacing(5)
+        });
+
+        Some(
+            scrollable(
+                column(
+                    generated
+                        .encodings
+                        .iter()
+                        .map(|(name, value)| (*name, value.clone()))
+                        .chain(envelopes)
+                        .map(|(name, value)| {
+                            row![
+                                text(format!("{} {name}: ", generated.kind)),
+                                copyable_text(&value, Message::CopyText)
+                            ]
+                            .wrap()
+                            .into()
+                        }),
+                )
+                .push_maybe(save)
+                .spacing(5),
+            )
+            .into(),
+        )
+    }
+
+    fn view_key(&self) -> Column<'_, Message> {
+        column![
+            encoding_picker(&self.key_detected, Message::KeyEncodingSet),
+            text_editor(&self.private_key)
+                .placeholder("paste your key...")
+                .on_action(Message::KeyChanged)
+                .height(Fill)
+                .wrapping(text::Wrapping::Glyph)
+                .style(|theme: &Theme, status| {
+                    let mut defaults = text_editor::default(theme, status);
+
+                    defaults.border.radius = 4.0.into();
+
+                    defaults
+                }),
+            row![
+                pick_list(&KeyKind::ALL[..], Some(self.key_kind), Message::KeyKindSet),
                 button("Generate Key").on_press(Message::GenerateKey),
             ]
-            .width(FillPortion(1))
-            .padding(5),
+            .spacing(5),
+        ]
+        .spacing(5)
+        .height(Fill)
+    }
+
+    fn view_verify_inputs(&self) -> Column<'_, Message> {
+        let editor = |content, placeholder, on_action: fn(text_editor::Action) -> Message| {
+            text_editor(content)
+                .placeholder(placeholder)
+                .on_action(on_action)
+                .height(Fill)
+                .wrapping(text::Wrapping::Glyph)
+                .style(|theme: &Theme, status| {
+                    let mut defaults = text_editor::default(theme, status);
+
+                    defaults.border.radius = 4.0.into();
+
+                    defaults
+                })
+        };
+
+        column![
+            editor(
+                &self.public_key,
+                "paste the public key...",
+                Message::PublicKeyChanged
+            ),
+            editor(
+                &self.signature,
+                "paste the signature...",
+                Message::SignatureChanged
+            ),
+        ]
+        .spacing(5)
+        .height(Fill)
+    }
+
+    fn view_verdicts(&self) -> Column<'_, Message> {
+        column(self.verdicts.iter().map(|(scheme, verdict)| {
+            let verdict = match verdict {
+                Verdict::Valid => text("valid").color(GREEN),
+                Verdict::Invalid(None) => text("invalid").color(RED),
+                Verdict::Invalid(Some(reason)) => text(format!("invalid: {reason}")).color(RED),
+                Verdict::Error(e) => text(format!("the builtin would fail: {e}")).color(RED),
+            };
+
+            row![text(format!("{scheme}: ")).width(200), verdict].into()
+        }))
+        .push_maybe(
+            self.warning
+                .as_ref()
+                .map(|warning| text(warning).color(RED)),
+        )
+        .spacing(10)
+    }
+
+    fn view_cose(&self) -> Column<'_, Message> {
+        let editor = |content, placeholder, on_action: fn(text_editor::Action) -> Message| {
+            text_editor(content)
+                .placeholder(placeholder)
+                .on_action(on_action)
+                .height(120)
+                .wrapping(text::Wrapping::Glyph)
+                .style(|theme: &Theme, status| {
+                    let mut defaults = text_editor::default(theme, status);
+
+                    defaults.border.radius = 4.0.into();
+
+                    defaults
+                })
+        };
+
+        let field = |label: &str, value: Element<'static, Message>| {
+            row![text(label.to_string()).width(160), value].into()
+        };
+
+        let signed = self.signed.as_ref().map(|(sign1, key)| {
             column![
-                row![
-                    "Ed25519 Pub: ",
-                    copyable_text(&self.ed25519_pub, Message::CopyText)
-                ]
-                .wrap(),
-                row![
-                    "Ed25519 Sig: ",
-                    copyable_text(&self.ed25519_sig, Message::CopyText)
-                ]
-                .wrap(),
-                row![
-                    "EcdsaSecp256k1 Pub: ",
-                    copyable_text(&self.ecdsa_secp256k1_pub, Message::CopyText)
-                ]
-                .wrap(),
-                row![
-                    "EcdsaSecp256k1 Sig: ",
-                    copyable_text(&self.ecdsa_secp256k1_sig, Message::CopyText)
-                ]
-                .wrap(),
-                row![
-                    "SchnorrSecp256k1 Pub: ",
-                    copyable_text(&self.schnorr_secp256k1_pub, Message::CopyText)
-                ]
-                .wrap(),
-                row![
-                    "SchnorrSecp256k1 Sig: ",
-                    copyable_text(&self.schnorr_secp256k1_sig, Message::CopyText)
-                ]
-                .wrap(),
+                row!["COSE_Sign1: ", copyable_text(sign1, Message::CopyText)].wrap(),
+                row!["COSE_Key: ", copyable_text(key, Message::CopyText)].wrap(),
             ]
+            .spacing(5)
+        });
+
+        let mut decoded: Vec<Element<'_, Message>> = vec![];
+
+        match &self.decoded_sign1 {
+            Some(Ok(sign1)) => {
+                let payload = match &sign1.payload {
+                    None => text("detached"),
+                    Some(payload) if sign1.hashed => {
+                        text(format!("blake2b-224 {}", hex::encode(payload)))
+                    }
+                    Some(payload) => match std::str::from_utf8(payload) {
+                        Ok(payload) => text(payload.to_string()),
+                        Err(_) => text(hex::encode(payload)),
+                    },
+                };
+
+                decoded.extend([
+                    field(
+                        "alg",
+                        text(sign1.algorithm.map_or("none".to_string(), algorithm_name)).into(),
+                    ),
+                    field(
+                        "address",
+                        text(
+                            sign1
+                                .address
+                                .as_ref()
+                                .map_or("none".to_string(), |address| {
+                                    Address::from_bytes(address)
+                                        .map(|address| address.to_string())
+                                        .unwrap_or_else(|_| hex::encode(address))
+                                }),
+                        )
+                        .into(),
+                    ),
+                ]);
+
+                if let Some(key_id) = &sign1.key_id {
+                    decoded.push(field("kid", text(hex::encode(key_id)).into()));
+                }
+
+                decoded.extend([
+                    field("hashed", text(sign1.hashed.to_string()).into()),
+                    field("payload", payload.into()),
+                    field("signature", text(hex::encode(&sign1.signature)).into()),
+                ]);
+            }
+            Some(Err(e)) => decoded.push(text(format!("COSE_Sign1: {e}")).color(RED).into()),
+            None => {}
+        }
+
+        match &self.decoded_key {
+            Some(Ok(key)) => {
+                let int = |value: Option<i64>| value.map_or("none".to_string(), |v| v.to_string());
+
+                decoded.extend([
+                    field("public key", text(hex::encode(&key.x)).into()),
+                    field(
+                        "kty / crv / alg",
+                        text(format!(
+                            "{} / {} / {}",
+                            int(key.key_type),
+                            int(key.curve),
+                            key.algorithm.map_or("none".to_string(), algorithm_name)
+                        ))
+                        .into(),
+                    ),
+                ]);
+
+                if let Some(key_id) = &key.key_id {
+                    decoded.push(field("key kid", text(hex::encode(key_id)).into()));
+                }
+            }
+            Some(Err(e)) => decoded.push(text(format!("COSE_Key: {e}")).color(RED).into()),
+            None => {}
+        }
+
+        match &self.verified {
+            Some(Ok(true)) => decoded.push(field("signature", text("valid").color(GREEN).into())),
+            Some(Ok(false)) => decoded.push(field("signature", text("invalid").color(RED).into())),
+            Some(Err(e)) => decoded.push(field("signature", text(e.to_string()).color(RED).into())),
+            None => {}
+        }
+
+        // Wallets sign with the key behind the address they were asked for,
+        // anything else is suspect however valid the signature
+        if let (Some(Ok(sign1)), Some(Ok(key))) = (&self.decoded_sign1, &self.decoded_key) {
+            if let Some(address) = &sign1.address {
+                decoded.push(field(
+                    "key",
+                    match key_role(address, &key.x) {
+                        Some(role) => text(format!("the address's {role} key")).color(GREEN),
+                        None => text("doesn't belong to the address").color(RED),
+                    }
+                    .into(),
+                ));
+            }
+        }
+
+        column![]
+            .push_maybe(self.view_generated())
+            .push_maybe(signed)
             .push_maybe(
                 self.warning
                     .as_ref()
-                    .map(|warning| text(warning).color([1.0, 0.0, 0.0]))
+                    .map(|warning| text(warning).color(RED)),
             )
-            .padding(5)
-            .width(FillPortion(3))
-        ]
-        .into()
+            .push(
+                row![
+                    editor(
+                        &self.sign1,
+                        "paste a COSE_Sign1 to decode...",
+                        Message::Sign1Changed
+                    ),
+                    editor(
+                        &self.cose_key,
+                        "paste its COSE_Key or public key...",
+                        Message::CoseKeyChanged
+                    ),
+                ]
+                .spacing(5),
+            )
+            .push(scrollable(column(decoded).spacing(5)).height(Fill))
+            .spacing(10)
     }
 }
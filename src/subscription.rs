@@ -4,6 +4,9 @@ use super::{Message, State};
 
 impl State {
     pub fn subscription(&self) -> Subscription<Message> {
-        window::resize_events().map(|(_id, size)| Message::WindowResized(size))
+        Subscription::batch([
+            window::resize_events().map(|(_id, size)| Message::WindowResized(size)),
+            self.workspace.subscription().map(Message::Workspace),
+        ])
     }
 }
@@ -1,16 +1,24 @@
+use std::sync::Arc;
+
 use iced::{
     widget::{button, column, container, pane_grid, row, text, PaneGrid, Space},
     Center, Color, Element,
     Length::Fill,
-    Task, Theme,
+    Subscription, Task, Theme,
 };
 
-use crate::{block_inspector, hashes, signatures};
+use crate::{
+    address_inspector, block_inspector, blueprint, cbor_explorer, chain_sync, hashes, hd_keys,
+    mempool, native_script, plutus_data, script_address, signatures, tx_builder, tx_diff,
+    uplc_debugger,
+};
 use iced_font_awesome::fa_icon_solid;
 
 pub struct State {
     focus: Option<pane_grid::Pane>,
     panes: pane_grid::State<Pane>,
+    /// The last blueprint loaded, shared with every tool that shows data
+    blueprint: Option<Arc<blueprint::Blueprint>>,
 }
 
 impl Default for State {
@@ -19,6 +27,7 @@ impl Default for State {
         Self {
             focus: Some(focus),
             panes,
+            blueprint: None,
         }
     }
 }
@@ -40,9 +49,21 @@ impl Pane {
 #[derive(Debug)]
 pub enum Tool {
     Select,
-    BlockInspector(block_inspector::State),
+    BlockInspector(Box<block_inspector::State>),
+    AddressInspector(address_inspector::State),
+    CborExplorer(cbor_explorer::State),
+    PlutusData(plutus_data::State),
+    Uplc(uplc_debugger::State),
+    Blueprint(blueprint::State),
+    ScriptAddress(script_address::State),
+    NativeScript(native_script::State),
+    TxBuilder(tx_builder::State),
+    TxDiff(tx_diff::State),
+    ChainSync(chain_sync::State),
+    Mempool(mempool::State),
     Hashes(hashes::State),
-    Signatures(signatures::State),
+    Signatures(Box<signatures::State>),
+    HdKeys(hd_keys::State),
 }
 
 impl Tool {
@@ -50,8 +71,29 @@ impl Tool {
         match self {
             Tool::Select => "Select",
             Tool::BlockInspector(_) => "Block Inspector",
+            Tool::AddressInspector(_) => "Address Inspector",
+            Tool::CborExplorer(_) => "CBOR Explorer",
+            Tool::PlutusData(_) => "Plutus Data",
+            Tool::Uplc(_) => "UPLC",
+            Tool::Blueprint(_) => "Blueprint",
+            Tool::ScriptAddress(_) => "Script Address",
+            Tool::NativeScript(_) => "Native Script",
+            Tool::TxBuilder(_) => "Tx Builder",
+            Tool::TxDiff(_) => "Tx Diff",
+            Tool::ChainSync(_) => "Chain Sync",
+            Tool::Mempool(_) => "Mempool",
             Tool::Hashes(_) => "Hashes",
             Tool::Signatures(_) => "Signatures",
+            Tool::HdKeys(_) => "HD Keys",
+        }
+    }
+
+    /// Hand the tools that show plutus data the blueprint to name it with.
+    fn set_blueprint(&mut self, blueprint: Option<Arc<blueprint::Blueprint>>) {
+        match self {
+            Tool::BlockInspector(state) => state.set_blueprint(blueprint),
+            Tool::PlutusData(state) => state.set_blueprint(blueprint),
+            _ => {}
         }
     }
 }
@@ -60,8 +102,20 @@ impl Tool {
 pub enum ToolMessage {
     SelectTool(fn() -> Tool),
     BlockInspector(block_inspector::Message),
+    AddressInspector(address_inspector::Message),
+    CborExplorer(cbor_explorer::Message),
+    PlutusData(plutus_data::Message),
+    Uplc(uplc_debugger::Message),
+    Blueprint(blueprint::Message),
+    ScriptAddress(script_address::Message),
+    NativeScript(native_script::Message),
+    TxBuilder(tx_builder::Message),
+    TxDiff(tx_diff::Message),
+    ChainSync(chain_sync::Message),
+    Mempool(mempool::Message),
     Hashes(hashes::Message),
     Signatures(signatures::Message),
+    HdKeys(hd_keys::Message),
 }
 
 #[expect(dead_code)] // The remaining things can be bound to hotkeys eventually
@@ -145,6 +199,25 @@ impl State {
                     }
                 }
             }
+            Dispatch(pane, ToolMessage::Mempool(mempool::Message::Open(hash))) => {
+                let cbor = match self.panes.get(pane).map(|pane| &pane.content) {
+                    Some(Tool::Mempool(state)) => state.tx(&hash).map(|tx| tx.cbor.clone()),
+                    _ => None,
+                };
+
+                if let Some(cbor) = cbor {
+                    self.open_beside(
+                        pane,
+                        Tool::BlockInspector(block_inspector::State::from_cbor(&cbor).into()),
+                    );
+                }
+            }
+            Dispatch(pane, ToolMessage::TxBuilder(tx_builder::Message::Open(cbor))) => {
+                self.open_beside(
+                    pane,
+                    Tool::BlockInspector(block_inspector::State::from_cbor(&cbor).into()),
+                );
+            }
             Dispatch(pane, message) => {
                 let pane_state = self.panes.panes.get_mut(&pane);
                 if pane_state.is_none() {
@@ -155,10 +228,55 @@ impl State {
                 match (&mut pane_state.content, message) {
                     (Tool::Select, SelectTool(content_fn)) => {
                         pane_state.content = content_fn();
+                        pane_state.content.set_blueprint(self.blueprint.clone());
                     }
                     (Tool::BlockInspector(state), BlockInspector(message)) => {
                         return state.update(message).map(dispatch(pane, BlockInspector));
                     }
+                    (Tool::AddressInspector(state), AddressInspector(message)) => {
+                        return state.update(message).map(dispatch(pane, AddressInspector));
+                    }
+                    (Tool::CborExplorer(state), CborExplorer(message)) => {
+                        return state.update(message).map(dispatch(pane, CborExplorer));
+                    }
+                    (Tool::PlutusData(state), PlutusData(message)) => {
+                        return state.update(message).map(dispatch(pane, PlutusData));
+                    }
+                    (Tool::Uplc(state), Uplc(message)) => {
+                        return state.update(message).map(dispatch(pane, Uplc));
+                    }
+                    (Tool::Blueprint(state), Blueprint(message)) => {
+                        let loaded = matches!(message, blueprint::Message::Loaded(Ok(_)));
+                        let task = state.update(message).map(dispatch(pane, Blueprint));
+
+                        if loaded {
+                            self.blueprint = state.blueprint.clone().map(Arc::new);
+
+                            for (_, pane) in self.panes.iter_mut() {
+                                pane.content.set_blueprint(self.blueprint.clone());
+                            }
+                        }
+
+                        return task;
+                    }
+                    (Tool::ScriptAddress(state), ScriptAddress(message)) => {
+                        return state.update(message).map(dispatch(pane, ScriptAddress));
+                    }
+                    (Tool::NativeScript(state), NativeScript(message)) => {
+                        return state.update(message).map(dispatch(pane, NativeScript));
+                    }
+                    (Tool::TxBuilder(state), TxBuilder(message)) => {
+                        return state.update(message).map(dispatch(pane, TxBuilder));
+                    }
+                    (Tool::TxDiff(state), TxDiff(message)) => {
+                        return state.update(message).map(dispatch(pane, TxDiff));
+                    }
+                    (Tool::ChainSync(state), ChainSync(message)) => {
+                        return state.update(message).map(dispatch(pane, ChainSync));
+                    }
+                    (Tool::Mempool(state), Mempool(message)) => {
+                        return state.update(message).map(dispatch(pane, Mempool));
+                    }
                     (Tool::Hashes(state), Hashes(m)) => {
                         return state.update(m).map(dispatch(pane, Hashes));
                     }
@@ -166,6 +284,9 @@ impl State {
                     (Tool::Signatures(state), Signatures(message)) => {
                         return state.update(message).map(dispatch(pane, Signatures));
                     }
+                    (Tool::HdKeys(state), HdKeys(message)) => {
+                        return state.update(message).map(dispatch(pane, HdKeys));
+                    }
                     _ => {}
                 }
             }
@@ -173,7 +294,38 @@ impl State {
         Task::none()
     }
 
-    pub fn view(&self) -> Element<Message> {
+    /// Open `tool` in a new pane next to `pane`, for tools handing their
+    /// results over to another one.
+    fn open_beside(&mut self, pane: pane_grid::Pane, mut tool: Tool) {
+        tool.set_blueprint(self.blueprint.clone());
+
+        if let Some((pane, _)) = self
+            .panes
+            .split(pane_grid::Axis::Vertical, pane, Pane::new(tool))
+        {
+            self.focus = Some(pane);
+        }
+    }
+
+    /// Live tools keep streaming while their pane is open, wherever it is.
+    pub fn subscription(&self) -> Subscription<Message> {
+        Subscription::batch(self.panes.iter().map(|(pane, state)| {
+            match &state.content {
+                Tool::ChainSync(state) => {
+                    state.subscription().with(*pane).map(|(pane, message)| {
+                        Message::Dispatch(pane, ToolMessage::ChainSync(message))
+                    })
+                }
+                Tool::Mempool(state) => state
+                    .subscription()
+                    .with(*pane)
+                    .map(|(pane, message)| Message::Dispatch(pane, ToolMessage::Mempool(message))),
+                _ => Subscription::none(),
+            }
+        }))
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
         let total_panes = self.panes.len();
 
         let pane_grid = PaneGrid::new(&self.panes, |id, pane, is_maximized| {
@@ -263,24 +415,78 @@ fn view_content(id: pane_grid::Pane, tool: &Tool) -> Element<'_, Message> {
     };
     use ToolMessage::*;
     match tool {
-        Tool::Select => container(row![
-            tool_button("cube", "Block Inspector", || Tool::BlockInspector(
-                block_inspector::State::default()
-            )),
-            tool_button("hashtag", "Hashes", || Tool::Hashes(
-                hashes::State::default()
-            )),
-            tool_button("sign", "Signatures", || Tool::Signatures(
-                signatures::State::default()
-            ))
-        ])
+        Tool::Select => container(
+            row![
+                tool_button("cube", "Block Inspector", || Tool::BlockInspector(
+                    block_inspector::State::default().into()
+                )),
+                tool_button("address-card", "Address Inspector", || {
+                    Tool::AddressInspector(address_inspector::State::default())
+                }),
+                tool_button("code", "CBOR Explorer", || Tool::CborExplorer(
+                    cbor_explorer::State::default()
+                )),
+                tool_button("database", "Plutus Data", || Tool::PlutusData(
+                    plutus_data::State::default()
+                )),
+                tool_button(
+                    "bug",
+                    "UPLC",
+                    || Tool::Uplc(uplc_debugger::State::default())
+                ),
+                tool_button("file-code", "Blueprint", || Tool::Blueprint(
+                    blueprint::State::default()
+                )),
+                tool_button("location-dot", "Script Address", || Tool::ScriptAddress(
+                    script_address::State::default()
+                )),
+                tool_button("key", "Native Script", || Tool::NativeScript(
+                    native_script::State::default()
+                )),
+                tool_button("hammer", "Tx Builder", || Tool::TxBuilder(
+                    tx_builder::State::default()
+                )),
+                tool_button("code-compare", "Tx Diff", || Tool::TxDiff(
+                    tx_diff::State::default()
+                )),
+                tool_button("link", "Chain Sync", || Tool::ChainSync(
+                    chain_sync::State::default()
+                )),
+                tool_button("hourglass-half", "Mempool", || Tool::Mempool(
+                    mempool::State::default()
+                )),
+                tool_button("hashtag", "Hashes", || Tool::Hashes(
+                    hashes::State::default()
+                )),
+                tool_button("sign", "Signatures", || Tool::Signatures(
+                    signatures::State::default().into()
+                )),
+                tool_button("sitemap", "HD Keys", || Tool::HdKeys(
+                    hd_keys::State::default()
+                ))
+            ]
+            // There are more tools than fit in a narrow pane
+            .wrap(),
+        )
         .center(Fill)
         .width(Fill)
         .height(Fill)
         .into(),
         Tool::BlockInspector(state) => state.view().map(dispatch(id, BlockInspector)),
+        Tool::AddressInspector(state) => state.view().map(dispatch(id, AddressInspector)),
+        Tool::CborExplorer(state) => state.view().map(dispatch(id, CborExplorer)),
+        Tool::PlutusData(state) => state.view().map(dispatch(id, PlutusData)),
+        Tool::Uplc(state) => state.view().map(dispatch(id, Uplc)),
+        Tool::Blueprint(state) => state.view().map(dispatch(id, Blueprint)),
+        Tool::ScriptAddress(state) => state.view().map(dispatch(id, ScriptAddress)),
+        Tool::NativeScript(state) => state.view().map(dispatch(id, NativeScript)),
+        Tool::TxBuilder(state) => state.view().map(dispatch(id, TxBuilder)),
+        Tool::TxDiff(state) => state.view().map(dispatch(id, TxDiff)),
+        Tool::ChainSync(state) => state.view().map(dispatch(id, ChainSync)),
+        Tool::Mempool(state) => state.view().map(dispatch(id, Mempool)),
         Tool::Hashes(state) => state.view().map(dispatch(id, Hashes)),
         Tool::Signatures(state) => state.view().map(dispatch(id, Signatures)),
+        Tool::HdKeys(state) => state.view().map(dispatch(id, HdKeys)),
     }
 }
 
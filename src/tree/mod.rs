@@ -0,0 +1,145 @@
+use std::collections::HashSet;
+
+use iced::{
+    font,
+    widget::{button, column, row, text, Space},
+    Element, Font,
+};
+use iced_font_awesome::fa_icon_solid;
+
+/// A labelled node in a collapsible tree.
+///
+/// Nodes are plain data so that tools can build them once after decoding and
+/// render (or compare) them as often as they like.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Node {
+    pub label: String,
+    pub value: Option<String>,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            value: None,
+            children: vec![],
+        }
+    }
+
+    pub fn leaf(label: impl Into<String>, value: impl ToString) -> Self {
+        Self::new(label).value(value)
+    }
+
+    pub fn branch(label: impl Into<String>, children: impl IntoIterator<Item = Node>) -> Self {
+        Self::new(label).children(children)
+    }
+
+    /// A branch that also shows how many children it has.
+    pub fn list(label: impl Into<String>, children: impl IntoIterator<Item = Node>) -> Self {
+        let node = Self::branch(label, children);
+        let len = node.children.len();
+
+        node.value(format!("[{len}]"))
+    }
+
+    pub fn value(mut self, value: impl ToString) -> Self {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    pub fn children(mut self, children: impl IntoIterator<Item = Node>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    pub fn push(mut self, child: Node) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn push_maybe(mut self, child: Option<Node>) -> Self {
+        self.children.extend(child);
+        self
+    }
+}
+
+/// Path of child indexes from the root, used to remember which nodes the user
+/// collapsed.
+pub type Path = Vec<usize>;
+
+/// Only the nodes that aren't collapsed away are looked at, so a big tree
+/// kept in the state renders without being copied.
+pub fn tree<'a, Message>(
+    root: &Node,
+    collapsed: &HashSet<Path>,
+    on_toggle: fn(Path) -> Message,
+) -> Element<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    render(root, vec![], collapsed, on_toggle)
+}
+
+fn render<'a, Message>(
+    node: &Node,
+    path: Path,
+    collapsed: &HashSet<Path>,
+    on_toggle: fn(Path) -> Message,
+) -> Element<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    let label = text(node.label.clone()).font(Font {
+        weight: font::Weight::Bold,
+        ..Default::default()
+    });
+    let value = node
+        .value
+        .clone()
+        .map(|value| text(value).font(Font::MONOSPACE).size(14));
+
+    if node.children.is_empty() {
+        return row![Space::with_width(18), label]
+            .push_maybe(value)
+            .spacing(8)
+            .into();
+    }
+
+    let is_collapsed = collapsed.contains(&path);
+
+    let header = button(
+        row![
+            fa_icon_solid(if is_collapsed {
+                "caret-right"
+            } else {
+                "caret-down"
+            })
+            .size(14.),
+            label
+        ]
+        .push_maybe(value)
+        .spacing(8),
+    )
+    .style(button::text)
+    .padding(0)
+    .on_press(on_toggle(path.clone()));
+
+    if is_collapsed {
+        return header.into();
+    }
+
+    let children = node.children.iter().enumerate().map(|(i, child)| {
+        let mut path = path.clone();
+        path.push(i);
+
+        render(child, path, collapsed, on_toggle)
+    });
+
+    column![
+        header,
+        row![Space::with_width(18), column(children).spacing(4)]
+    ]
+    .spacing(4)
+    .into()
+}
@@ -0,0 +1,8 @@
+mod state;
+mod tx;
+mod update;
+mod view;
+
+pub use state::*;
+pub use tx::*;
+pub use update::*;
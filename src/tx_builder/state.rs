@@ -0,0 +1,23 @@
+use iced::widget::text_editor;
+
+use super::{Built, Output};
+
+#[derive(Debug, Default)]
+pub struct State {
+    /// `<tx hash>#<index>` each
+    pub inputs: Vec<String>,
+    pub outputs: Vec<Output>,
+    pub fee: String,
+    pub valid_from: String,
+    pub ttl: String,
+    pub mint: String,
+    /// Key hashes, separated by whitespace or commas
+    pub required_signers: String,
+    pub metadata: text_editor::Content,
+    /// ed25519 private keys as hex, bech32 or TextEnvelopes, one per line
+    pub signing_keys: text_editor::Content,
+    pub built: Option<Result<Built, String>>,
+    /// Where to save the built tx as a TextEnvelope
+    pub path: String,
+    pub saved: Option<Result<String, String>>,
+}
@@ -0,0 +1,506 @@
+use std::collections::BTreeMap;
+
+use pallas::{
+    codec::{
+        minicbor,
+        utils::{CborWrap, Int, KeyValuePairs, NonEmptyKeyValuePairs, NonEmptySet, Nullable, Set},
+    },
+    crypto::hash::{Hash, Hasher},
+    ledger::primitives::{
+        conway::{
+            AuxiliaryData, Metadatum, MintedPostAlonzoTransactionOutput, MintedTransactionBody,
+            MintedTransactionOutput, Multiasset, PseudoDatumOption, PseudoTx, TransactionInput,
+            VKeyWitness, Value, WitnessSet,
+        },
+        Metadata,
+    },
+};
+use serde_json::Value as Json;
+
+use crate::{
+    address_inspector,
+    encoding::{likely_encoding, TextEnvelope},
+    plutus_data,
+    signatures::sign_with_edd25519,
+};
+
+use super::State;
+
+/// The ledger rejects metadata strings and bytes longer than this.
+const MAX_METADATUM_LENGTH: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct Built {
+    pub cbor: Vec<u8>,
+    pub hash: Hash<32>,
+    /// Key hashes of the keys the tx was signed with
+    pub signers: Vec<Hash<28>>,
+}
+
+impl Built {
+    /// The tx as cardano-cli writes it, typed by whether it's been signed.
+    pub fn text_envelope(&self) -> TextEnvelope {
+        let kind = if self.signers.is_empty() {
+            "Unwitnessed Tx ConwayEra"
+        } else {
+            "Witnessed Tx ConwayEra"
+        };
+
+        TextEnvelope::new(kind, "Ledger Cddl Format", &self.cbor)
+    }
+}
+
+impl State {
+    /// Put the form together into a Conway tx, signed with whatever keys
+    /// were pasted in.
+    pub fn build(&self) -> Result<Built, String> {
+        let inputs = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| input_of(input).map_err(|e| format!("Input #{i}: {e}")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if inputs.is_empty() {
+            return Err("A tx needs at least one input".to_string());
+        }
+
+        let datums = self
+            .outputs
+            .iter()
+            .enumerate()
+            .map(|(i, output)| output.datum().map_err(|e| format!("Output #{i}: {e}")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let outputs = self
+            .outputs
+            .iter()
+            .zip(&datums)
+            .enumerate()
+            .map(|(i, (output, datum))| {
+                output
+                    .build(datum.as_deref())
+                    .map_err(|e| format!("Output #{i}: {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let slot = |slot: &str, name: &str| {
+            let slot = slot.trim();
+
+            if slot.is_empty() {
+                return Ok(None);
+            }
+
+            slot.parse()
+                .map(Some)
+                .map_err(|_| format!("{name} must be a slot number"))
+        };
+
+        let fee = self
+            .fee
+            .trim()
+            .parse()
+            .map_err(|_| "Fee must be an amount of lovelace".to_string())?;
+
+        let mint = assets(&self.mint)
+            .map_err(|e| format!("Mint: {e}"))?
+            .map(|assets| {
+                multiasset(assets, |quantity| {
+                    i64::try_from(quantity).ok()?.try_into().ok()
+                })
+            })
+            .transpose()
+            .map_err(|e| format!("Mint: {e}"))?;
+
+        let required_signers = self
+            .required_signers
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|signer| !signer.is_empty())
+            .map(|signer| {
+                signer
+                    .parse::<Hash<28>>()
+                    .map_err(|_| format!("Required signers must be 28 byte key hashes: {signer}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let metadata = self.metadata.text();
+        let auxiliary_data = match metadata.trim() {
+            "" => None,
+            json => Some(AuxiliaryData::Shelley(metadata_of(json)?)),
+        };
+
+        let body = MintedTransactionBody {
+            inputs: Set::from(inputs),
+            outputs,
+            fee,
+            ttl: slot(&self.ttl, "TTL")?,
+            certificates: None,
+            withdrawals: None,
+            auxiliary_data_hash: auxiliary_data
+                .as_ref()
+                .map(|data| Hasher::<256>::hash_cbor(data).to_vec().into()),
+            validity_interval_start: slot(&self.valid_from, "Valid from")?,
+            mint,
+            script_data_hash: None,
+            collateral: None,
+            required_signers: NonEmptySet::try_from(required_signers).ok(),
+            network_id: None,
+            collateral_return: None,
+            total_collateral: None,
+            reference_inputs: None,
+            voting_procedures: None,
+            proposal_procedures: None,
+            treasury_value: None,
+            donation: None,
+        };
+
+        let hash = Hasher::<256>::hash_cbor(&body);
+
+        let mut witnesses = vec![];
+        let mut signers = vec![];
+
+        for (i, key) in signing_keys(&self.signing_keys.text()).iter().enumerate() {
+            let key = likely_encoding(key)
+                .decode(key)
+                .map_err(|e| format!("Signing key #{i}: {e}"))?;
+
+            let (vkey, signature) = sign_with_edd25519(key, hash.to_vec())
+                .map_err(|e| format!("Signing key #{i}: {e}"))?;
+
+            signers.push(Hasher::<224>::hash(&vkey));
+            witnesses.push(VKeyWitness {
+                vkey: vkey.to_vec().into(),
+                signature: signature.to_vec().into(),
+            });
+        }
+
+        let tx = PseudoTx {
+            transaction_body: body,
+            transaction_witness_set: WitnessSet {
+                vkeywitness: NonEmptySet::try_from(witnesses).ok(),
+                native_script: None,
+                bootstrap_witness: None,
+                plutus_v1_script: None,
+                plutus_data: None,
+                redeemer: None,
+                plutus_v2_script: None,
+                plutus_v3_script: None,
+            },
+            success: true,
+            auxiliary_data: auxiliary_data.map_or(Nullable::Null, Nullable::Some),
+        };
+
+        let cbor = minicbor::to_vec(&tx).map_err(|e| format!("Failed to encode the tx: {e}"))?;
+
+        Ok(Built {
+            cbor,
+            hash,
+            signers,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Output {
+    pub address: String,
+    pub lovelace: String,
+    pub assets: String,
+    pub datum: String,
+    /// Only put the datum's hash in the output rather than the datum itself
+    pub datum_hash: bool,
+}
+
+impl Output {
+    /// The datum's CBOR, kept exactly as given when it was pasted as CBOR
+    /// since whatever spends the output has to reveal the same bytes.
+    fn datum(&self) -> Result<Option<Vec<u8>>, String> {
+        match self.datum.trim() {
+            "" => Ok(None),
+            datum => {
+                let (data, cbor) = plutus_data::parse(datum).map_err(|e| format!("Datum: {e}"))?;
+
+                match cbor {
+                    Some(cbor) => Ok(Some(cbor)),
+                    None => minicbor::to_vec(&data)
+                        .map(Some)
+                        .map_err(|e| format!("Datum: {e}")),
+                }
+            }
+        }
+    }
+
+    fn build<'a>(&self, datum: Option<&'a [u8]>) -> Result<MintedTransactionOutput<'a>, String> {
+        let address = self.address.trim();
+        let (_, address) =
+            address_inspector::parse(address).ok_or_else(|| "Invalid address".to_string())?;
+
+        let lovelace = self
+            .lovelace
+            .trim()
+            .parse()
+            .map_err(|_| "Lovelace must be a whole number".to_string())?;
+
+        let value = match assets(&self.assets)? {
+            Some(assets) => Value::Multiasset(
+                lovelace,
+                multiasset(assets, |quantity| {
+                    u64::try_from(quantity).ok()?.try_into().ok()
+                })?,
+            ),
+            None => Value::Coin(lovelace),
+        };
+
+        let datum_option = match datum {
+            None => None,
+            Some(cbor) if self.datum_hash => {
+                Some(PseudoDatumOption::Hash(Hasher::<256>::hash(cbor)))
+            }
+            Some(cbor) => Some(PseudoDatumOption::Data(CborWrap(
+                minicbor::decode(cbor).map_err(|e| format!("Datum: {e}"))?,
+            ))),
+        };
+
+        Ok(MintedTransactionOutput::PostAlonzo(
+            MintedPostAlonzoTransactionOutput {
+                address: address.to_vec().into(),
+                value,
+                datum_option,
+                script_ref: None,
+            },
+        ))
+    }
+}
+
+/// Signing keys are one per line, in whatever encoding, except for
+/// TextEnvelopes which run until their closing brace.
+fn signing_keys(text: &str) -> Vec<String> {
+    let mut keys: Vec<String> = vec![];
+    let mut envelope: Option<String> = None;
+
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        match envelope.as_mut() {
+            Some(json) => json.push_str(line),
+            None if line.starts_with('{') => envelope = Some(line.to_string()),
+            None => keys.push(line.to_string()),
+        }
+
+        if envelope.as_ref().is_some_and(|json| json.ends_with('}')) {
+            keys.extend(envelope.take());
+        }
+    }
+
+    keys.extend(envelope);
+    keys
+}
+
+/// Inputs are written the way explorers and cardano-cli show them,
+/// `<tx hash>#<index>`.
+fn input_of(input: &str) -> Result<TransactionInput, String> {
+    let (hash, index) = input
+        .trim()
+        .split_once('#')
+        .ok_or_else(|| "expected <tx hash>#<index>".to_string())?;
+
+    Ok(TransactionInput {
+        transaction_id: hash
+            .parse()
+            .map_err(|_| "the tx hash must be 32 bytes of hex".to_string())?,
+        index: index
+            .parse()
+            .map_err(|_| "the index must be a whole number".to_string())?,
+    })
+}
+
+type Assets = BTreeMap<Hash<28>, BTreeMap<Vec<u8>, i128>>;
+
+/// Assets in cardano-cli's notation, `5 <policy id>.<asset name hex> + 10
+/// <policy id>`, with the quantities of repeated assets added up.
+fn assets(input: &str) -> Result<Option<Assets>, String> {
+    let mut assets = Assets::new();
+
+    for term in input.split('+').map(str::trim).filter(|t| !t.is_empty()) {
+        let (quantity, unit) = term
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| format!("expected <quantity> <policy id>.<asset name>: {term}"))?;
+
+        let quantity: i128 = quantity
+            .parse()
+            .map_err(|_| format!("invalid quantity: {quantity}"))?;
+
+        let (policy, name) = unit.trim().split_once('.').unwrap_or((unit.trim(), ""));
+
+        let policy = policy
+            .parse()
+            .map_err(|_| format!("policy ids must be 28 bytes of hex: {policy}"))?;
+
+        let name = hex::decode(name).map_err(|e| format!("invalid asset name {name}: {e}"))?;
+
+        if name.len() > 32 {
+            return Err(format!(
+                "asset names can't be longer than 32 bytes: {}",
+                hex::encode(&name)
+            ));
+        }
+
+        *assets.entry(policy).or_default().entry(name).or_default() += quantity;
+    }
+
+    Ok((!assets.is_empty()).then_some(assets))
+}
+
+/// Minting and burning the same asset cancels out, so only what's left after
+/// adding everything up goes in the tx.
+fn multiasset<A: Clone>(
+    assets: Assets,
+    quantity_of: impl Fn(i128) -> Option<A>,
+) -> Result<Multiasset<A>, String> {
+    let policies = assets
+        .into_iter()
+        .filter_map(|(policy, names)| {
+            let names = names
+                .into_iter()
+                .filter(|(_, quantity)| *quantity != 0)
+                .map(|(name, quantity)| {
+                    quantity_of(quantity)
+                        .map(|quantity| (name.into(), quantity))
+                        .ok_or_else(|| format!("quantity out of range: {quantity}"))
+                })
+                .collect::<Result<Vec<_>, _>>();
+
+            match names {
+                Ok(names) => NonEmptyKeyValuePairs::try_from(names)
+                    .ok()
+                    .map(|names| Ok((policy, names))),
+                Err(e) => Some(Err(e)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    NonEmptyKeyValuePairs::try_from(policies).map_err(|_| "the quantities add up to 0".to_string())
+}
+
+/// Metadata as cardano-cli's no-schema JSON: an object from labels to
+/// values, where strings starting with 0x are bytes.
+fn metadata_of(json: &str) -> Result<Metadata, String> {
+    let json: Json = serde_json::from_str(json).map_err(|e| format!("Invalid metadata: {e}"))?;
+
+    let labels = json
+        .as_object()
+        .ok_or_else(|| "Metadata must be an object of labels".to_string())?;
+
+    let metadata = labels
+        .iter()
+        .map(|(label, value)| {
+            let label = label
+                .parse()
+                .map_err(|_| format!("Metadata labels must be whole numbers: {label}"))?;
+
+            Ok((label, metadatum(value, &format!("$.{label}"))?))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(KeyValuePairs::Def(metadata))
+}
+
+fn metadatum(value: &Json, path: &str) -> Result<Metadatum, String> {
+    match value {
+        Json::Number(number) => number
+            .to_string()
+            .parse::<i128>()
+            .ok()
+            .and_then(|int| Int::try_from(int).ok())
+            .map(Metadatum::Int)
+            .ok_or_else(|| format!("{path}: expected a whole number, got {number}")),
+        Json::String(s) => {
+            let metadatum = match s.strip_prefix("0x") {
+                Some(bytes) => hex::decode(bytes)
+                    .map(|bytes| Metadatum::Bytes(bytes.into()))
+                    .map_err(|e| format!("{path}: invalid hex: {e}"))?,
+                None => Metadatum::Text(s.clone()),
+            };
+
+            let length = match &metadatum {
+                Metadatum::Bytes(bytes) => bytes.len(),
+                _ => s.len(),
+            };
+
+            if length > MAX_METADATUM_LENGTH {
+                return Err(format!(
+                    "{path}: strings and bytes can't be longer than {MAX_METADATUM_LENGTH} bytes"
+                ));
+            }
+
+            Ok(metadatum)
+        }
+        Json::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| metadatum(item, &format!("{path}[{i}]")))
+            .collect::<Result<_, _>>()
+            .map(Metadatum::Array),
+        Json::Object(entries) => entries
+            .iter()
+            .map(|(key, value)| {
+                let path = format!("{path}.{key}");
+
+                // Keys that read as integers are integers, the way
+                // cardano-cli reads them
+                let key = match key.parse::<i64>() {
+                    Ok(int) => Metadatum::Int(int.into()),
+                    Err(_) => Metadatum::Text(key.clone()),
+                };
+
+                Ok((key, metadatum(value, &path)?))
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map(|entries| Metadatum::Map(KeyValuePairs::Def(entries))),
+        Json::Bool(_) | Json::Null => Err(format!(
+            "{path}: only numbers, strings, lists and objects can be metadata"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_datums_keep_the_cbor_they_were_given() {
+        let output = Output {
+            address: format!("61{}", "00".repeat(28)),
+            lovelace: "1000000".to_string(),
+            // A definite list, which would be re-encoded as an indefinite one
+            datum: "8101".to_string(),
+            ..Default::default()
+        };
+
+        let datum = output.datum().unwrap();
+        let built = output.build(datum.as_deref()).unwrap();
+        let cbor = hex::encode(minicbor::to_vec(&built).unwrap());
+
+        assert!(cbor.ends_with("d818428101"), "{cbor}");
+    }
+
+    #[test]
+    fn signing_keys_take_any_encoding() {
+        let seed = [7; 32];
+        let envelope = TextEnvelope::new(
+            "PaymentSigningKeyShelley_ed25519",
+            "Payment Signing Key",
+            &minicbor::to_vec(minicbor::bytes::ByteArray::from(seed)).unwrap(),
+        );
+
+        let keys = signing_keys(&format!(
+            "{}\n\n{}\n{}\n",
+            hex::encode(seed),
+            crate::encoding::encode_bech32("ed25519_sk", &seed),
+            envelope.to_json(),
+        ));
+
+        assert_eq!(keys.len(), 3);
+
+        for key in keys {
+            assert_eq!(likely_encoding(&key).decode(&key).unwrap(), seed, "{key}");
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use iced::{clipboard, widget::text_editor, Task};
+
+use super::{Output, State};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    AddInput,
+    RemoveInput(usize),
+    InputChanged(usize, String),
+    AddOutput,
+    RemoveOutput(usize),
+    OutputChanged(usize, OutputField, String),
+    DatumHashToggled(usize, bool),
+    FeeChanged(String),
+    ValidFromChanged(String),
+    TtlChanged(String),
+    MintChanged(String),
+    RequiredSignersChanged(String),
+    MetadataChanged(text_editor::Action),
+    SigningKeysChanged(text_editor::Action),
+    Copy(String),
+    PathChanged(String),
+    Save,
+    Saved(Result<String, String>),
+    /// Handled by the workspace, which opens the tx in a block inspector
+    Open(Vec<u8>),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum OutputField {
+    Address,
+    Lovelace,
+    Assets,
+    Datum,
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            AddInput => self.inputs.push(String::new()),
+            RemoveInput(i) => {
+                if i < self.inputs.len() {
+                    self.inputs.remove(i);
+                }
+            }
+            InputChanged(i, input) => {
+                if let Some(old) = self.inputs.get_mut(i) {
+                    *old = input;
+                }
+            }
+            AddOutput => self.outputs.push(Output::default()),
+            RemoveOutput(i) => {
+                if i < self.outputs.len() {
+                    self.outputs.remove(i);
+                }
+            }
+            OutputChanged(i, field, value) => {
+                if let Some(output) = self.outputs.get_mut(i) {
+                    match field {
+                        OutputField::Address => output.address = value,
+                        OutputField::Lovelace => output.lovelace = value,
+                        OutputField::Assets => output.assets = value,
+                        OutputField::Datum => output.datum = value,
+                    }
+                }
+            }
+            DatumHashToggled(i, datum_hash) => {
+                if let Some(output) = self.outputs.get_mut(i) {
+                    output.datum_hash = datum_hash;
+                }
+            }
+            FeeChanged(fee) => self.fee = fee,
+            ValidFromChanged(slot) => self.valid_from = slot,
+            TtlChanged(slot) => self.ttl = slot,
+            MintChanged(mint) => self.mint = mint,
+            RequiredSignersChanged(signers) => self.required_signers = signers,
+            MetadataChanged(action) => {
+                let edit = action.is_edit();
+
+                self.metadata.perform(action);
+
+                if !edit {
+                    return Task::none();
+                }
+            }
+            SigningKeysChanged(action) => {
+                let edit = action.is_edit();
+
+                self.signing_keys.perform(action);
+
+                if !edit {
+                    return Task::none();
+                }
+            }
+            Copy(s) => return clipboard::write(s),
+            PathChanged(path) => {
+                self.path = path;
+                return Task::none();
+            }
+            Save => {
+                let Some(Ok(built)) = &self.built else {
+                    return Task::none();
+                };
+
+                return Task::perform(
+                    built.text_envelope().save(self.path.trim().to_string()),
+                    Saved,
+                );
+            }
+            Saved(result) => {
+                self.saved = Some(result);
+                return Task::none();
+            }
+            Open(_) => return Task::none(),
+        }
+
+        // Small enough to redo on every change, which keeps the hash and
+        // any errors in sync with the form
+        self.built = Some(self.build());
+        self.saved = None;
+
+        Task::none()
+    }
+}
@@ -0,0 +1,201 @@
+use iced::{
+    padding,
+    widget::{
+        button, checkbox, column, container, row, scrollable, text, text_editor, text_input, Column,
+    },
+    Border, Color, Element, Font,
+    Length::{Fill, FillPortion},
+    Theme,
+};
+use iced_font_awesome::fa_icon_solid;
+
+use super::{Message, OutputField, State};
+use crate::copyable_text::copyable_text;
+
+const RED: Color = Color::from_rgb(1.0, 0.0, 0.0);
+const GREEN: Color = Color::from_rgb(0.0, 0.7, 0.0);
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        row![
+            scrollable(self.view_form().padding(padding::right(15)))
+                .width(FillPortion(1))
+                .height(Fill),
+            container(scrollable(self.view_tx().width(Fill)).width(Fill))
+                .width(FillPortion(1))
+                .height(Fill)
+                .style(|theme: &Theme| {
+                    let palette = theme.extended_palette();
+
+                    container::Style {
+                        border: Border {
+                            width: 1.0,
+                            radius: 4.0.into(),
+                            color: palette.background.strong.color,
+                        },
+                        ..Default::default()
+                    }
+                })
+                .padding(10)
+        ]
+        .spacing(5)
+        .padding(padding::top(20).left(5).right(10).bottom(10))
+        .into()
+    }
+
+    fn view_form(&self) -> Column<'_, Message> {
+        let remove = |message| {
+            button(fa_icon_solid("xmark").size(14.))
+                .on_press(message)
+                .style(button::text)
+                .padding(5)
+        };
+
+        let add = |label, message| {
+            button(row![fa_icon_solid("plus").size(14.), text(label)].spacing(5))
+                .on_press(message)
+                .style(button::secondary)
+        };
+
+        let heading = |label| text(label).size(18);
+
+        let editor_style = |theme: &Theme, status| {
+            let mut defaults = text_editor::default(theme, status);
+
+            defaults.border.radius = 4.0.into();
+
+            defaults
+        };
+
+        let inputs = self.inputs.iter().enumerate().map(|(i, input)| {
+            row![
+                text_input("<tx hash>#<index>", input)
+                    .on_input(move |input| Message::InputChanged(i, input))
+                    .font(Font::MONOSPACE),
+                remove(Message::RemoveInput(i))
+            ]
+            .spacing(5)
+            .into()
+        });
+
+        let outputs = self.outputs.iter().enumerate().map(|(i, output)| {
+            let field = move |placeholder, value, field| {
+                text_input(placeholder, value)
+                    .on_input(move |value| Message::OutputChanged(i, field, value))
+            };
+
+            column![
+                row![
+                    text(format!("#{i}")).width(Fill),
+                    remove(Message::RemoveOutput(i))
+                ],
+                field("address", &output.address, OutputField::Address),
+                field("lovelace", &output.lovelace, OutputField::Lovelace),
+                field(
+                    "assets: 5 <policy id>.<asset name> + ...",
+                    &output.assets,
+                    OutputField::Assets
+                ),
+                field(
+                    "datum: cbor or detailed schema json",
+                    &output.datum,
+                    OutputField::Datum
+                ),
+                checkbox("only the datum hash", output.datum_hash)
+                    .on_toggle(move |toggled| Message::DatumHashToggled(i, toggled)),
+            ]
+            .spacing(5)
+            .into()
+        });
+
+        column![
+            heading("inputs"),
+            column(inputs).spacing(5),
+            add("input", Message::AddInput),
+            heading("outputs"),
+            column(outputs).spacing(15),
+            add("output", Message::AddOutput),
+            heading("body"),
+            text_input("fee in lovelace", &self.fee).on_input(Message::FeeChanged),
+            row![
+                text_input("valid from slot", &self.valid_from).on_input(Message::ValidFromChanged),
+                text_input("valid until slot (ttl)", &self.ttl).on_input(Message::TtlChanged),
+            ]
+            .spacing(5),
+            text_input("mint: 5 <policy id>.<asset name> + -1 ...", &self.mint)
+                .on_input(Message::MintChanged),
+            text_input("required signers: key hashes", &self.required_signers)
+                .on_input(Message::RequiredSignersChanged),
+            text_editor(&self.metadata)
+                .placeholder("metadata: {\"674\": {\"msg\": [\"hello\"]}}")
+                .on_action(Message::MetadataChanged)
+                .height(120)
+                .style(editor_style),
+            heading("witnesses"),
+            text_editor(&self.signing_keys)
+                .placeholder("ed25519 signing keys in hex, bech32 or .skey JSON, one per line...")
+                .on_action(Message::SigningKeysChanged)
+                .height(80)
+                .wrapping(text::Wrapping::Glyph)
+                .style(editor_style),
+        ]
+        .spacing(10)
+    }
+
+    fn view_tx(&self) -> Column<'_, Message> {
+        let built = match &self.built {
+            None => return column![text("nothing built yet")],
+            Some(Err(e)) => return column![text(e).color(RED)],
+            Some(Ok(built)) => built,
+        };
+
+        let field = |label, value: Element<'static, Message>| row![text(label).width(160), value];
+
+        let cbor = hex::encode(&built.cbor);
+
+        column![
+            field(
+                "tx hash",
+                copyable_text(&built.hash.to_string(), Message::Copy)
+            ),
+            field("size", text(format!("{} bytes", built.cbor.len())).into()),
+            field(
+                "signed by",
+                if built.signers.is_empty() {
+                    text("nobody").into()
+                } else {
+                    column(built.signers.iter().map(|signer| {
+                        text(signer.to_string())
+                            .font(Font::MONOSPACE)
+                            .size(14)
+                            .into()
+                    }))
+                    .spacing(2)
+                    .into()
+                }
+            ),
+            button(text("Open in block inspector")).on_press(Message::Open(built.cbor.clone())),
+            row![
+                text_input("path to save to, e.g. tx.signed", &self.path)
+                    .on_input(Message::PathChanged)
+                    .on_submit(Message::Save),
+                button(text("Save as TextEnvelope"))
+                    .on_press_maybe((!self.path.trim().is_empty()).then_some(Message::Save)),
+            ]
+            .spacing(5),
+        ]
+        .push_maybe(self.saved.as_ref().map(|saved| match saved {
+            Ok(path) => text(format!("saved {path}")).color(GREEN),
+            Err(e) => text(e).color(RED),
+        }))
+        .push(row![
+            text("cbor").width(Fill),
+            button(fa_icon_solid("clone").size(14.))
+                .on_press(Message::Copy(cbor.clone()))
+                .style(button::text)
+                .padding(5)
+        ])
+        .push(text(cbor).font(Font::MONOSPACE).size(14))
+        .spacing(10)
+    }
+}
@@ -0,0 +1,479 @@
+//! Differences in how two txs are encoded, including the ones that don't
+//! change any value but still change the bytes, and with them the hash.
+
+use crate::cbor_explorer::{compact, Item, Kind};
+
+/// Longest a value is shown in a description before it's cut short.
+const MAX_VALUE_LENGTH: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct Difference {
+    /// Where in the tx, e.g. `body.outputs[0]`
+    pub path: String,
+    /// Byte offsets of the items on each side
+    pub left: usize,
+    pub right: usize,
+    pub description: String,
+}
+
+impl Difference {
+    /// Whether the difference is in the bytes the tx hash is taken over.
+    pub fn in_body(&self) -> bool {
+        self.path == "body" || self.path.starts_with("body.") || self.path.starts_with("body[")
+    }
+}
+
+/// Walk both txs side by side, matching map entries by key rather than by
+/// position so reordered maps show up as such.
+pub fn differences(left: (&[u8], &Item), right: (&[u8], &Item)) -> Vec<Difference> {
+    let mut walker = Walker {
+        left: left.0,
+        right: right.0,
+        differences: vec![],
+    };
+
+    walker.walk("", left.1, right.1);
+    walker.differences
+}
+
+/// The bytes of the body, the first item of every tx, which is what the tx
+/// hash is taken over.
+pub fn body<'a>(bytes: &'a [u8], tx: &Item) -> Option<&'a [u8]> {
+    let Kind::Array { items, .. } = &tx.kind else {
+        return None;
+    };
+
+    let body = items.first()?;
+    let end = items.get(1)?.offset;
+
+    bytes.get(body.offset..end)
+}
+
+struct Walker<'a> {
+    left: &'a [u8],
+    right: &'a [u8],
+    differences: Vec<Difference>,
+}
+
+impl Walker<'_> {
+    fn push(&mut self, path: &str, left: &Item, right: &Item, description: impl Into<String>) {
+        self.differences.push(Difference {
+            path: if path.is_empty() { "tx" } else { path }.to_string(),
+            left: left.offset,
+            right: right.offset,
+            description: description.into(),
+        });
+    }
+
+    /// The initial byte and argument, which is where equal values can still
+    /// be encoded differently, e.g. a 0 written in two bytes.
+    fn header<'b>(bytes: &'b [u8], item: &Item) -> &'b [u8] {
+        let len = match bytes.get(item.offset).map(|initial| initial & 0x1f) {
+            Some(24) => 2,
+            Some(25) => 3,
+            Some(26) => 5,
+            Some(27) => 9,
+            _ => 1,
+        };
+
+        bytes
+            .get(item.offset..item.offset + len)
+            .unwrap_or_default()
+    }
+
+    fn compare_headers(&mut self, path: &str, left: &Item, right: &Item) {
+        let (l, r) = (
+            Self::header(self.left, left),
+            Self::header(self.right, right),
+        );
+
+        if l != r {
+            self.push(
+                path,
+                left,
+                right,
+                format!(
+                    "same value, but the head is {} vs {}",
+                    hex::encode(l),
+                    hex::encode(r)
+                ),
+            );
+        }
+    }
+
+    fn walk(&mut self, path: &str, left: &Item, right: &Item) {
+        match (&left.kind, &right.kind) {
+            (Kind::Tag(l, inner_left), Kind::Tag(r, inner_right)) => {
+                if l != r {
+                    self.push(path, left, right, format!("tag {l} vs {r}"));
+                } else {
+                    self.compare_headers(path, left, right);
+                }
+
+                self.walk(path, inner_left, inner_right);
+            }
+            // Sets may or may not be wrapped in tag 258, both decode the same
+            (Kind::Tag(tag, inner), _) => {
+                self.push(path, left, right, format!("tag {tag} on the left only"));
+                self.walk(path, inner, right);
+            }
+            (_, Kind::Tag(tag, inner)) => {
+                self.push(path, left, right, format!("tag {tag} on the right only"));
+                self.walk(path, left, inner);
+            }
+            (Kind::Unsigned(l), Kind::Unsigned(r)) | (Kind::Negative(l), Kind::Negative(r)) => {
+                if l != r {
+                    self.push(path, left, right, values(left, right));
+                } else {
+                    self.compare_headers(path, left, right);
+                }
+            }
+            (Kind::Bytes(l), Kind::Bytes(r)) => {
+                if l != r {
+                    self.push(path, left, right, values(left, right));
+                } else {
+                    self.compare_headers(path, left, right);
+                }
+            }
+            (Kind::Text(l), Kind::Text(r)) => {
+                if l != r {
+                    self.push(path, left, right, values(left, right));
+                } else {
+                    self.compare_headers(path, left, right);
+                }
+            }
+            (Kind::Chunks(_), _) | (_, Kind::Chunks(_)) => {
+                if contents(left).is_none() || contents(left) != contents(right) {
+                    return self.push(path, left, right, values(left, right));
+                }
+
+                match (&left.kind, &right.kind) {
+                    (Kind::Chunks(l), Kind::Chunks(r)) => {
+                        if l.iter().map(contents).ne(r.iter().map(contents)) {
+                            self.push(path, left, right, "same value, chunked differently");
+                        }
+                    }
+                    (Kind::Chunks(_), _) => {
+                        self.push(path, left, right, "same value, chunked on the left only")
+                    }
+                    _ => self.push(path, left, right, "same value, chunked on the right only"),
+                }
+            }
+            (
+                Kind::Array {
+                    items: l,
+                    indefinite: l_indefinite,
+                },
+                Kind::Array {
+                    items: r,
+                    indefinite: r_indefinite,
+                },
+            ) => {
+                if l_indefinite != r_indefinite {
+                    self.push(path, left, right, length(*l_indefinite, *r_indefinite));
+                } else if l.len() == r.len() {
+                    self.compare_headers(path, left, right);
+                }
+
+                if l.len() != r.len() {
+                    self.push(
+                        path,
+                        left,
+                        right,
+                        format!("{} vs {} items", l.len(), r.len()),
+                    );
+                }
+
+                let len = l.len().max(r.len());
+
+                for (i, (l, r)) in l.iter().zip(r).enumerate() {
+                    self.walk(&item_path(path, i, len), l, r);
+                }
+            }
+            (
+                Kind::Map {
+                    entries: l,
+                    indefinite: l_indefinite,
+                },
+                Kind::Map {
+                    entries: r,
+                    indefinite: r_indefinite,
+                },
+            ) => {
+                if l_indefinite != r_indefinite {
+                    self.push(path, left, right, length(*l_indefinite, *r_indefinite));
+                } else if l.len() == r.len() {
+                    self.compare_headers(path, left, right);
+                }
+
+                let mut matched = vec![];
+
+                for (key, value) in l {
+                    let key_path = entry_path(path, key);
+
+                    match r.iter().position(|(other, _)| same_value(key, other)) {
+                        Some(j) => {
+                            matched.push(j);
+
+                            let (other_key, other_value) = &r[j];
+
+                            self.walk(&key_path, key, other_key);
+                            self.walk(&key_path, value, other_value);
+                        }
+                        None => self.push(&key_path, key, right, "only on the left"),
+                    }
+                }
+
+                for (j, (key, _)) in r.iter().enumerate() {
+                    if !matched.contains(&j) {
+                        self.push(&entry_path(path, key), left, key, "only on the right");
+                    }
+                }
+
+                if matched.windows(2).any(|pair| pair[0] > pair[1]) {
+                    self.push(path, left, right, "same keys in a different order");
+                }
+            }
+            (Kind::Float(l, l_precision), Kind::Float(r, r_precision)) => {
+                if l.to_bits() != r.to_bits() {
+                    self.push(path, left, right, values(left, right));
+                } else if l_precision != r_precision {
+                    self.push(
+                        path,
+                        left,
+                        right,
+                        format!("same value, as a {l_precision:?} vs {r_precision:?} float"),
+                    );
+                }
+            }
+            (l, r) if l == r => {}
+            (l, r) if l.major() == r.major() => self.push(path, left, right, values(left, right)),
+            (l, r) => self.push(
+                path,
+                left,
+                right,
+                format!("{} vs {}: {}", l.major(), r.major(), values(left, right)),
+            ),
+        }
+    }
+}
+
+fn values(left: &Item, right: &Item) -> String {
+    format!("{} vs {}", shorten(compact(left)), shorten(compact(right)))
+}
+
+fn shorten(value: String) -> String {
+    match value.char_indices().nth(MAX_VALUE_LENGTH) {
+        Some((end, _)) => format!("{}…", &value[..end]),
+        None => value,
+    }
+}
+
+fn length(left_indefinite: bool, right_indefinite: bool) -> String {
+    let length = |indefinite| if indefinite { "indefinite" } else { "definite" };
+
+    format!(
+        "{} vs {} length",
+        length(left_indefinite),
+        length(right_indefinite)
+    )
+}
+
+/// The contents of byte and text strings, joined up if they were chunked.
+fn contents(item: &Item) -> Option<Vec<u8>> {
+    match &item.kind {
+        Kind::Bytes(bytes) => Some(bytes.clone()),
+        Kind::Text(text) => Some(text.as_bytes().to_vec()),
+        Kind::Chunks(chunks) => chunks
+            .iter()
+            .map(contents)
+            .collect::<Option<Vec<_>>>()
+            .map(|chunks| chunks.concat()),
+        _ => None,
+    }
+}
+
+/// Whether two items decode to the same value, however they were encoded.
+fn same_value(left: &Item, right: &Item) -> bool {
+    match (&left.kind, &right.kind) {
+        (Kind::Tag(l, l_item), Kind::Tag(r, r_item)) => l == r && same_value(l_item, r_item),
+        (Kind::Array { items: l, .. }, Kind::Array { items: r, .. }) => {
+            l.len() == r.len() && l.iter().zip(r).all(|(l, r)| same_value(l, r))
+        }
+        (Kind::Map { entries: l, .. }, Kind::Map { entries: r, .. }) => {
+            l.len() == r.len()
+                && l.iter().all(|(key, value)| {
+                    r.iter()
+                        .any(|(k, v)| same_value(key, k) && same_value(value, v))
+                })
+        }
+        (Kind::Bytes(_) | Kind::Text(_) | Kind::Chunks(_), _) => {
+            left.kind.major() == right.kind.major()
+                && contents(left).is_some_and(|l| contents(right) == Some(l))
+        }
+        (Kind::Float(l, _), Kind::Float(r, _)) => l.to_bits() == r.to_bits(),
+        (l, r) => l == r,
+    }
+}
+
+/// Names of the top level items, which depend on how many there are: Byron
+/// txs have two, Shelley to Mary three and everything since four.
+fn item_path(path: &str, index: usize, len: usize) -> String {
+    let name = match (path, len, index) {
+        ("", _, 0) => Some("body"),
+        ("", _, 1) => Some("witnesses"),
+        ("", 3, 2) | ("", 4, 3) => Some("auxiliary_data"),
+        ("", 4, 2) => Some("is_valid"),
+        _ => None,
+    };
+
+    match name {
+        Some(name) => name.to_string(),
+        None => format!("{path}[{index}]"),
+    }
+}
+
+fn entry_path(path: &str, key: &Item) -> String {
+    let name = match (path, &key.kind) {
+        ("body", Kind::Unsigned(key)) => body_field(*key),
+        ("witnesses", Kind::Unsigned(key)) => witness_field(*key),
+        _ => None,
+    };
+
+    match name {
+        Some(name) => format!("{path}.{name}"),
+        None => format!("{path}.{}", shorten(compact(key))),
+    }
+}
+
+fn body_field(key: u64) -> Option<&'static str> {
+    Some(match key {
+        0 => "inputs",
+        1 => "outputs",
+        2 => "fee",
+        3 => "ttl",
+        4 => "certificates",
+        5 => "withdrawals",
+        6 => "update",
+        7 => "auxiliary_data_hash",
+        8 => "validity_interval_start",
+        9 => "mint",
+        11 => "script_data_hash",
+        13 => "collateral",
+        14 => "required_signers",
+        15 => "network_id",
+        16 => "collateral_return",
+        17 => "total_collateral",
+        18 => "reference_inputs",
+        19 => "voting_procedures",
+        20 => "proposal_procedures",
+        21 => "treasury_value",
+        22 => "donation",
+        _ => return None,
+    })
+}
+
+fn witness_field(key: u64) -> Option<&'static str> {
+    Some(match key {
+        0 => "vkeywitness",
+        1 => "native_script",
+        2 => "bootstrap_witness",
+        3 => "plutus_v1_script",
+        4 => "plutus_data",
+        5 => "redeemer",
+        6 => "plutus_v2_script",
+        7 => "plutus_v3_script",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cbor_explorer::decode_sequence;
+
+    use super::*;
+
+    /// Differences between two txs made of `body` and `witnesses`, as paths,
+    /// descriptions and whether they're in the body.
+    fn diff(left: (&str, &str), right: (&str, &str)) -> Vec<(String, String, bool)> {
+        let tx = |(body, witnesses)| hex::decode(format!("84{body}{witnesses}f5f6")).unwrap();
+        let (left, right) = (tx(left), tx(right));
+        let item = |bytes: &[u8]| decode_sequence(bytes).unwrap().remove(0);
+
+        differences((&left, &item(&left)), (&right, &item(&right)))
+            .into_iter()
+            .map(|d| {
+                let in_body = d.in_body();
+
+                (d.path, d.description, in_body)
+            })
+            .collect()
+    }
+
+    fn found(path: &str, description: &str, in_body: bool) -> (String, String, bool) {
+        (path.to_string(), description.to_string(), in_body)
+    }
+
+    #[test]
+    fn equal_txs_have_no_differences() {
+        assert_eq!(diff(("a10201", "a0"), ("a10201", "a0")), []);
+    }
+
+    #[test]
+    fn values_are_compared_by_field() {
+        // A fee of 1 vs 2
+        assert_eq!(
+            diff(("a10201", "a0"), ("a10202", "a0")),
+            [found("body.fee", "1 vs 2", true)]
+        );
+        // A ttl on the left only
+        assert_eq!(
+            diff(("a202010305", "a0"), ("a10201", "a0")),
+            [found("body.ttl", "only on the left", true)]
+        );
+    }
+
+    #[test]
+    fn equal_values_encoded_differently_are_found() {
+        // A fee of 1000 in two bytes vs four
+        assert_eq!(
+            diff(("a1021903e8", "a0"), ("a1021a000003e8", "a0")),
+            [found(
+                "body.fee",
+                "same value, but the head is 1903e8 vs 1a000003e8",
+                true
+            )]
+        );
+        // Inputs as a tagged set vs a plain array
+        assert_eq!(
+            diff(("a100d9010280", "a0"), ("a10080", "a0")),
+            [found("body.inputs", "tag 258 on the left only", true)]
+        );
+        // The same body keys in another order
+        assert_eq!(
+            diff(("a200800201", "a0"), ("a202010080", "a0")),
+            [found("body", "same keys in a different order", true)]
+        );
+    }
+
+    #[test]
+    fn witness_differences_leave_the_hash_alone() {
+        // No vkey witnesses, as an indefinite vs a definite array
+        assert_eq!(
+            diff(("a0", "a1009fff"), ("a0", "a10080")),
+            [found(
+                "witnesses.vkeywitness",
+                "indefinite vs definite length",
+                false
+            )]
+        );
+    }
+
+    #[test]
+    fn the_body_is_the_first_item() {
+        let tx = hex::decode("84a10201a0f5f6").unwrap();
+        let item = decode_sequence(&tx).unwrap().remove(0);
+
+        assert_eq!(body(&tx, &item), Some(&tx[1..4]));
+    }
+}
@@ -0,0 +1,140 @@
+use pallas::{
+    crypto::hash::{Hash, Hasher},
+    ledger::traverse::Era,
+};
+
+use crate::{
+    block_inspector::{MultiEraTx, Utxos},
+    cbor_explorer::decode_sequence,
+    tree::Node,
+};
+
+use super::bytes::{self, Difference};
+
+#[derive(Debug, Clone)]
+pub struct Side {
+    pub era: Era,
+    pub size: usize,
+    pub hash: Hash<32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diff {
+    pub left: Side,
+    pub right: Side,
+    /// The fields that differ, or nothing when the txs are equal field by
+    /// field
+    pub structure: Option<Node>,
+    pub bytes: Vec<Difference>,
+}
+
+impl Diff {
+    pub fn new(left: &[u8], right: &[u8]) -> Result<Self, String> {
+        let (left_tx, left_item) = decode(left).map_err(|e| format!("Left: {e}"))?;
+        let (right_tx, right_item) = decode(right).map_err(|e| format!("Right: {e}"))?;
+
+        let side = |tx: &MultiEraTx, cbor: &[u8], item| {
+            let body = bytes::body(cbor, item).ok_or("the tx has no body")?;
+
+            Ok::<_, String>(Side {
+                era: tx.era(),
+                size: cbor.len(),
+                hash: Hasher::<256>::hash(body),
+            })
+        };
+
+        let utxos = Utxos::default();
+
+        Ok(Self {
+            left: side(&left_tx, left, &left_item).map_err(|e| format!("Left: {e}"))?,
+            right: side(&right_tx, right, &right_item).map_err(|e| format!("Right: {e}"))?,
+            structure: compare(&left_tx.tree(&utxos, None), &right_tx.tree(&utxos, None)),
+            bytes: if left == right {
+                vec![]
+            } else {
+                bytes::differences((left, &left_item), (right, &right_item))
+            },
+        })
+    }
+}
+
+fn decode(cbor: &[u8]) -> Result<(MultiEraTx, crate::cbor_explorer::Item), String> {
+    let tx = MultiEraTx::decode(cbor).map_err(|_| "not a tx of any era".to_string())?;
+
+    // Anything the tx decoders accept is a single well formed item
+    let item = decode_sequence(cbor)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .next()
+        .ok_or("empty")?;
+
+    Ok((tx, item))
+}
+
+/// The parts of two trees that differ: changed values read `left → right`,
+/// and nodes only on one side are prefixed with `-` or `+`.
+fn compare(left: &Node, right: &Node) -> Option<Node> {
+    if left == right {
+        return None;
+    }
+
+    let value = if left.value == right.value {
+        left.value.clone()
+    } else {
+        Some(format!(
+            "{} → {}",
+            left.value.as_deref().unwrap_or("-"),
+            right.value.as_deref().unwrap_or("-")
+        ))
+    };
+
+    let mut matched = vec![false; right.children.len()];
+    let mut children = vec![];
+
+    // Children are matched by label, and by how many times the label came
+    // up before for the rare nodes that repeat one
+    for (i, child) in left.children.iter().enumerate() {
+        let nth = occurrence(&left.children, i);
+
+        let other = right.children.iter().enumerate().find(|(j, other)| {
+            other.label == child.label && occurrence(&right.children, *j) == nth
+        });
+
+        match other {
+            Some((j, other)) => {
+                matched[j] = true;
+                children.extend(compare(child, other));
+            }
+            None => children.push(marked("-", child)),
+        }
+    }
+
+    children.extend(
+        right
+            .children
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(child, _)| marked("+", child)),
+    );
+
+    Some(Node {
+        label: left.label.clone(),
+        value,
+        children,
+    })
+}
+
+fn occurrence(nodes: &[Node], index: usize) -> usize {
+    nodes[..index]
+        .iter()
+        .filter(|node| node.label == nodes[index].label)
+        .count()
+}
+
+fn marked(mark: &str, node: &Node) -> Node {
+    Node {
+        label: format!("{mark} {}", node.label),
+        ..node.clone()
+    }
+}
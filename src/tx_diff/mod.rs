@@ -0,0 +1,9 @@
+mod bytes;
+mod diff;
+mod state;
+mod update;
+mod view;
+
+pub use diff::*;
+pub use state::*;
+pub use update::*;
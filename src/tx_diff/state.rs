@@ -0,0 +1,15 @@
+use std::collections::HashSet;
+
+use iced::widget::text_editor;
+
+use crate::tree;
+
+use super::Diff;
+
+#[derive(Debug, Default)]
+pub struct State {
+    pub left: text_editor::Content,
+    pub right: text_editor::Content,
+    pub diff: Option<Result<Diff, String>>,
+    pub collapsed: HashSet<tree::Path>,
+}
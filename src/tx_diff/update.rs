@@ -0,0 +1,61 @@
+use iced::{widget::text_editor, Task};
+
+use crate::tree;
+
+use super::{Diff, State};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    LeftChanged(text_editor::Action),
+    RightChanged(text_editor::Action),
+    Swap,
+    ToggleNode(tree::Path),
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            LeftChanged(action) => {
+                self.left.perform(action);
+                self.compare();
+            }
+            RightChanged(action) => {
+                self.right.perform(action);
+                self.compare();
+            }
+            Swap => {
+                std::mem::swap(&mut self.left, &mut self.right);
+                self.compare();
+            }
+            ToggleNode(path) => {
+                if !self.collapsed.remove(&path) {
+                    self.collapsed.insert(path);
+                }
+            }
+        }
+
+        Task::none()
+    }
+
+    fn compare(&mut self) {
+        self.diff = None;
+        self.collapsed.clear();
+
+        let (left, right) = (self.left.text(), self.right.text());
+
+        if left.trim().is_empty() || right.trim().is_empty() {
+            return;
+        }
+
+        let decode = |cbor: &str, side: &str| {
+            hex::decode(cbor.trim()).map_err(|e| format!("{side}: invalid hex: {e}"))
+        };
+
+        self.diff = Some(
+            decode(&left, "Left")
+                .and_then(|left| Ok((left, decode(&right, "Right")?)))
+                .and_then(|(left, right)| Diff::new(&left, &right)),
+        );
+    }
+}
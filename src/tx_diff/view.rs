@@ -0,0 +1,151 @@
+use iced::{
+    padding,
+    widget::{button, column, container, row, scrollable, text, text_editor, Column},
+    Border, Color, Element, Font,
+    Length::{Fill, FillPortion},
+    Theme,
+};
+use iced_font_awesome::fa_icon_solid;
+
+use super::{Diff, Message, State};
+use crate::tree::tree;
+
+const RED: Color = Color::from_rgb(1.0, 0.0, 0.0);
+const GREEN: Color = Color::from_rgb(0.0, 0.7, 0.0);
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        let editor = |content, placeholder, on_action: fn(text_editor::Action) -> Message| {
+            text_editor(content)
+                .placeholder(placeholder)
+                .on_action(on_action)
+                .height(Fill)
+                .wrapping(text::Wrapping::Glyph)
+                .style(|theme: &Theme, status| {
+                    let mut defaults = text_editor::default(theme, status);
+
+                    defaults.border.radius = 4.0.into();
+
+                    defaults
+                })
+        };
+
+        row![
+            column![
+                editor(&self.left, "left tx cbor...", Message::LeftChanged),
+                button(row![fa_icon_solid("right-left").size(14.), text("Swap")].spacing(5))
+                    .on_press(Message::Swap)
+                    .style(button::secondary),
+                editor(&self.right, "right tx cbor...", Message::RightChanged),
+            ]
+            .spacing(5)
+            .width(FillPortion(1))
+            .padding(5),
+            container(scrollable(self.view_diff().width(Fill)).width(Fill))
+                .width(FillPortion(2))
+                .height(Fill)
+                .style(|theme: &Theme| {
+                    let palette = theme.extended_palette();
+
+                    container::Style {
+                        border: Border {
+                            width: 1.0,
+                            radius: 4.0.into(),
+                            color: palette.background.strong.color,
+                        },
+                        ..Default::default()
+                    }
+                })
+                .padding(10)
+        ]
+        .spacing(5)
+        .padding(padding::top(20).left(5).right(10).bottom(10))
+        .into()
+    }
+
+    fn view_diff(&self) -> Column<'_, Message> {
+        let diff = match &self.diff {
+            None => return column![text("paste a tx on each side")],
+            Some(Err(e)) => return column![text(e).color(RED)],
+            Some(Ok(diff)) => diff,
+        };
+
+        let field = |label, left: String, right: String| {
+            row![
+                text(label).width(160),
+                text(left).font(Font::MONOSPACE).size(14).width(Fill),
+                text(right).font(Font::MONOSPACE).size(14).width(Fill),
+            ]
+            .spacing(10)
+        };
+
+        let same_hash = diff.left.hash == diff.right.hash;
+
+        column![
+            field("", "left".to_string(), "right".to_string()),
+            field(
+                "era",
+                format!("{:?}", diff.left.era),
+                format!("{:?}", diff.right.era)
+            ),
+            field(
+                "size",
+                format!("{} bytes", diff.left.size),
+                format!("{} bytes", diff.right.size)
+            ),
+            field(
+                "tx hash",
+                diff.left.hash.to_string(),
+                diff.right.hash.to_string()
+            ),
+            if same_hash {
+                text("the tx hashes match").color(GREEN)
+            } else if diff.structure.is_none() {
+                text("the txs are equal field by field, but their bodies are encoded differently")
+                    .color(RED)
+            } else {
+                text("the tx hashes differ").color(RED)
+            },
+            text("fields").size(18),
+            match &diff.structure {
+                Some(structure) => tree(structure, &self.collapsed, Message::ToggleNode),
+                None => text("no differences").into(),
+            },
+            text("encoding").size(18),
+            view_bytes(diff),
+        ]
+        .spacing(10)
+    }
+}
+
+fn view_bytes(diff: &Diff) -> Element<'_, Message> {
+    if diff.bytes.is_empty() {
+        return text("byte for byte identical").into();
+    }
+
+    column(diff.bytes.iter().map(|difference| {
+        row![
+            text(&difference.path)
+                .font(Font::MONOSPACE)
+                .size(14)
+                .width(FillPortion(2)),
+            text(&difference.description)
+                .font(Font::MONOSPACE)
+                .size(14)
+                .width(FillPortion(3)),
+            text(format!("@{} / @{}", difference.left, difference.right))
+                .font(Font::MONOSPACE)
+                .size(14)
+                .width(FillPortion(1)),
+        ]
+        .push_maybe(
+            difference
+                .in_body()
+                .then(|| text("changes the hash").size(14).color(RED)),
+        )
+        .spacing(10)
+        .into()
+    }))
+    .spacing(4)
+    .into()
+}
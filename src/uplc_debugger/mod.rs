@@ -0,0 +1,8 @@
+mod script;
+mod state;
+mod update;
+mod view;
+
+pub use script::{hashes, unwrap_flat, Version};
+pub use state::*;
+pub use update::*;
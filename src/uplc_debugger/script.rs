@@ -0,0 +1,386 @@
+use std::{
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+};
+
+use pallas::{
+    codec::{minicbor, utils::Bytes},
+    crypto::hash::{Hash, Hasher},
+    ledger::{
+        primitives::{conway, PlutusData},
+        traverse::MultiEraTx,
+    },
+};
+use serde_json::Value;
+use uplc::{
+    ast::{DeBruijn, Name, NamedDeBruijn, Program, Term},
+    machine::cost_model::ExBudget,
+};
+
+use crate::plutus_data;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Version {
+    V1,
+    V2,
+    #[default]
+    V3,
+}
+
+impl Version {
+    pub const ALL: [Version; 3] = [Version::V1, Version::V2, Version::V3];
+
+    /// The tag prepended to the script before hashing it
+    fn tag(&self) -> u8 {
+        match self {
+            Version::V1 => 1,
+            Version::V2 => 2,
+            Version::V3 => 3,
+        }
+    }
+
+    fn language(&self) -> conway::Language {
+        match self {
+            Version::V1 => conway::Language::PlutusV1,
+            Version::V2 => conway::Language::PlutusV2,
+            Version::V3 => conway::Language::PlutusV3,
+        }
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Plutus V{}", self.tag())
+    }
+}
+
+/// A script found in a pasted tx, kept as the bytes the ledger stores.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    pub label: String,
+    pub version: Version,
+    pub cbor: Vec<u8>,
+}
+
+impl Display for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.label, self.version)
+    }
+}
+
+/// Scripts in a tx's witness set and the reference scripts of its outputs.
+pub fn find_scripts(cbor: &[u8]) -> Vec<Script> {
+    let Ok(tx) = MultiEraTx::decode(cbor) else {
+        return vec![];
+    };
+
+    let witness = |version, i, script: &[u8]| Script {
+        label: format!("witness #{i}"),
+        version,
+        cbor: script.to_vec(),
+    };
+
+    let mut scripts: Vec<Script> = tx
+        .plutus_v1_scripts()
+        .iter()
+        .enumerate()
+        .map(|(i, script)| witness(Version::V1, i, &script.0))
+        .chain(
+            tx.plutus_v2_scripts()
+                .iter()
+                .enumerate()
+                .map(|(i, script)| witness(Version::V2, i, &script.0)),
+        )
+        .chain(
+            tx.plutus_v3_scripts()
+                .iter()
+                .enumerate()
+                .map(|(i, script)| witness(Version::V3, i, &script.0)),
+        )
+        .collect();
+
+    for (i, output) in tx.outputs().iter().enumerate() {
+        let (version, script) = match output.script_ref() {
+            Some(conway::PseudoScript::PlutusV1Script(script)) => (Version::V1, script.0),
+            Some(conway::PseudoScript::PlutusV2Script(script)) => (Version::V2, script.0),
+            Some(conway::PseudoScript::PlutusV3Script(script)) => (Version::V3, script.0),
+            _ => continue,
+        };
+
+        scripts.push(Script {
+            label: format!("output #{i} reference script"),
+            version,
+            cbor: script.to_vec(),
+        });
+    }
+
+    scripts
+}
+
+/// Strip the CBOR bytestrings scripts tend to be wrapped in: once in the
+/// ledger, twice in cardano-cli's text envelopes. Returns the flat bytes and
+/// how many layers were removed.
+pub fn unwrap_flat(bytes: &[u8]) -> Result<(Vec<u8>, usize), String> {
+    let mut flat = bytes.to_vec();
+    let mut layers = 0;
+
+    // The flat decoder is happy to read a CBOR header as part of a program,
+    // so only try it once there's no bytestring left spanning the input
+    while layers < 2 {
+        let mut decoder = minicbor::Decoder::new(&flat);
+
+        match decoder.decode::<Bytes>() {
+            Ok(inner) if decoder.position() == flat.len() => {
+                flat = inner.to_vec();
+                layers += 1;
+            }
+            _ => break,
+        }
+    }
+
+    Program::<DeBruijn>::from_flat(&flat)
+        .map(|_| (flat, layers))
+        .map_err(|e| format!("Not a flat encoded program: {e}"))
+}
+
+/// The script hash under each plutus version, over the script as the
+/// ledger stores it: the flat bytes wrapped in a CBOR bytestring.
+pub fn hashes(flat: &[u8]) -> Vec<(Version, Hash<28>)> {
+    let cbor = minicbor::to_vec(Bytes::from(flat.to_vec())).unwrap_or_default();
+
+    Version::ALL
+        .iter()
+        .map(|version| {
+            let mut tagged = vec![version.tag()];
+
+            tagged.extend_from_slice(&cbor);
+
+            (*version, Hasher::<224>::hash(&tagged))
+        })
+        .collect()
+}
+
+pub fn pretty(flat: &[u8]) -> Result<String, String> {
+    let program = Program::<DeBruijn>::from_flat(flat).map_err(|e| e.to_string())?;
+    let program: Program<Name> = program.try_into().map_err(|e| format!("{e}"))?;
+
+    Ok(program.to_pretty())
+}
+
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    pub result: Result<String, String>,
+    pub mem: i64,
+    pub cpu: i64,
+    pub logs: Vec<String>,
+}
+
+enum Argument {
+    Data(PlutusData),
+    Term(Term<Name>),
+}
+
+/// Apply `arguments` to the program and run it within `budget`. Programs
+/// hold `Rc`s, so everything is decoded here rather than passed in.
+pub async fn evaluate(
+    flat: Vec<u8>,
+    arguments: String,
+    version: Version,
+    budget: ExBudget,
+) -> Result<Evaluation, String> {
+    let program = Program::<DeBruijn>::from_flat(&flat).map_err(|e| e.to_string())?;
+    let mut program: Program<Name> = program.try_into().map_err(|e| format!("{e}"))?;
+
+    for argument in parse_arguments(&arguments)? {
+        program = match argument {
+            Argument::Data(data) => program.apply_data(data),
+            Argument::Term(term) => program.apply_term(&term),
+        };
+    }
+
+    let program: Program<NamedDeBruijn> = program.try_into().map_err(|e| format!("{e}"))?;
+
+    // Some malformed programs make the machine panic rather than fail
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        program.eval_version(budget, &version.language())
+    }))
+    .map_err(|_| "the machine crashed on this program".to_string())?;
+    let cost = result.cost();
+    let logs = result.logs();
+
+    Ok(Evaluation {
+        result: result
+            .result
+            .map(|term| term.to_pretty())
+            .map_err(|e| e.to_string()),
+        mem: cost.mem,
+        cpu: cost.cpu,
+        logs,
+    })
+}
+
+/// Arguments are separated by whitespace, each one either plutus data, as
+/// CBOR hex or detailed schema JSON, or a UPLC term in parentheses or
+/// brackets.
+fn parse_arguments(input: &str) -> Result<Vec<Argument>, String> {
+    let mut arguments = vec![];
+    let mut rest = input.trim_start();
+
+    while !rest.is_empty() {
+        let i = arguments.len();
+
+        let (argument, end) = match rest.as_bytes()[0] {
+            b'{' => {
+                let mut stream = serde_json::Deserializer::from_str(rest).into_iter::<Value>();
+
+                let json = stream
+                    .next()
+                    .unwrap_or(Ok(Value::Null))
+                    .map_err(|e| format!("argument #{i}: invalid JSON: {e}"))?;
+
+                let data =
+                    plutus_data::from_json(&json).map_err(|e| format!("argument #{i}: {e}"))?;
+
+                (Argument::Data(data), stream.byte_offset())
+            }
+            b'(' | b'[' => {
+                let end = term_end(rest).ok_or(format!("argument #{i}: unbalanced brackets"))?;
+
+                let term = uplc::parser::term(&rest[..end])
+                    .map_err(|e| format!("argument #{i}: invalid term: {e}"))?;
+
+                (Argument::Term(term), end)
+            }
+            _ => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+
+                let cbor = hex::decode(&rest[..end])
+                    .map_err(|e| format!("argument #{i}: invalid hex: {e}"))?;
+
+                let data = minicbor::decode(&cbor)
+                    .map_err(|e| format!("argument #{i}: invalid plutus data: {e}"))?;
+
+                (Argument::Data(data), end)
+            }
+        };
+
+        arguments.push(argument);
+        rest = rest[end..].trim_start();
+    }
+
+    Ok(arguments)
+}
+
+/// Where the bracketed term at the start of `input` ends, skipping over
+/// string constants.
+fn term_end(input: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '(' | '[' if !in_string => depth += 1,
+            ')' | ']' if !in_string => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas::ledger::{primitives::PlutusScript, traverse::ComputeHash};
+
+    use super::*;
+
+    /// A program taking one argument and returning it as is.
+    fn identity() -> Vec<u8> {
+        uplc::parser::program("(program 1.1.0 (lam x x))")
+            .unwrap()
+            .to_debruijn()
+            .unwrap()
+            .to_flat()
+            .unwrap()
+    }
+
+    fn wrapped(bytes: &[u8]) -> Vec<u8> {
+        minicbor::to_vec(Bytes::from(bytes.to_vec())).unwrap()
+    }
+
+    #[test]
+    fn scripts_are_unwrapped_from_up_to_two_bytestrings() {
+        let flat = identity();
+
+        assert_eq!(unwrap_flat(&flat), Ok((flat.clone(), 0)));
+        assert_eq!(unwrap_flat(&wrapped(&flat)), Ok((flat.clone(), 1)));
+        assert_eq!(unwrap_flat(&wrapped(&wrapped(&flat))), Ok((flat, 2)));
+        assert!(unwrap_flat(&wrapped(&[0xff, 0xff])).is_err());
+    }
+
+    #[test]
+    fn hashes_are_tagged_with_the_plutus_version() {
+        let flat = identity();
+        let cbor = Bytes::from(wrapped(&flat));
+
+        assert_eq!(
+            hashes(&flat),
+            [
+                (Version::V1, PlutusScript::<1>(cbor.clone()).compute_hash()),
+                (Version::V2, PlutusScript::<2>(cbor.clone()).compute_hash()),
+                (Version::V3, PlutusScript::<3>(cbor).compute_hash()),
+            ]
+        );
+    }
+
+    #[test]
+    fn term_ends_skip_brackets_in_strings() {
+        assert_eq!(term_end("(con integer 1) rest"), Some(15));
+        assert_eq!(term_end(r#"(con string "a)\"(") rest"#), Some(20));
+        assert_eq!(term_end("[(lam x x) (con unit ())]"), Some(25));
+        assert_eq!(term_end("(con integer 1"), None);
+    }
+
+    #[test]
+    fn arguments_can_be_mixed() {
+        let arguments =
+            parse_arguments(r#" 182a {"int": 42} (con integer 42) [(lam x x) (con unit ())]"#)
+                .unwrap();
+
+        assert_eq!(arguments.len(), 4);
+        assert!(matches!(arguments[0], Argument::Data(_)));
+        assert!(matches!(arguments[1], Argument::Data(_)));
+        assert!(matches!(arguments[2], Argument::Term(_)));
+        assert!(matches!(arguments[3], Argument::Term(_)));
+
+        let error = |input| parse_arguments(input).err().unwrap();
+
+        assert!(error("00 zz").starts_with("argument #1: invalid hex"));
+        assert!(error("(con integer").starts_with("argument #0: unbalanced brackets"));
+    }
+
+    #[tokio::test]
+    async fn arguments_are_applied_before_evaluating() {
+        let evaluation = evaluate(
+            identity(),
+            "(con integer 42)".to_string(),
+            Version::V3,
+            ExBudget::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(evaluation.result, Ok("(con integer 42)".to_string()));
+        assert!(evaluation.cpu > 0);
+    }
+}
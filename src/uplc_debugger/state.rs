@@ -0,0 +1,46 @@
+use iced::widget::text_editor;
+use pallas::crypto::hash::Hash;
+
+use super::script::{Evaluation, Script, Version};
+
+#[derive(Debug)]
+pub struct State {
+    pub input: text_editor::Content,
+    /// Scripts found when the input is a whole tx
+    pub scripts: Vec<Script>,
+    pub selected: Option<Script>,
+    pub flat: Vec<u8>,
+    /// How many CBOR bytestrings the flat program was wrapped in
+    pub layers: usize,
+    pub pretty: String,
+    pub hashes: Vec<(Version, Hash<28>)>,
+    pub version: Version,
+    pub arguments: text_editor::Content,
+    pub mem: String,
+    pub cpu: String,
+    pub evaluating: bool,
+    pub evaluation: Option<Result<Evaluation, String>>,
+    pub warning: Option<String>,
+}
+
+/// Budgets start at the per-tx limits on mainnet
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            input: Default::default(),
+            scripts: vec![],
+            selected: None,
+            flat: vec![],
+            layers: 0,
+            pretty: String::new(),
+            hashes: vec![],
+            version: Version::default(),
+            arguments: Default::default(),
+            mem: "14000000".to_string(),
+            cpu: "10000000000".to_string(),
+            evaluating: false,
+            evaluation: None,
+            warning: None,
+        }
+    }
+}
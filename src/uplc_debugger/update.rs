@@ -0,0 +1,141 @@
+use iced::{clipboard, widget::text_editor, Task};
+use uplc::machine::cost_model::ExBudget;
+
+use crate::encoding::likely_encoding;
+
+use super::{
+    script::{evaluate, find_scripts, hashes, pretty, unwrap_flat, Evaluation, Script, Version},
+    State,
+};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    InputChanged(text_editor::Action),
+    ScriptSelected(Script),
+    VersionSet(Version),
+    ArgumentsChanged(text_editor::Action),
+    MemChanged(String),
+    CpuChanged(String),
+    Evaluate,
+    Evaluated(Result<Evaluation, String>),
+    Copy(String),
+}
+
+impl State {
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        use Message::*;
+        match message {
+            InputChanged(action) => {
+                self.input.perform(action);
+                self.decode_input();
+                Task::none()
+            }
+            ScriptSelected(script) => {
+                self.version = script.version;
+                self.load(&script.cbor);
+                self.selected = Some(script);
+                Task::none()
+            }
+            VersionSet(version) => {
+                self.version = version;
+                Task::none()
+            }
+            ArgumentsChanged(action) => {
+                self.arguments.perform(action);
+                Task::none()
+            }
+            MemChanged(mem) => {
+                self.mem = mem;
+                Task::none()
+            }
+            CpuChanged(cpu) => {
+                self.cpu = cpu;
+                Task::none()
+            }
+            Evaluate => {
+                let (Ok(mem), Ok(cpu)) = (self.mem.trim().parse(), self.cpu.trim().parse()) else {
+                    self.evaluation = Some(Err("The budget must be whole numbers".to_string()));
+                    return Task::none();
+                };
+
+                self.evaluating = true;
+
+                Task::perform(
+                    evaluate(
+                        self.flat.clone(),
+                        self.arguments.text(),
+                        self.version,
+                        ExBudget { mem, cpu },
+                    ),
+                    Evaluated,
+                )
+            }
+            Evaluated(evaluation) => {
+                self.evaluating = false;
+                self.evaluation = Some(evaluation);
+                Task::none()
+            }
+            Copy(s) => clipboard::write(s),
+        }
+    }
+
+    fn decode_input(&mut self) {
+        self.scripts.clear();
+        self.selected = None;
+        self.clear();
+
+        let input = self.input.text();
+        let input = input.trim();
+
+        if input.is_empty() {
+            return;
+        }
+
+        let bytes = match likely_encoding(input).decode(input) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.warning = Some(e);
+                return;
+            }
+        };
+
+        self.scripts = find_scripts(&bytes);
+
+        match self.scripts.first().cloned() {
+            Some(script) => {
+                let _ = self.update(Message::ScriptSelected(script));
+            }
+            None => self.load(&bytes),
+        }
+    }
+
+    fn load(&mut self, bytes: &[u8]) {
+        self.clear();
+
+        let (flat, layers) = match unwrap_flat(bytes) {
+            Ok(unwrapped) => unwrapped,
+            Err(e) => {
+                self.warning = Some(e);
+                return;
+            }
+        };
+
+        match pretty(&flat) {
+            Ok(pretty) => self.pretty = pretty,
+            Err(e) => self.warning = Some(e),
+        }
+
+        self.hashes = hashes(&flat);
+        self.flat = flat;
+        self.layers = layers;
+    }
+
+    fn clear(&mut self) {
+        self.flat.clear();
+        self.layers = 0;
+        self.pretty.clear();
+        self.hashes.clear();
+        self.evaluation = None;
+        self.warning = None;
+    }
+}
@@ -0,0 +1,160 @@
+use iced::{
+    padding,
+    widget::{
+        button, column, container, pick_list, row, scrollable, text, text_editor, text_input,
+        Column,
+    },
+    Border, Element, Font,
+    Length::{Fill, FillPortion},
+    Theme,
+};
+
+use super::{script::Version, Message, State};
+use crate::copyable_text::copyable_text;
+
+const RED: [f32; 3] = [1.0, 0.0, 0.0];
+
+impl State {
+    pub fn view(&self) -> Element<'_, Message> {
+        let editor = |content, placeholder, on_action: fn(text_editor::Action) -> Message| {
+            text_editor(content)
+                .placeholder(placeholder)
+                .on_action(on_action)
+                .height(Fill)
+                .wrapping(text::Wrapping::Glyph)
+                .style(|theme: &Theme, status| {
+                    let mut defaults = text_editor::default(theme, status);
+
+                    defaults.border.radius = 4.0.into();
+
+                    defaults
+                })
+        };
+
+        row![
+            column![editor(
+                &self.input,
+                "script cbor, flat, or a tx with scripts...",
+                Message::InputChanged
+            )]
+            .push_maybe((!self.scripts.is_empty()).then(|| {
+                pick_list(
+                    &self.scripts[..],
+                    self.selected.clone(),
+                    Message::ScriptSelected,
+                )
+                .width(Fill)
+            }))
+            .push(editor(
+                &self.arguments,
+                "arguments: plutus data as cbor hex or detailed schema json, \
+                 or terms like (con integer 42)",
+                Message::ArgumentsChanged
+            ))
+            .push(
+                row![
+                    pick_list(&Version::ALL[..], Some(self.version), Message::VersionSet),
+                    text_input("mem", &self.mem).on_input(Message::MemChanged),
+                    text_input("cpu", &self.cpu).on_input(Message::CpuChanged),
+                    button(text(if self.evaluating {
+                        "Evaluating..."
+                    } else {
+                        "Evaluate"
+                    }))
+                    .on_press_maybe(
+                        (!self.flat.is_empty() && !self.evaluating).then_some(Message::Evaluate)
+                    )
+                ]
+                .spacing(5)
+            )
+            .push_maybe(
+                self.warning
+                    .as_ref()
+                    .map(|warning| text(warning).color(RED))
+            )
+            .spacing(5)
+            .width(FillPortion(1))
+            .padding(5),
+            container(scrollable(self.view_program().width(Fill)).width(Fill))
+                .width(FillPortion(2))
+                .height(Fill)
+                .style(|theme: &Theme| {
+                    let palette = theme.extended_palette();
+
+                    container::Style {
+                        border: Border {
+                            width: 1.0,
+                            radius: 4.0.into(),
+                            color: palette.background.strong.color,
+                        },
+                        ..Default::default()
+                    }
+                })
+                .padding(10)
+        ]
+        .spacing(5)
+        .padding(padding::top(20).left(5).right(10).bottom(10))
+        .into()
+    }
+
+    fn view_program(&self) -> Column<'_, Message> {
+        if self.flat.is_empty() {
+            return column![text("nothing to decode")];
+        }
+
+        let wrapping = match self.layers {
+            0 => "flat".to_string(),
+            1 => "flat in a CBOR bytestring".to_string(),
+            n => format!("flat in {n} nested CBOR bytestrings"),
+        };
+
+        column![
+            column(self.hashes.iter().map(|(version, hash)| {
+                row![
+                    text(format!("{version} hash")).width(160),
+                    copyable_text(&hash.to_string(), Message::Copy)
+                ]
+                .into()
+            }))
+            .spacing(5),
+            text(format!("{} bytes, {wrapping}", self.flat.len())),
+        ]
+        .push_maybe(self.evaluation.as_ref().map(|evaluation| {
+            match evaluation {
+                Ok(evaluation) => column![
+                    match &evaluation.result {
+                        Ok(term) => text(format!("result: {term}")).font(Font::MONOSPACE),
+                        Err(e) => text(format!("failed: {e}")).color(RED),
+                    },
+                    text(format!(
+                        "budget: {} mem, {} cpu",
+                        evaluation.mem, evaluation.cpu
+                    )),
+                ]
+                .push_maybe((!evaluation.logs.is_empty()).then(|| {
+                    column![text("traces")]
+                        .extend(
+                            evaluation
+                                .logs
+                                .iter()
+                                .map(|log| text(log).font(Font::MONOSPACE).size(14).into()),
+                        )
+                        .spacing(2)
+                }))
+                .spacing(5),
+                Err(e) => column![text(e).color(RED)],
+            }
+        }))
+        .push(row![
+            text(&self.pretty)
+                .font(Font::MONOSPACE)
+                .size(14)
+                .width(Fill),
+            button(iced_font_awesome::fa_icon_solid("clone").size(14.))
+                .on_press(Message::Copy(self.pretty.clone()))
+                .style(button::text)
+                .padding(5)
+        ])
+        .spacing(10)
+    }
+}
@@ -3,7 +3,7 @@ use iced::Element;
 use super::{Message, State};
 
 impl State {
-    pub fn view(&self) -> Element<Message> {
+    pub fn view(&self) -> Element<'_, Message> {
         self.workspace.view().map(Message::Workspace)
     }
 }